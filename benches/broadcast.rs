@@ -0,0 +1,84 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fuiz::{
+    game::{self, LeaderboardMessage},
+    session::{CloseReason, Tunnel},
+    watcher::{Id, Limits, PlayerValue, Value, Watchers},
+    TruncatedVec, UpdateMessage,
+};
+
+#[derive(Clone, Default)]
+struct CountingTunnel(Arc<AtomicUsize>);
+
+impl Tunnel for CountingTunnel {
+    fn send_raw(&self, message: Arc<str>) {
+        self.0.fetch_add(message.len(), Ordering::Relaxed);
+    }
+
+    fn close(self, _reason: CloseReason) {}
+}
+
+fn watchers(players: usize) -> (Watchers, HashSet<Id>) {
+    let host_id = Id::new();
+    let mut watchers = Watchers::with_host_id(host_id, Limits::default());
+    let mut connected = HashSet::from([host_id]);
+
+    for i in 0..players {
+        let id = Id::new();
+        watchers
+            .add_watcher(
+                id,
+                Value::Player(PlayerValue::Individual {
+                    name: format!("Player {i}"),
+                }),
+            )
+            .expect("bench stays under the player limit");
+        connected.insert(id);
+    }
+
+    (watchers, connected)
+}
+
+fn announce(c: &mut Criterion) {
+    let mut group = c.benchmark_group("announce");
+
+    for players in [100, 500, 1000] {
+        let (watchers, connected) = watchers(players);
+        let tunnel = CountingTunnel::default();
+        let tunnel_finder = |id: Id| connected.contains(&id).then(|| tunnel.clone());
+
+        let message: UpdateMessage = game::UpdateMessage::Leaderboard {
+            leaderboard: LeaderboardMessage {
+                current: TruncatedVec::default(),
+                prior: TruncatedVec::default(),
+                members: None,
+                round: None,
+            },
+        }
+        .into();
+
+        group.bench_with_input(BenchmarkId::new("shared", players), &players, |b, _| {
+            b.iter(|| watchers.announce(&message, tunnel_finder));
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("per_recipient", players),
+            &players,
+            |b, _| {
+                b.iter(|| watchers.announce_with(|_, _| Some(message.clone()), tunnel_finder));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, announce);
+criterion_main!(benches);
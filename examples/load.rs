@@ -0,0 +1,189 @@
+//! Load generator driving full games through in-process tunnels.
+//!
+//! Usage: `cargo run --release --example load -- [players] [slides] [answer threads]`
+//!
+//! Reports how long host broadcasts take and how long answering threads wait for the game lock.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{Game, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage, Options},
+    session::{CloseReason, Outbox, Tunnel},
+    watcher::Id,
+    AlarmMessage,
+};
+
+#[derive(Clone, Default)]
+struct LoadTunnel {
+    messages: Arc<AtomicUsize>,
+    bytes: Arc<AtomicUsize>,
+}
+
+impl Tunnel for LoadTunnel {
+    fn send_raw(&self, message: Arc<str>) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(message.len(), Ordering::Relaxed);
+    }
+
+    fn close(self, _reason: CloseReason) {}
+}
+
+fn quiz(slides: usize) -> Fuiz {
+    let slide = serde_json::json!({
+        "MultipleChoice": {
+            "title": "How many legs does a spider have?",
+            "introduce_question": 0,
+            "time_limit": 30000,
+            "points_awarded": 1000,
+            "answers": [
+                { "correct": false, "content": { "Text": "Six" } },
+                { "correct": true, "content": { "Text": "Eight" } },
+                { "correct": false, "content": { "Text": "Ten" } },
+                { "correct": false, "content": { "Text": "Twelve" } },
+            ],
+        }
+    });
+
+    serde_json::from_value(serde_json::json!({
+        "title": "Load",
+        "slides": vec![slide; slides],
+    }))
+    .expect("static quiz is valid")
+}
+
+fn options() -> Options {
+    serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": false,
+        "no_leaderboard": false,
+        "teams": null,
+    }))
+    .expect("static options are valid")
+}
+
+#[derive(Default)]
+struct Timings(Vec<Duration>);
+
+impl Timings {
+    fn report(&mut self, label: &str) {
+        self.0.sort();
+        let percentile = |p: usize| {
+            self.0
+                .get((self.0.len() * p / 100).min(self.0.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or_default()
+        };
+        println!(
+            "{label}: n={} p50={:?} p99={:?} max={:?}",
+            self.0.len(),
+            percentile(50),
+            percentile(99),
+            self.0.last().copied().unwrap_or_default()
+        );
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).map(|a| a.parse::<usize>());
+    let players = args.next().and_then(Result::ok).unwrap_or(2000);
+    let slides = args.next().and_then(Result::ok).unwrap_or(5);
+    let threads = args.next().and_then(Result::ok).unwrap_or(8).max(1);
+
+    let tunnel = LoadTunnel::default();
+    let host_id = Id::new();
+    let player_ids: Vec<Id> = (0..players).map(|_| Id::new()).collect();
+    let connected: HashSet<Id> = player_ids.iter().copied().chain([host_id]).collect();
+
+    let tunnel_finder = |id: Id| connected.contains(&id).then(|| tunnel.clone());
+    let ignore_alarms = |_: AlarmMessage, _: Duration| {};
+
+    let game = RwLock::new(Game::new(quiz(slides), options(), host_id));
+
+    let join_start = Instant::now();
+    for id in &player_ids {
+        game.write()
+            .expect("game lock poisoned")
+            .add_unassigned(*id, tunnel_finder)
+            .expect("player limit reached, lower the player count");
+    }
+    println!("{players} players joined in {:?}", join_start.elapsed());
+
+    let mut broadcasts = Timings::default();
+    let mut lock_waits = Timings::default();
+
+    let host_next = |broadcasts: &mut Timings| {
+        let mut game = game.write().expect("game lock poisoned");
+        let start = Instant::now();
+        game.receive_message(
+            host_id,
+            IncomingMessage::Host(IncomingHostMessage::Next),
+            ignore_alarms,
+            tunnel_finder,
+        );
+        broadcasts.0.push(start.elapsed());
+    };
+
+    host_next(&mut broadcasts);
+
+    for _ in 0..slides {
+        let waits = thread::scope(|scope| {
+            player_ids
+                .chunks(players.div_ceil(threads).max(1))
+                .map(|chunk| {
+                    scope.spawn(|| {
+                        chunk
+                            .iter()
+                            .map(|id| {
+                                let outbox = Outbox::default();
+                                let wait_start = Instant::now();
+                                let waited = {
+                                    let mut game = game.write().expect("game lock poisoned");
+                                    let waited = wait_start.elapsed();
+                                    game.receive_message(
+                                        *id,
+                                        IncomingMessage::Player(
+                                            IncomingPlayerMessage::IndexAnswer(fastrand::usize(
+                                                0..4,
+                                            )),
+                                        ),
+                                        ignore_alarms,
+                                        outbox.tunnel_finder(tunnel_finder),
+                                    );
+                                    waited
+                                };
+                                // fan out after the lock is released
+                                outbox.flush(tunnel_finder);
+                                waited
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("answering thread panicked"))
+                .collect::<Vec<_>>()
+        });
+        lock_waits.0.extend(waits);
+
+        // scores the slide and shows the leaderboard, then moves to the next slide
+        host_next(&mut broadcasts);
+        host_next(&mut broadcasts);
+    }
+
+    broadcasts.report("host broadcast");
+    lock_waits.report("answer lock wait");
+    println!(
+        "delivered {} messages ({} bytes)",
+        tunnel.messages.load(Ordering::Relaxed),
+        tunnel.bytes.load(Ordering::Relaxed)
+    );
+}
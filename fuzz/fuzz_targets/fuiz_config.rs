@@ -0,0 +1,43 @@
+#![no_main]
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{IncomingMessage, IncomingPlayerMessage},
+    testing::Simulation,
+};
+use garde::Validate;
+use libfuzzer_sys::fuzz_target;
+use web_time::Duration;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fuiz) = serde_json::from_slice::<Fuiz>(data) else {
+        return;
+    };
+    if fuiz.validate().is_err() {
+        return;
+    }
+
+    let options = serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": true,
+        "no_leaderboard": false,
+        "teams": null,
+    }))
+    .expect("static options are valid");
+
+    // any valid quiz must be playable to the end
+    let slides = fuiz.len();
+    let mut sim = Simulation::new(fuiz, options);
+    sim.join(2);
+    for _ in 0..=slides * 5 {
+        sim.host_next();
+        for answer in [
+            IncomingPlayerMessage::IndexAnswer(0),
+            IncomingPlayerMessage::StringAnswer(String::new()),
+            IncomingPlayerMessage::StringArrayAnswer(Vec::new()),
+        ] {
+            sim.send(sim.player(0), IncomingMessage::Player(answer));
+        }
+        sim.advance(Duration::from_secs(1));
+    }
+});
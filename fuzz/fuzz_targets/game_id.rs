@@ -0,0 +1,15 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use enum_map::Enum;
+use fuiz::game_id::GameId;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Ok(game_id) = GameId::from_str(data) {
+        // accepted ids print back to the same string and index within the game table
+        assert_eq!(game_id.to_string(), data);
+        assert!(game_id.into_usize() < GameId::LENGTH);
+    }
+});
@@ -0,0 +1,42 @@
+#![no_main]
+
+use fuiz::{game::IncomingMessage, testing::Simulation};
+use libfuzzer_sys::fuzz_target;
+use web_time::Duration;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = serde_json::from_slice::<IncomingMessage>(data) else {
+        return;
+    };
+
+    let fuiz = serde_json::from_value(serde_json::json!({
+        "title": "Fuzz",
+        "slides": [{
+            "TypeAnswer": {
+                "title": "Anything",
+                "time_limit": 10000,
+                "points_awarded": 1000,
+                "answers": ["anything"],
+            }
+        }],
+    }))
+    .expect("static quiz is valid");
+    let options = serde_json::from_value(serde_json::json!({
+        "random_names": false,
+        "show_answers": true,
+        "no_leaderboard": false,
+        "teams": null,
+    }))
+    .expect("static options are valid");
+
+    let mut sim = Simulation::new(fuiz, options);
+    sim.join(2);
+
+    // every phase of the game gets the message from every kind of watcher
+    for _ in 0..6 {
+        for watcher in [sim.host(), sim.player(0), sim.player(1)] {
+            sim.send(watcher, message.clone());
+        }
+        sim.host_next().advance(Duration::from_secs(1));
+    }
+});
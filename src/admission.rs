@@ -0,0 +1,39 @@
+//! Admission control for new games, whoever owns the games asks before creating one so the
+//! search for an unused [`GameId`] stays short and the instance doesn't take on more than it can
+//! run.
+
+use serde::Serialize;
+use thiserror::Error;
+use web_time::Duration;
+
+use crate::game_id::GameId;
+
+const CONFIG: crate::config::fuiz::admission::AdmissionConfig = crate::CONFIG.fuiz.admission;
+
+/// most games running at once
+pub const MAX_ACTIVE_GAMES: usize = CONFIG.max_active_games.unsigned_abs() as usize;
+const RETRY_AFTER: Duration = Duration::from_secs(CONFIG.retry_after.unsigned_abs());
+
+// a random id must keep a good chance of being free even at the limit
+const _: () = assert!(MAX_ACTIVE_GAMES <= GameId::COUNT / 2);
+
+#[serde_with::serde_as]
+#[derive(Error, Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("too many games are running, try again later")]
+    Saturated {
+        /// suggested wait before trying again
+        #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+        retry_after: Duration,
+    },
+}
+
+/// whether a new game may start alongside the `active_games` already running
+pub fn admit(active_games: usize) -> Result<(), Error> {
+    if active_games >= MAX_ACTIVE_GAMES {
+        return Err(Error::Saturated {
+            retry_after: RETRY_AFTER,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Answers locked in once given, for games where players may not change their mind. The exact
+//! payload of every answer is remembered so attempts to swap it are caught and shown to the host.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+use web_time::SystemTime;
+
+use crate::{game::IncomingPlayerMessage, watcher::Id};
+
+/// attempts kept for the host over the whole game, later ones are only refused
+pub const MAX_AUDIT_ENTRIES: usize = crate::CONFIG
+    .fuiz
+    .answer_lock
+    .max_audit_entries
+    .unsigned_abs() as usize;
+
+/// Answer of a player as it was first received
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct LockedAnswer {
+    /// hash of the exact payload, only compared within the same build of the server
+    hash: u64,
+    at: SystemTime,
+}
+
+/// Attempt of a player to replace their locked answer, kept for the host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub player: Id,
+    pub name: Option<String>,
+    /// index of the slide the answer was meant for
+    pub slide: usize,
+    /// when the original answer was received
+    pub locked_at: SystemTime,
+    /// when the different answer was attempted
+    pub at: SystemTime,
+}
+
+/// How an answer compares to the one already given on the slide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// no answer was given yet
+    First(u64),
+    /// the same payload was sent again, such as after a reconnection
+    Repeated,
+    /// a different payload was sent, holding when the locked answer arrived
+    Changed(SystemTime),
+}
+
+/// Answers given on the current slide
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AnswerLock {
+    answers: HashMap<Id, LockedAnswer>,
+    /// players already reported for trying to change their answer
+    #[serde(default)]
+    reported: HashSet<Id>,
+}
+
+fn hash(answer: &IncomingPlayerMessage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    answer.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl AnswerLock {
+    /// compares the answer with the one the player already gave
+    pub fn check(&self, player: Id, answer: &IncomingPlayerMessage) -> Check {
+        let hash = hash(answer);
+        match self.answers.get(&player) {
+            None => Check::First(hash),
+            Some(locked) if locked.hash == hash => Check::Repeated,
+            Some(locked) => Check::Changed(locked.at),
+        }
+    }
+
+    /// locks in an answer accepted by the slide, `hash` as returned by [`Check::First`]
+    pub fn record(&mut self, player: Id, hash: u64, at: SystemTime) {
+        self.answers.insert(player, LockedAnswer { hash, at });
+    }
+
+    /// whether an attempt to change the answer is worth reporting, only the first attempt of
+    /// each player on a slide is
+    pub fn report(&mut self, player: Id) -> bool {
+        self.reported.insert(player)
+    }
+
+    /// forgets the answers of the previous slide
+    pub fn clear(&mut self) {
+        self.answers.clear();
+        self.reported.clear();
+    }
+}
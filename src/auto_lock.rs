@@ -0,0 +1,63 @@
+//! Locking the lobby on its own once enough players joined or enough time passed since the first
+//! one did, so the host doesn't have to send the lock at the right moment.
+
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, SystemTime};
+
+use crate::watcher;
+
+const CONFIG: crate::config::fuiz::auto_lock::AutoLockConfig = crate::CONFIG.fuiz.auto_lock;
+
+const MAX_DELAY: Duration = Duration::from_secs(CONFIG.max_delay.unsigned_abs());
+
+fn validate_delay(delay: &Option<Duration>, _: &()) -> garde::Result {
+    match delay {
+        Some(delay) if delay.is_zero() || *delay > MAX_DELAY => Err(garde::Error::new(format!(
+            "delay is outside of the bounds (0,{}]s",
+            MAX_DELAY.as_secs()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Conditions locking the game, whichever comes first
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Validate)]
+pub struct AutoLockOptions {
+    /// locks once this many players are in the game
+    #[garde(range(min = 1, max = watcher::MAX_PLAYERS))]
+    #[serde(default)]
+    players: Option<usize>,
+    /// locks this long after the first player joined
+    #[garde(custom(validate_delay))]
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[serde(default)]
+    after: Option<Duration>,
+}
+
+impl AutoLockOptions {
+    pub fn after(&self) -> Option<Duration> {
+        self.after
+    }
+
+    /// whether any of the conditions is met
+    pub fn reached(
+        &self,
+        player_count: usize,
+        first_join: Option<SystemTime>,
+        now: SystemTime,
+    ) -> bool {
+        self.players.is_some_and(|players| player_count >= players)
+            || self
+                .after
+                .zip(first_join)
+                .is_some_and(|(after, first_join)| {
+                    now.duration_since(first_join).unwrap_or_default() >= after
+                })
+    }
+}
+
+/// Checks the conditions once the delay after the first join is over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmMessage;
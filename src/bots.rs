@@ -0,0 +1,74 @@
+//! Players driven by the server, letting a host rehearse a quiz alone or demo the leaderboard.
+//!
+//! Bots join with the game and answer every slide a random delay after it starts accepting
+//! answers, right with the configured probability. They are ordinary players otherwise, scored
+//! and ranked like everyone else.
+
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::watcher::Id;
+
+const CONFIG: crate::config::fuiz::bots::BotsConfig = crate::CONFIG.fuiz.bots;
+
+pub const MAX_BOTS: usize = CONFIG.max_count.unsigned_abs() as usize;
+const MAX_LATENCY: Duration = Duration::from_secs(CONFIG.max_latency.unsigned_abs());
+
+fn validate_latency(latency: &Duration, _: &()) -> garde::Result {
+    if *latency > MAX_LATENCY {
+        return Err(garde::Error::new(format!(
+            "latency is longer than {}s",
+            MAX_LATENCY.as_secs()
+        )));
+    }
+    Ok(())
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Validate)]
+pub struct BotOptions {
+    /// number of bots joining the game
+    #[garde(range(min = 1, max = MAX_BOTS))]
+    count: usize,
+    /// chance out of 100 of a bot answering correctly
+    #[garde(range(max = 100))]
+    accuracy: u8,
+    /// shortest delay between answers opening and a bot answering
+    #[garde(custom(validate_latency))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    min_latency: Duration,
+    /// longest delay between answers opening and a bot answering
+    #[garde(custom(validate_latency))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    max_latency: Duration,
+}
+
+impl BotOptions {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// whether the next answer is correct
+    pub fn roll(&self, rng: &mut fastrand::Rng) -> bool {
+        rng.u8(0..100) < self.accuracy
+    }
+
+    /// how long the next bot waits before answering, uniform over the configured range
+    pub fn latency(&self, rng: &mut fastrand::Rng) -> Duration {
+        let (min, max) = if self.min_latency <= self.max_latency {
+            (self.min_latency, self.max_latency)
+        } else {
+            (self.max_latency, self.min_latency)
+        };
+        let spread = (max - min).as_millis() as u64;
+        min + Duration::from_millis(rng.u64(0..=spread))
+    }
+}
+
+/// Wakes a bot up to answer the slide run with the given generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmMessage {
+    pub bot: Id,
+    pub generation: u64,
+}
@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use web_time::{Duration, SystemTime};
+
+/// Source of the current time for the engine, slides read time exclusively through it
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// Time left on a countdown along with the server time it was measured at and the instant it
+/// runs out, letting clients with a skewed clock or a slow connection count down accurately
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Countdown {
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub duration: Duration,
+    pub server_time: SystemTime,
+    pub deadline: SystemTime,
+}
+
+impl Countdown {
+    pub fn new(clock: &dyn Clock, duration: Duration) -> Self {
+        let server_time = clock.now();
+        Self {
+            duration,
+            server_time,
+            deadline: server_time + duration,
+        }
+    }
+}
+
+/// Clock following the system time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Manually advanced clock, lets tests fast-forward through slide timers without sleeping
+#[derive(Debug)]
+pub struct VirtualClock {
+    now: Mutex<SystemTime>,
+}
+
+impl VirtualClock {
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            now: Mutex::new(start),
+        }
+    }
+
+    /// moves the clock forward by the given duration
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().expect("virtual clock lock poisoned");
+        *now += duration;
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH)
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().expect("virtual clock lock poisoned")
+    }
+}
+
+/// default clock used by games unless another one is injected
+pub fn system() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
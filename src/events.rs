@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+use super::{game::GameClosedReason, watcher::Id};
+
+/// Milestones of a game reported to observers
+#[derive(Debug, Clone, Serialize)]
+pub enum GameEvent {
+    /// the game was set up, sent to each observer as it subscribes
+    Created { slides: usize },
+    /// a player got a name and entered the game
+    PlayerJoined { id: Id, name: String },
+    /// a slide started being shown
+    SlideStarted { index: usize },
+    /// the correct answers of a slide were revealed
+    AnswersRevealed { index: usize },
+    /// the game stopped accepting watchers
+    Finished { reason: GameClosedReason },
+}
+
+/// Receives the events of the games it subscribed to, used for metrics, webhooks and
+/// recordings without involving the slides themselves
+pub trait Observer: Send + Sync {
+    fn on_event(&self, event: &GameEvent);
+}
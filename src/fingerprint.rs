@@ -0,0 +1,35 @@
+//! Devices players join from, told apart by a fingerprint computed by whoever owns the
+//! connections (such as a hash of the address and a client hint token). Several players coming
+//! from the same device are reported to the host to discourage farming points with extra
+//! accounts.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::watcher::Id;
+
+/// Fingerprint of the connection of every watcher who reported one
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Fingerprints {
+    of: HashMap<Id, String>,
+}
+
+impl Fingerprints {
+    pub fn record(&mut self, watcher: Id, fingerprint: String) {
+        self.of.insert(watcher, fingerprint);
+    }
+
+    /// the watchers among `candidates` sharing the device of `watcher`, `watcher` excluded
+    pub fn sharing_with<I: IntoIterator<Item = Id>>(&self, watcher: Id, candidates: I) -> Vec<Id> {
+        let Some(fingerprint) = self.of.get(&watcher) else {
+            return Vec::new();
+        };
+
+        candidates
+            .into_iter()
+            .filter(|candidate| *candidate != watcher)
+            .filter(|candidate| self.of.get(candidate) == Some(fingerprint))
+            .collect()
+    }
+}
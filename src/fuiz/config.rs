@@ -1,9 +1,14 @@
-use web_time;
+use std::collections::HashMap;
+
+use web_time::{self, SystemTime};
 
 use garde::Validate;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 
 use crate::{
+    clock::{Clock, Countdown},
     leaderboard::Leaderboard,
     session::Tunnel,
     teams::TeamManager,
@@ -11,29 +16,403 @@ use crate::{
     AlarmMessage, SyncMessage,
 };
 
-use super::{super::game::IncomingMessage, media::Media, multiple_choice, order, type_answer};
+use super::{
+    super::game::{Error, IncomingMessage, IncomingPlayerMessage},
+    crossword, intermission,
+    media::Media,
+    memory_match, multiple_choice, order, plugin, type_answer, typing,
+};
 
 const CONFIG: crate::config::fuiz::FuizConfig = crate::CONFIG.fuiz;
 
 const MAX_SLIDES_COUNT: usize = CONFIG.max_slides_count.unsigned_abs() as usize;
 const MAX_TITLE_LENGTH: usize = CONFIG.max_title_length.unsigned_abs() as usize;
+const MAX_DESCRIPTION_LENGTH: usize = CONFIG.max_description_length.unsigned_abs() as usize;
+const MAX_SUBJECT_LENGTH: usize = CONFIG.max_subject_length.unsigned_abs() as usize;
+const MAX_LANGUAGE_LENGTH: usize = CONFIG.max_language_length.unsigned_abs() as usize;
+const MAX_SECTION_TITLE_LENGTH: usize = CONFIG.max_section_title_length.unsigned_abs() as usize;
 
 const MAX_TEXT_LENGTH: usize = crate::CONFIG.fuiz.answer_text.max_length.unsigned_abs() as usize;
 
+pub const MAX_HOST_NOTES_LENGTH: usize =
+    crate::CONFIG.fuiz.host_notes.max_length.unsigned_abs() as usize;
+
+/// trims host notes, dropping them if nothing is left
+pub fn canonicalize_host_notes(notes: &mut Option<String>) {
+    canonicalize_optional(notes);
+}
+
+/// host notes as seen by the watcher, players and unassigned never receive them
+pub fn host_notes(notes: Option<&str>, watcher_kind: ValueKind) -> Option<String> {
+    notes
+        .filter(|_| watcher_kind == ValueKind::Host)
+        .map(str::to_owned)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Validate)]
 pub enum TextOrMedia {
     Media(#[garde(skip)] Media),
     Text(#[garde(length(max = MAX_TEXT_LENGTH))] String),
 }
 
-/// A fuiz configuration, title is unused
+impl TextOrMedia {
+    pub fn canonicalize(&mut self) {
+        match self {
+            Self::Media(media) => media.canonicalize(),
+            Self::Text(text) => *text = text.trim().to_owned(),
+        }
+    }
+}
+
+const TIMELINE_BUCKET: web_time::Duration =
+    web_time::Duration::from_secs(crate::CONFIG.fuiz.timeline.bucket_seconds.unsigned_abs());
+
+/// Number of answers received over the answering phase, helping hosts tune time limits
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnswerTimeline {
+    /// length of each bucket
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub bucket: web_time::Duration,
+    /// answers received in each bucket, starting when answers opened
+    pub counts: Vec<usize>,
+}
+
+impl AnswerTimeline {
+    /// buckets the answer instants over the time limit, answers landing on the deadline count
+    /// towards the last bucket
+    pub fn new<I: IntoIterator<Item = SystemTime>>(
+        answer_start: SystemTime,
+        time_limit: web_time::Duration,
+        instants: I,
+    ) -> Self {
+        let bucket_count = time_limit
+            .as_millis()
+            .div_ceil(TIMELINE_BUCKET.as_millis())
+            .max(1) as usize;
+        let mut counts = vec![0; bucket_count];
+
+        for instant in instants {
+            let elapsed = instant.duration_since(answer_start).unwrap_or_default();
+            let bucket = (elapsed.as_millis() / TIMELINE_BUCKET.as_millis()) as usize;
+            counts[bucket.min(bucket_count - 1)] += 1;
+        }
+
+        Self {
+            bucket: TIMELINE_BUCKET,
+            counts,
+        }
+    }
+}
+
+const ANSWER_COUNT_CONFIG: crate::config::fuiz::answer_count::AnswerCountConfig =
+    crate::CONFIG.fuiz.answer_count;
+
+/// shortest time between two answer counts sent to the host
+const ANSWER_COUNT_INTERVAL: web_time::Duration =
+    web_time::Duration::from_millis(ANSWER_COUNT_CONFIG.interval_ms.unsigned_abs());
+/// share of the players answering at once that is sent to the host right away
+const ANSWER_COUNT_CHANGE_PERCENT: usize =
+    ANSWER_COUNT_CONFIG.change_percent.unsigned_abs() as usize;
+
+/// time between two broadcasts of the time left to answer, 0 disables them
+const TIME_CORRECTION_INTERVAL: web_time::Duration = web_time::Duration::from_millis(
+    crate::CONFIG
+        .fuiz
+        .time_correction
+        .interval_ms
+        .unsigned_abs(),
+);
+
+/// delay before the next broadcast of the time left to answer, `None` when they are disabled
+/// or when answers close first
+pub fn next_time_correction(remaining: web_time::Duration) -> Option<web_time::Duration> {
+    (!TIME_CORRECTION_INTERVAL.is_zero() && TIME_CORRECTION_INTERVAL < remaining)
+        .then_some(TIME_CORRECTION_INTERVAL)
+}
+
+/// tells everyone the time left to answer a question opened at `answer_start`, wrapped by
+/// `message` for the slide, returns the delay before the next broadcast
+pub fn announce_remaining_time<
+    T: Tunnel,
+    F: Fn(Id) -> Option<T>,
+    M: FnOnce(Countdown) -> crate::UpdateMessage,
+>(
+    watchers: &Watchers,
+    tunnel_finder: F,
+    clock: &dyn Clock,
+    time_limit: web_time::Duration,
+    answer_start: SystemTime,
+    message: M,
+) -> Option<web_time::Duration> {
+    let remaining =
+        time_limit.saturating_sub(clock.now().duration_since(answer_start).unwrap_or_default());
+    watchers.announce(&message(Countdown::new(clock, remaining)), tunnel_finder);
+    next_time_correction(remaining)
+}
+
+/// What to do with a new answer count, decided by [`AnswerCountThrottle::update`]
+pub enum AnswerCountUpdate {
+    Send,
+    /// schedule an alarm sending the latest count after the delay
+    Schedule(web_time::Duration),
+    Skip,
+}
+
+/// Batches the answer counts sent to the host, so answer storms of large games send a few
+/// counts instead of one for every answer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AnswerCountThrottle {
+    /// count the host last received
+    sent: usize,
+    sent_at: Option<SystemTime>,
+    /// whether an alarm will send the latest count
+    flush_scheduled: bool,
+}
+
+impl AnswerCountThrottle {
+    /// counts are sent right away once the previous one is old enough or enough of the players
+    /// answered since, otherwise a single alarm sends the latest count later
+    pub fn update(&mut self, count: usize, players: usize, now: SystemTime) -> AnswerCountUpdate {
+        if count == self.sent {
+            return AnswerCountUpdate::Skip;
+        }
+
+        let elapsed = self.sent_at.map_or(ANSWER_COUNT_INTERVAL, |sent_at| {
+            now.duration_since(sent_at).unwrap_or_default()
+        });
+        let large_change = count.abs_diff(self.sent) * 100 >= players * ANSWER_COUNT_CHANGE_PERCENT;
+
+        if elapsed >= ANSWER_COUNT_INTERVAL || large_change {
+            self.record(count, now);
+            AnswerCountUpdate::Send
+        } else if self.flush_scheduled {
+            AnswerCountUpdate::Skip
+        } else {
+            self.flush_scheduled = true;
+            AnswerCountUpdate::Schedule(ANSWER_COUNT_INTERVAL - elapsed)
+        }
+    }
+
+    /// whether the count found by the scheduled alarm still has to be sent
+    pub fn flush(&mut self, count: usize, now: SystemTime) -> bool {
+        self.flush_scheduled = false;
+        if count == self.sent {
+            return false;
+        }
+        self.record(count, now);
+        true
+    }
+
+    fn record(&mut self, count: usize, now: SystemTime) {
+        self.sent = count;
+        self.sent_at = Some(now);
+    }
+
+    /// tells the host how many players answered, wrapped by `message` for the slide, or
+    /// schedules the `flush` alarm to tell them later, see [`Self::update`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn announce<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+        M: FnOnce(usize) -> crate::UpdateMessage,
+    >(
+        &mut self,
+        answered: usize,
+        players: usize,
+        watchers: &Watchers,
+        mut schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        message: M,
+        flush: AlarmMessage,
+    ) {
+        match self.update(answered, players, clock.now()) {
+            AnswerCountUpdate::Send => {
+                watchers.announce_specific(ValueKind::Host, &message(answered), tunnel_finder);
+            }
+            AnswerCountUpdate::Schedule(delay) => schedule_message(flush, delay),
+            AnswerCountUpdate::Skip => {}
+        }
+    }
+
+    /// sends the count found by the `flush` alarm to the host, unless it was already sent
+    pub fn announce_flush<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        M: FnOnce(usize) -> crate::UpdateMessage,
+    >(
+        &mut self,
+        answered: usize,
+        watchers: &Watchers,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        message: M,
+    ) {
+        if self.flush(answered, clock.now()) {
+            watchers.announce_specific(ValueKind::Host, &message(answered), tunnel_finder);
+        }
+    }
+}
+
+/// Answer a player submitted to a slide, sent back to them along with the results
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OwnAnswer<T> {
+    pub answer: T,
+    /// whether the answer earned points
+    pub correct: bool,
+}
+
+impl<T> OwnAnswer<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> OwnAnswer<U> {
+        OwnAnswer {
+            answer: f(self.answer),
+            correct: self.correct,
+        }
+    }
+}
+
+/// Answer to any kind of slide
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum SubmittedAnswer {
+    Index(usize),
+    Text(String),
+    Order(Vec<String>),
+    /// clues of a crossword solved by the player
+    Clues(Vec<usize>),
+    /// number of pairs the player matched first in a memory match
+    Matches(usize),
+}
+
+/// language tag like `en` or `pt-BR`, made of letters and digits separated by hyphens
+fn validate_language(language: &Option<String>) -> garde::Result {
+    match language {
+        Some(language)
+            if !language.split('-').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric())
+            }) =>
+        {
+            Err(garde::Error::new("language is not a valid language tag"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// sections start on distinct slides, in order
+fn validate_sections(slide_count: usize) -> impl FnOnce(&Vec<Section>, &()) -> garde::Result {
+    move |sections, _| {
+        if sections.iter().any(|section| section.start >= slide_count) {
+            return Err(garde::Error::new("section starts past the last slide"));
+        }
+        if !sections
+            .iter()
+            .tuple_windows()
+            .all(|(previous, next)| previous.start < next.start)
+        {
+            return Err(garde::Error::new("sections are not in slide order"));
+        }
+        Ok(())
+    }
+}
+
+/// Named group of consecutive slides, like `Round 1: Geography`
+#[derive(Debug, Serialize, Deserialize, Clone, Validate)]
+pub struct Section {
+    #[garde(length(chars, min = 1, max = MAX_SECTION_TITLE_LENGTH))]
+    pub title: String,
+    /// index of its first slide, the section runs until the next one starts
+    #[garde(skip)]
+    pub start: usize,
+    /// whether the section starts a new round, with its own standings, that runs until the next
+    /// one starts
+    #[garde(skip)]
+    #[serde(default)]
+    pub round: bool,
+}
+
+/// How totals are kept from one round to the next
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RoundScoring {
+    /// totals keep growing, the standings of the round are shown alongside them
+    #[default]
+    Carry,
+    /// totals start from zero at every round
+    Reset,
+}
+
+/// Round a slide belongs to
+#[derive(Debug, Serialize, Clone)]
+pub struct Round {
+    pub title: String,
+    /// index of the round (0-indexing)
+    pub round: usize,
+    /// total count of rounds
+    pub rounds: usize,
+    /// index of its first slide
+    #[serde(skip)]
+    pub start: usize,
+}
+
+/// Position of a slide within its section
+#[derive(Debug, Serialize, Clone)]
+pub struct SectionProgress {
+    pub title: String,
+    /// index of the section (0-indexing)
+    pub section: usize,
+    /// total count of sections
+    pub sections: usize,
+    /// index of the slide within the section (0-indexing)
+    pub slide: usize,
+    /// total count of slides in the section
+    pub slides: usize,
+}
+
+/// trims the text, dropping it if nothing is left
+fn canonicalize_optional(text: &mut Option<String>) {
+    *text = text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_owned);
+}
+
+/// A fuiz configuration, its title and metadata are shown to players and in the lobby directory
 #[derive(Debug, Serialize, Deserialize, Clone, Validate)]
 pub struct Fuiz {
     #[garde(length(max = MAX_TITLE_LENGTH))]
     title: String,
+    #[garde(length(chars, max = MAX_DESCRIPTION_LENGTH))]
+    #[serde(default)]
+    description: Option<String>,
+    /// topic of the fuiz, like `History`
+    #[garde(length(chars, max = MAX_SUBJECT_LENGTH))]
+    #[serde(default)]
+    subject: Option<String>,
+    /// language the fuiz is written in
+    #[garde(length(max = MAX_LANGUAGE_LENGTH), custom(|v, _| validate_language(v)))]
+    #[serde(default)]
+    language: Option<String>,
 
     #[garde(length(max = MAX_SLIDES_COUNT), dive)]
     pub slides: Vec<SlideConfig>,
+    /// named groups of slides, slides before the first section belong to none
+    #[garde(dive, custom(validate_sections(self.slides.len())))]
+    #[serde(default)]
+    sections: Vec<Section>,
+    #[garde(skip)]
+    #[serde(default)]
+    round_scoring: RoundScoring,
+}
+
+/// What players are told about the fuiz they joined
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub struct FuizInfo {
+    pub title: String,
+    pub description: Option<String>,
+    pub subject: Option<String>,
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -47,16 +426,91 @@ pub enum SlideConfig {
     MultipleChoice(#[garde(dive)] multiple_choice::SlideConfig),
     TypeAnswer(#[garde(dive)] type_answer::SlideConfig),
     Order(#[garde(dive)] order::SlideConfig),
+    /// timed break between questions
+    Intermission(#[garde(dive)] intermission::SlideConfig),
+    /// grid of clues solved by everyone at their own pace
+    Crossword(#[garde(dive)] crossword::SlideConfig),
+    /// face-down cards turned over by the host, matched by players
+    MemoryMatch(#[garde(dive)] memory_match::SlideConfig),
+    /// sentence typed as fast and accurately as possible
+    Typing(#[garde(dive)] typing::SlideConfig),
+    /// slide of a type registered through [`plugin::register`]
+    Custom(#[garde(custom(plugin::validate_config))] plugin::CustomConfig),
+}
+
+/// Validation failure of a single field, `path` locating it like `slides[2][0].time_limit`
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Outcome of [`Fuiz::lint`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Lint {
+    /// the fuiz as games would store it
+    pub config: Fuiz,
+    pub errors: Vec<FieldError>,
 }
 
 impl SlideConfig {
-    pub fn to_state(&self) -> SlideState {
+    /// normalizes the slide without changing its meaning, custom slides are left untouched
+    pub fn canonicalize(&mut self) {
+        match self {
+            Self::MultipleChoice(s) => s.canonicalize(),
+            Self::TypeAnswer(s) => s.canonicalize(),
+            Self::Order(s) => s.canonicalize(),
+            Self::Intermission(s) => s.canonicalize(),
+            Self::Crossword(s) => s.canonicalize(),
+            Self::MemoryMatch(s) => s.canonicalize(),
+            Self::Typing(s) => s.canonicalize(),
+            Self::Custom(_) => {}
+        }
+    }
+
+    /// drops the notes meant for the host, slides without any are left untouched
+    pub fn clear_host_notes(&mut self) {
+        match self {
+            Self::MultipleChoice(s) => s.clear_host_notes(),
+            Self::Intermission(s) => s.clear_host_notes(),
+            Self::Crossword(s) => s.clear_host_notes(),
+            Self::MemoryMatch(s) => s.clear_host_notes(),
+            Self::Typing(s) => s.clear_host_notes(),
+            Self::TypeAnswer(s) => s.clear_host_notes(),
+            Self::Order(s) => s.clear_host_notes(),
+            Self::Custom(_) => {}
+        }
+    }
+
+    /// drops the correct answers of the slide so results only show how players answered,
+    /// slides without any are left untouched
+    pub fn clear_correct_answers(&mut self) {
         match self {
-            Self::MultipleChoice(s) => SlideState::MultipleChoice(s.to_state()),
-            Self::TypeAnswer(s) => SlideState::TypeAnswer(s.to_state()),
-            Self::Order(s) => SlideState::Order(s.to_state()),
+            Self::MultipleChoice(s) => s.clear_correct_answers(),
+            Self::TypeAnswer(s) => s.clear_correct_answers(),
+            Self::Order(s) => s.clear_correct_answers(),
+            Self::Intermission(_)
+            | Self::Crossword(_)
+            | Self::MemoryMatch(_)
+            | Self::Typing(_)
+            | Self::Custom(_) => {}
         }
     }
+
+    /// state of a new run of the slide, `seed` drives its random choices such as shuffles, only
+    /// custom slides can fail to start
+    pub fn to_state(&self, generation: u64, seed: u64) -> Result<SlideState, String> {
+        Ok(match self {
+            Self::MultipleChoice(s) => SlideState::MultipleChoice(s.to_state(generation)),
+            Self::TypeAnswer(s) => SlideState::TypeAnswer(s.to_state(generation)),
+            Self::Order(s) => SlideState::Order(s.to_state(generation, seed)),
+            Self::Intermission(s) => SlideState::Intermission(s.to_state(generation)),
+            Self::Crossword(s) => SlideState::Crossword(s.to_state(generation)),
+            Self::MemoryMatch(s) => SlideState::MemoryMatch(s.to_state(generation, seed)),
+            Self::Typing(s) => SlideState::Typing(s.to_state(generation)),
+            Self::Custom(s) => SlideState::Custom(s.to_state(generation)?),
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,25 +518,307 @@ pub enum SlideState {
     MultipleChoice(multiple_choice::State),
     TypeAnswer(type_answer::State),
     Order(order::State),
+    Intermission(intermission::State),
+    Crossword(crossword::State),
+    MemoryMatch(memory_match::State),
+    Typing(typing::State),
+    Custom(plugin::State),
 }
 
 impl Fuiz {
+    pub fn new(title: String, slides: Vec<SlideConfig>) -> Self {
+        Self {
+            title,
+            description: None,
+            subject: None,
+            language: None,
+            slides,
+            sections: Vec::new(),
+            round_scoring: RoundScoring::default(),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.slides.len()
     }
 
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// where the slide at the index stands in its section, `None` outside of sections
+    pub fn section_progress(&self, index: usize) -> Option<SectionProgress> {
+        let section = self
+            .sections
+            .iter()
+            .rposition(|section| section.start <= index)?;
+        let start = self.sections[section].start;
+        let end = self
+            .sections
+            .get(section + 1)
+            .map_or(self.slides.len(), |next| next.start);
+
+        Some(SectionProgress {
+            title: self.sections[section].title.clone(),
+            section,
+            sections: self.sections.len(),
+            slide: index - start,
+            slides: end.saturating_sub(start),
+        })
+    }
+
+    /// round the slide at the index belongs to, `None` before the first round
+    pub fn round(&self, index: usize) -> Option<Round> {
+        let rounds = self
+            .sections
+            .iter()
+            .filter(|section| section.round)
+            .collect_vec();
+        let round = rounds.iter().rposition(|section| section.start <= index)?;
+
+        Some(Round {
+            title: rounds[round].title.clone(),
+            round,
+            rounds: rounds.len(),
+            start: rounds[round].start,
+        })
+    }
+
+    pub fn round_scoring(&self) -> RoundScoring {
+        self.round_scoring
+    }
+
+    pub fn info(&self) -> FuizInfo {
+        FuizInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            subject: self.subject.clone(),
+            language: self.language.clone(),
+        }
+    }
+
+    /// trims every text, leaving the meaning of the fuiz unchanged
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        canonicalize_optional(&mut self.description);
+        canonicalize_optional(&mut self.subject);
+        canonicalize_optional(&mut self.language);
+        for section in &mut self.sections {
+            section.title = section.title.trim().to_owned();
+        }
+        for slide in &mut self.slides {
+            slide.canonicalize();
+        }
+    }
+
+    /// copy of the fuiz safe to show players, without the notes meant for the host
+    pub fn without_host_notes(&self) -> Self {
+        let mut fuiz = self.clone();
+        for slide in &mut fuiz.slides {
+            slide.clear_host_notes();
+        }
+        fuiz
+    }
+
+    /// turns every slide into a poll without correct answers
+    pub fn clear_correct_answers(&mut self) {
+        for slide in &mut self.slides {
+            slide.clear_correct_answers();
+        }
+    }
+
+    /// canonicalizes then validates the fuiz without starting a game, for editors
+    pub fn lint(mut self) -> Lint {
+        self.canonicalize();
+
+        let errors = match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(report) => report
+                .iter()
+                .map(|(path, error)| FieldError {
+                    path: path.to_string(),
+                    message: error.message().to_owned(),
+                })
+                .collect(),
+        };
+
+        Lint {
+            config: self,
+            errors,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.slides.is_empty()
     }
 }
 
 impl SlideState {
+    /// name of the kind of slide, as used in configurations
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MultipleChoice(_) => "MultipleChoice",
+            Self::TypeAnswer(_) => "TypeAnswer",
+            Self::Order(_) => "Order",
+            Self::Intermission(_) => "Intermission",
+            Self::Crossword(_) => "Crossword",
+            Self::MemoryMatch(_) => "MemoryMatch",
+            Self::Typing(_) => "Typing",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    /// whether players can answer right now, custom slides are never answered by bots
+    pub fn accepting_answers(&self) -> bool {
+        match self {
+            Self::MultipleChoice(s) => s.accepting_answers(),
+            Self::TypeAnswer(s) => s.accepting_answers(),
+            Self::Order(s) => s.accepting_answers(),
+            Self::Crossword(s) => s.accepting_answers(),
+            Self::MemoryMatch(s) => s.accepting_answers(),
+            Self::Typing(s) => s.accepting_answers(),
+            Self::Intermission(_) | Self::Custom(_) => false,
+        }
+    }
+
+    /// whether a player gives a single answer to the slide, which can then be locked in
+    pub fn takes_single_answer(&self) -> bool {
+        matches!(
+            self,
+            Self::MultipleChoice(_) | Self::TypeAnswer(_) | Self::Order(_)
+        )
+    }
+
+    /// answer a bot submits to the slide, `None` if bots can't answer it
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        match self {
+            Self::MultipleChoice(s) => s.bot_answer(correct, rng),
+            Self::TypeAnswer(s) => s.bot_answer(correct, rng),
+            Self::Order(s) => s.bot_answer(correct, rng),
+            Self::Crossword(s) => s.bot_answer(correct, rng),
+            Self::MemoryMatch(s) => s.bot_answer(correct, rng),
+            Self::Typing(s) => s.bot_answer(correct),
+            Self::Intermission(_) | Self::Custom(_) => None,
+        }
+    }
+
+    /// when answers arrived, `None` for slides without an answering phase or before it
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        match self {
+            Self::MultipleChoice(s) => s.answer_timeline(),
+            Self::TypeAnswer(s) => s.answer_timeline(),
+            Self::Order(s) => s.answer_timeline(),
+            Self::Crossword(s) => s.answer_timeline(),
+            Self::MemoryMatch(s) => s.answer_timeline(),
+            Self::Typing(s) => s.answer_timeline(),
+            Self::Intermission(_) | Self::Custom(_) => None,
+        }
+    }
+
+    /// what each player answered and whether it earned points
+    pub fn submitted_answers(&self) -> HashMap<Id, OwnAnswer<SubmittedAnswer>> {
+        match self {
+            Self::MultipleChoice(s) => s
+                .submitted_answers()
+                .map(|(id, answer)| (id, answer.map(SubmittedAnswer::Index)))
+                .collect(),
+            Self::TypeAnswer(s) => s
+                .submitted_answers()
+                .map(|(id, answer)| (id, answer.map(SubmittedAnswer::Text)))
+                .collect(),
+            Self::Order(s) => s
+                .submitted_answers()
+                .map(|(id, answer)| (id, answer.map(SubmittedAnswer::Order)))
+                .collect(),
+            Self::Crossword(s) => s
+                .submitted_answers()
+                .map(|(id, clues, correct)| {
+                    (
+                        id,
+                        OwnAnswer {
+                            answer: SubmittedAnswer::Clues(clues),
+                            correct,
+                        },
+                    )
+                })
+                .collect(),
+            Self::MemoryMatch(s) => s
+                .submitted_answers()
+                .map(|(id, matches)| {
+                    (
+                        id,
+                        OwnAnswer {
+                            answer: SubmittedAnswer::Matches(matches),
+                            correct: true,
+                        },
+                    )
+                })
+                .collect(),
+            Self::Typing(s) => s
+                .submitted_answers()
+                .map(|(id, typed, correct)| {
+                    (
+                        id,
+                        OwnAnswer {
+                            answer: SubmittedAnswer::Text(typed),
+                            correct,
+                        },
+                    )
+                })
+                .collect(),
+            Self::Intermission(_) | Self::Custom(_) => HashMap::new(),
+        }
+    }
+
+    /// how long each player took to answer, empty for slides without answers
+    pub fn response_times(&self) -> Vec<(Id, web_time::Duration)> {
+        match self {
+            Self::MultipleChoice(s) => s.response_times(),
+            Self::TypeAnswer(s) => s.response_times(),
+            Self::Order(s) => s.response_times(),
+            Self::Crossword(s) => s.response_times(),
+            Self::MemoryMatch(s) => s.response_times(),
+            Self::Typing(s) => s.response_times(),
+            Self::Intermission(_) | Self::Custom(_) => Vec::new(),
+        }
+    }
+
+    /// whether the correct answers are being shown
+    pub fn answers_revealed(&self) -> bool {
+        match self {
+            Self::MultipleChoice(s) => s.answers_revealed(),
+            Self::TypeAnswer(s) => s.answers_revealed(),
+            Self::Order(s) => s.answers_revealed(),
+            Self::Crossword(s) => s.answers_revealed(),
+            Self::MemoryMatch(s) => s.answers_revealed(),
+            Self::Typing(s) => s.answers_revealed(),
+            Self::Intermission(_) => false,
+            Self::Custom(s) => s.answers_revealed(),
+        }
+    }
+
+    /// whether a break is running, during which the game is expected to be quiet
+    pub fn on_break(&self) -> bool {
+        matches!(self, Self::Intermission(s) if s.on_break())
+    }
+
+    /// whether the leaderboard is shown after the slide, breaks change no scores
+    pub fn shows_leaderboard(&self) -> bool {
+        !matches!(self, Self::Intermission(_))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn play<T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(AlarmMessage, web_time::Duration)>(
         &mut self,
         team_manager: Option<&TeamManager>,
         watchers: &Watchers,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
@@ -93,19 +829,85 @@ impl SlideState {
                     watchers,
                     schedule_message,
                     tunnel_finder,
+                    clock,
                     index,
                     count,
                 );
             }
             Self::TypeAnswer(s) => {
-                s.play(watchers, schedule_message, tunnel_finder, index, count);
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
             }
             Self::Order(s) => {
-                s.play(watchers, schedule_message, tunnel_finder, index, count);
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
+            }
+            Self::Typing(s) => {
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
+            }
+            Self::MemoryMatch(s) => {
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
+            }
+            Self::Crossword(s) => {
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
+            }
+            Self::Intermission(s) => {
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
+            }
+            Self::Custom(s) => {
+                s.play(
+                    watchers,
+                    schedule_message,
+                    tunnel_finder,
+                    clock,
+                    index,
+                    count,
+                );
             }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_message<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -118,10 +920,11 @@ impl SlideState {
         schedule_message: S,
         watcher_id: Id,
         tunnel_finder: F,
+        clock: &dyn Clock,
         message: IncomingMessage,
         index: usize,
         count: usize,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         match self {
             Self::MultipleChoice(s) => s.receive_message(
                 watcher_id,
@@ -131,6 +934,7 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
                 index,
                 count,
             ),
@@ -142,6 +946,7 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
                 index,
                 count,
             ),
@@ -153,12 +958,71 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
+                index,
+                count,
+            ),
+            Self::Typing(s) => s.receive_message(
+                watcher_id,
+                message,
+                leaderboard,
+                watchers,
+                team_manager,
+                schedule_message,
+                tunnel_finder,
+                clock,
+                index,
+                count,
+            ),
+            Self::MemoryMatch(s) => s.receive_message(
+                watcher_id,
+                message,
+                leaderboard,
+                watchers,
+                team_manager,
+                schedule_message,
+                tunnel_finder,
+                clock,
+                index,
+                count,
+            ),
+            Self::Crossword(s) => s.receive_message(
+                watcher_id,
+                message,
+                leaderboard,
+                watchers,
+                team_manager,
+                schedule_message,
+                tunnel_finder,
+                clock,
+                index,
+                count,
+            ),
+            Self::Intermission(s) => s.receive_message(
+                watcher_id,
+                message,
+                leaderboard,
+                watchers,
+                schedule_message,
+                tunnel_finder,
+                clock,
                 index,
                 count,
             ),
+            Self::Custom(s) => s.receive_message(
+                watcher_id,
+                message,
+                leaderboard,
+                watchers,
+                schedule_message,
+                tunnel_finder,
+                clock,
+                index,
+            ),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
         watcher_id: Id,
@@ -166,6 +1030,7 @@ impl SlideState {
         team_manager: Option<&TeamManager>,
         watchers: &Watchers,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) -> SyncMessage {
@@ -176,6 +1041,7 @@ impl SlideState {
                 team_manager,
                 watchers,
                 tunnel_finder,
+                clock,
                 index,
                 count,
             )),
@@ -185,6 +1051,7 @@ impl SlideState {
                 team_manager,
                 watchers,
                 tunnel_finder,
+                clock,
                 index,
                 count,
             )),
@@ -194,12 +1061,98 @@ impl SlideState {
                 team_manager,
                 watchers,
                 tunnel_finder,
+                clock,
+                index,
+                count,
+            )),
+            Self::Typing(s) => SyncMessage::Typing(s.state_message(
+                watcher_id,
+                watcher_kind,
+                team_manager,
+                watchers,
+                tunnel_finder,
+                clock,
+                index,
+                count,
+            )),
+            Self::MemoryMatch(s) => SyncMessage::MemoryMatch(s.state_message(
+                watcher_id,
+                watcher_kind,
+                team_manager,
+                watchers,
+                tunnel_finder,
+                clock,
+                index,
+                count,
+            )),
+            Self::Crossword(s) => SyncMessage::Crossword(s.state_message(
+                watcher_id,
+                watcher_kind,
+                team_manager,
+                watchers,
+                tunnel_finder,
+                clock,
                 index,
                 count,
             )),
+            Self::Intermission(s) => SyncMessage::Intermission(s.state_message(
+                watcher_id,
+                watcher_kind,
+                clock,
+                index,
+                count,
+            )),
+            Self::Custom(s) => {
+                SyncMessage::Custom(s.state_message(watcher_id, watcher_kind, clock, index, count))
+            }
+        }
+    }
+
+    /// updates sent along with the state to a watcher reconnecting mid-slide, keeping live
+    /// counters from showing stale values until the next change
+    /// number of present players who answered, the same count live updates carry
+    pub fn answered_count<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> usize {
+        match self {
+            Self::MultipleChoice(s) => s.answered_count(watchers, tunnel_finder),
+            Self::TypeAnswer(s) => s.answered_count(watchers, tunnel_finder),
+            Self::Order(s) => s.answered_count(watchers, tunnel_finder),
+            Self::Intermission(_)
+            | Self::Crossword(_)
+            | Self::MemoryMatch(_)
+            | Self::Typing(_)
+            | Self::Custom(_) => {
+                let players = watchers.present_players(tunnel_finder);
+                self.submitted_answers()
+                    .keys()
+                    .filter(|id| players.contains(id))
+                    .count()
+            }
         }
     }
 
+    pub fn catch_up_updates<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> Vec<crate::UpdateMessage> {
+        match self {
+            Self::MultipleChoice(s) => s.catch_up_updates(watcher_kind, watchers, tunnel_finder),
+            Self::TypeAnswer(s) => s.catch_up_updates(watcher_kind, watchers, tunnel_finder),
+            Self::Order(s) => s.catch_up_updates(watcher_kind, watchers, tunnel_finder),
+            Self::Typing(s) => s.catch_up_updates(watcher_id, watcher_kind, watchers),
+            Self::Intermission(_) | Self::Crossword(_) | Self::MemoryMatch(_) | Self::Custom(_) => {
+                Vec::new()
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_alarm<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -211,6 +1164,7 @@ impl SlideState {
         team_manager: Option<&TeamManager>,
         schedule_message: &mut S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         message: AlarmMessage,
         index: usize,
         count: usize,
@@ -222,6 +1176,7 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
                 message,
                 index,
                 count,
@@ -232,6 +1187,7 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
                 message,
                 index,
                 count,
@@ -242,10 +1198,24 @@ impl SlideState {
                 team_manager,
                 schedule_message,
                 tunnel_finder,
+                clock,
                 message,
                 index,
                 count,
             ),
+            Self::Intermission(s) => s.receive_alarm(leaderboard, watchers, tunnel_finder, message),
+            Self::Crossword(s) => s.receive_alarm(watchers, tunnel_finder, message),
+            Self::MemoryMatch(s) => s.receive_alarm(watchers, tunnel_finder, message),
+            Self::Typing(s) => s.receive_alarm(watchers, tunnel_finder, message),
+            Self::Custom(s) => s.receive_alarm(
+                leaderboard,
+                watchers,
+                schedule_message,
+                tunnel_finder,
+                clock,
+                message,
+                index,
+            ),
         }
     }
 }
@@ -0,0 +1,398 @@
+//! Importers turning quizzes written for other tools into a [`Fuiz`].
+//!
+//! Entries that have no equivalent slide are reported in the same [`garde::Report`] as the
+//! validation of the converted quiz, keyed by the line they start on (`line[12]`), so a quiz is
+//! either converted whole or every problem with it is listed at once.
+
+use std::time::Duration;
+
+use garde::{Path, Report, Validate};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    config::{Fuiz, SlideConfig, TextOrMedia},
+    multiple_choice::{self, AnswerChoice},
+    type_answer,
+};
+
+const CONFIG: crate::config::fuiz::convert::ConvertConfig = crate::CONFIG.fuiz.convert;
+
+const INTRODUCE_QUESTION: Duration = Duration::from_secs(CONFIG.introduce_question.unsigned_abs());
+const TIME_LIMIT: Duration = Duration::from_secs(CONFIG.time_limit.unsigned_abs());
+const POINTS_AWARDED: u64 = CONFIG.points_awarded.unsigned_abs();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    /// Kahoot spreadsheet template, copied out of the sheet as tab separated rows
+    KahootSheet,
+    /// Moodle GIFT text format
+    Gift,
+}
+
+/// converts the quiz, failing with every unmappable entry and validation error
+pub fn convert(format: Format, title: &str, input: &str) -> Result<Fuiz, Report> {
+    match format {
+        Format::KahootSheet => kahoot_sheet(title, input),
+        Format::Gift => gift(title, input),
+    }
+}
+
+fn entry_error(report: &mut Report, line: usize, message: impl AsRef<str>) {
+    report.append(
+        Path::new("line").join(line),
+        garde::Error::new(message.as_ref()),
+    );
+}
+
+fn finish(title: &str, slides: Vec<SlideConfig>, mut report: Report) -> Result<Fuiz, Report> {
+    let fuiz = Fuiz::new(title.to_owned(), slides);
+
+    if let Err(validation) = fuiz.validate() {
+        for (path, error) in validation.into_inner() {
+            report.append(path, error);
+        }
+    }
+
+    if report.is_empty() {
+        Ok(fuiz)
+    } else {
+        Err(report)
+    }
+}
+
+fn multiple_choice(
+    question: String,
+    time_limit: Duration,
+    answers: Vec<(String, bool)>,
+) -> SlideConfig {
+    SlideConfig::MultipleChoice(multiple_choice::SlideConfig::new(
+        question,
+        INTRODUCE_QUESTION,
+        time_limit,
+        POINTS_AWARDED,
+        answers
+            .into_iter()
+            .map(|(text, correct)| AnswerChoice {
+                correct,
+                content: TextOrMedia::Text(text),
+            })
+            .collect(),
+    ))
+}
+
+/// Column positions of the Kahoot template
+struct KahootColumns {
+    question: usize,
+    answers: [usize; 4],
+    time_limit: usize,
+    correct: usize,
+}
+
+impl Default for KahootColumns {
+    fn default() -> Self {
+        Self {
+            question: 0,
+            answers: [1, 2, 3, 4],
+            time_limit: 5,
+            correct: 6,
+        }
+    }
+}
+
+impl KahootColumns {
+    /// locates the columns from the header row of the template
+    fn from_header(cells: &[&str]) -> Option<Self> {
+        let position = |prefix: &str| {
+            cells
+                .iter()
+                .position(|cell| cell.to_lowercase().starts_with(prefix))
+        };
+
+        Some(Self {
+            question: position("question")?,
+            answers: [
+                position("answer 1")?,
+                position("answer 2")?,
+                position("answer 3")?,
+                position("answer 4")?,
+            ],
+            time_limit: position("time limit")?,
+            correct: position("correct answer")?,
+        })
+    }
+}
+
+/// reads the rows of the Kahoot template, anything above its header row is instructions
+fn kahoot_sheet(title: &str, input: &str) -> Result<Fuiz, Report> {
+    let rows = input
+        .lines()
+        .enumerate()
+        .map(|(index, row)| {
+            (
+                index + 1,
+                row.split('\t').map(str::trim).collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let (columns, rows) = match rows
+        .iter()
+        .position(|(_, cells)| KahootColumns::from_header(cells).is_some())
+    {
+        Some(header) => (
+            KahootColumns::from_header(&rows[header].1).unwrap_or_default(),
+            &rows[header + 1..],
+        ),
+        None => (KahootColumns::default(), &rows[..]),
+    };
+
+    let mut slides = Vec::new();
+    let mut report = Report::new();
+
+    for (line, cells) in rows {
+        if cells.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+        let cell = |index: usize| cells.get(index).copied().unwrap_or_default();
+
+        let Ok(seconds) = cell(columns.time_limit).parse::<u64>() else {
+            entry_error(&mut report, *line, "time limit is not a number of seconds");
+            continue;
+        };
+
+        let correct = cell(columns.correct)
+            .split(',')
+            .map(|answer| answer.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>();
+        let Ok(correct) = correct else {
+            entry_error(&mut report, *line, "correct answers are not answer numbers");
+            continue;
+        };
+
+        if let Some(missing) = correct.iter().find(|answer| {
+            !(1..=4).contains(*answer) || cell(columns.answers[*answer - 1]).is_empty()
+        }) {
+            entry_error(
+                &mut report,
+                *line,
+                format!("answer {missing} is marked correct but is empty"),
+            );
+            continue;
+        }
+
+        let answers = columns
+            .answers
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| !cell(**column).is_empty())
+            .map(|(index, column)| (cell(*column).to_owned(), correct.contains(&(index + 1))))
+            .collect();
+
+        slides.push(multiple_choice(
+            cell(columns.question).to_owned(),
+            Duration::from_secs(seconds),
+            answers,
+        ));
+    }
+
+    finish(title, slides, report)
+}
+
+/// index of the first occurrence of the character that isn't escaped with a backslash
+fn find_unescaped(text: &str, target: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == target {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// resolves the escapes of GIFT special characters and trims the result
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some(next) => unescaped.push(next),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped.trim().to_owned()
+}
+
+/// splits an answer block into its answers, marked with whether they started with `=`
+fn gift_answers(block: &str) -> Vec<(bool, &str)> {
+    let mut answers = Vec::new();
+    let mut current: Option<(bool, usize)> = None;
+    let mut escaped = false;
+
+    for (index, c) in block.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | '~' => {
+                if let Some((marked, start)) = current {
+                    answers.push((marked, &block[start..index]));
+                }
+                current = Some((c == '=', index + 1));
+            }
+            _ => {}
+        }
+    }
+    if let Some((marked, start)) = current {
+        answers.push((marked, &block[start..]));
+    }
+
+    answers
+}
+
+/// one question of a GIFT file, failing with why it has no equivalent slide
+fn gift_question(entry: &str) -> Result<SlideConfig, &'static str> {
+    let mut entry = entry.trim();
+
+    // question name, unused since slides are titled by their question
+    if let Some(rest) = entry.strip_prefix("::") {
+        let end = rest.find("::").ok_or("question name is not closed")?;
+        entry = rest[end + 2..].trim_start();
+    }
+
+    // text format, everything is shown as plain text
+    if entry.starts_with('[') {
+        if let Some(end) = entry.find(']') {
+            entry = &entry[end + 1..];
+        }
+    }
+
+    let open =
+        find_unescaped(entry, '{').ok_or("descriptions without answers are not supported")?;
+    let close =
+        open + 1 + find_unescaped(&entry[open + 1..], '}').ok_or("answer block is not closed")?;
+
+    let (before, block, after) = (
+        &entry[..open],
+        entry[open + 1..close].trim(),
+        &entry[close + 1..],
+    );
+
+    // missing word questions have text after the answer block
+    let question = match unescape(after) {
+        after if after.is_empty() => unescape(before),
+        after => format!("{} _____ {}", unescape(before), after),
+    };
+
+    let without_feedback = |text: &str| {
+        let text = &text[..find_unescaped(text, '#').unwrap_or(text.len())];
+        text.trim().to_owned()
+    };
+
+    if block.is_empty() {
+        return Err("essay questions are not supported");
+    }
+    if block.starts_with('#') {
+        return Err("numerical questions are not supported");
+    }
+
+    match without_feedback(block).to_uppercase().as_str() {
+        "T" | "TRUE" => {
+            return Ok(multiple_choice(
+                question,
+                TIME_LIMIT,
+                vec![("True".to_owned(), true), ("False".to_owned(), false)],
+            ))
+        }
+        "F" | "FALSE" => {
+            return Ok(multiple_choice(
+                question,
+                TIME_LIMIT,
+                vec![("True".to_owned(), false), ("False".to_owned(), true)],
+            ))
+        }
+        _ => {}
+    }
+
+    let answers = gift_answers(block);
+    if answers.is_empty() {
+        return Err("answers must start with = or ~");
+    }
+    if answers.iter().any(|(_, text)| text.contains("->")) {
+        return Err("matching questions are not supported");
+    }
+
+    let answers = answers
+        .into_iter()
+        .map(|(marked, text)| {
+            let text = without_feedback(text);
+            // partial credit, any positive weight counts as a correct answer
+            match text.strip_prefix('%').and_then(|rest| rest.split_once('%')) {
+                Some((weight, text)) => (
+                    unescape(text),
+                    weight.parse::<f64>().is_ok_and(|weight| weight > 0.),
+                ),
+                None => (unescape(&text), marked),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // only correct answers means players type one of them
+    if answers.iter().all(|(_, correct)| *correct) {
+        Ok(SlideConfig::TypeAnswer(type_answer::SlideConfig::new(
+            question,
+            INTRODUCE_QUESTION,
+            TIME_LIMIT,
+            POINTS_AWARDED,
+            answers.into_iter().map(|(text, _)| text).collect(),
+            false,
+        )))
+    } else {
+        Ok(multiple_choice(question, TIME_LIMIT, answers))
+    }
+}
+
+/// reads a GIFT file, questions are separated by blank lines
+fn gift(title: &str, input: &str) -> Result<Fuiz, Report> {
+    let mut slides = Vec::new();
+    let mut report = Report::new();
+
+    let mut entries: Vec<(usize, String)> = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("//") || trimmed.starts_with("$CATEGORY:") {
+            continue;
+        }
+        if trimmed.is_empty() {
+            entries.extend(current.take());
+            continue;
+        }
+        let (_, entry) = current.get_or_insert_with(|| (index + 1, String::new()));
+        if !entry.is_empty() {
+            entry.push('\n');
+        }
+        entry.push_str(line);
+    }
+    entries.extend(current);
+
+    for (line, entry) in entries {
+        match gift_question(&entry) {
+            Ok(slide) => slides.push(slide),
+            Err(reason) => entry_error(&mut report, line, reason),
+        }
+    }
+
+    finish(title, slides, report)
+}
@@ -0,0 +1,832 @@
+//! Cooperative crossword: players solve the numbered clues of a small grid, which fills in on
+//! the host screen as correct answers arrive
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    time::{self, Duration},
+};
+
+use garde::Validate;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use web_time::SystemTime;
+
+use crate::{
+    clock::{Clock, Countdown},
+    leaderboard::Leaderboard,
+    session::Tunnel,
+    teams::TeamManager,
+    watcher::{Id, ValueKind, Watchers},
+};
+
+use super::{
+    super::game::{self, Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{canonicalize_host_notes, host_notes, AnswerTimeline, MAX_HOST_NOTES_LENGTH},
+    media::Media,
+};
+
+/// Phase of the slide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SlideState {
+    /// Unstarted, exists to distinguish between started and unstarted slide
+    #[default]
+    Unstarted,
+    /// Showing the grid and accepting words for its clues
+    Answers,
+    /// Showing the solved grid and how many players solved each clue
+    AnswersResults,
+}
+
+type ValidationResult = garde::Result;
+
+const CONFIG: crate::config::fuiz::crossword::CrosswordConfig = crate::CONFIG.fuiz.crossword;
+
+const MAX_TITLE_LENGTH: usize = CONFIG.max_title_length.unsigned_abs() as usize;
+const MIN_TIME_LIMIT: u64 = CONFIG.min_time_limit.unsigned_abs();
+const MAX_TIME_LIMIT: u64 = CONFIG.max_time_limit.unsigned_abs();
+const MAX_GRID_SIZE: usize = CONFIG.max_grid_size.unsigned_abs() as usize;
+const MAX_CLUE_COUNT: usize = CONFIG.max_clue_count.unsigned_abs() as usize;
+const MAX_HINT_LENGTH: usize = CONFIG.max_hint_length.unsigned_abs() as usize;
+
+fn validate_time_limit(val: &Duration) -> ValidationResult {
+    if (MIN_TIME_LIMIT..=MAX_TIME_LIMIT).contains(&val.as_secs()) {
+        Ok(())
+    } else {
+        Err(garde::Error::new(format!(
+            "time_limit is outside of the bounds [{MIN_TIME_LIMIT},{MAX_TIME_LIMIT}]",
+        )))
+    }
+}
+
+/// checks that every clue stays inside the grid and that crossing words agree on their letters
+fn validate_grid(width: usize, height: usize) -> impl FnOnce(&Vec<Clue>, &()) -> ValidationResult {
+    move |clues, _| {
+        let mut letters: HashMap<(usize, usize), char> = HashMap::new();
+
+        for (clue_index, clue) in clues.iter().enumerate() {
+            for (cell, letter) in clue.cells().zip(clean_word(&clue.answer).chars()) {
+                if cell.0 >= height || cell.1 >= width {
+                    return Err(garde::Error::new(format!(
+                        "clue {clue_index} goes past the edge of the grid",
+                    )));
+                }
+                if letters
+                    .insert(cell, letter)
+                    .is_some_and(|existing| existing != letter)
+                {
+                    return Err(garde::Error::new(format!(
+                        "clue {clue_index} disagrees with a crossing word at row {}, column {}",
+                        cell.0, cell.1,
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// letters of a word as they are compared, ignoring case and spaces
+fn clean_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Way a word is written in the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct Clue {
+    /// Number shown in the starting cell and next to the hint
+    #[garde(skip)]
+    number: usize,
+    #[garde(skip)]
+    direction: Direction,
+    /// Row of the first letter (0-indexing)
+    #[garde(skip)]
+    row: usize,
+    /// Column of the first letter (0-indexing)
+    #[garde(skip)]
+    column: usize,
+    /// Hint shown to everyone
+    #[garde(length(chars, min = 1, max = MAX_HINT_LENGTH))]
+    hint: String,
+    /// Word filling the cells, only revealed once solved
+    #[garde(length(chars, min = 1, max = MAX_GRID_SIZE))]
+    answer: String,
+}
+
+impl Clue {
+    /// cells covered by the word, as (row, column)
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..clean_word(&self.answer).chars().count()).map(|offset| match self.direction {
+            Direction::Across => (self.row, self.column + offset),
+            Direction::Down => (self.row + offset, self.column),
+        })
+    }
+
+    fn view(&self) -> ClueView {
+        ClueView {
+            number: self.number,
+            direction: self.direction,
+            row: self.row,
+            column: self.column,
+            length: clean_word(&self.answer).chars().count(),
+            hint: self.hint.clone(),
+        }
+    }
+}
+
+/// Clue as shown before it is solved, without its answer
+#[derive(Debug, Serialize, Clone)]
+pub struct ClueView {
+    number: usize,
+    direction: Direction,
+    row: usize,
+    column: usize,
+    /// Number of letters of the word
+    length: usize,
+    hint: String,
+}
+
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, Validate)]
+pub struct SlideConfig {
+    /// Title of the crossword
+    #[garde(length(chars, max = MAX_TITLE_LENGTH))]
+    title: String,
+    /// Accompanying media
+    #[garde(dive)]
+    media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
+    /// Time where players can solve clues
+    #[garde(custom(|v, _| validate_time_limit(v)))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    time_limit: Duration,
+    /// Maximum number of points awarded for each clue, decreases linearly to half the amount by the end of the slide
+    #[garde(skip)]
+    points_awarded: u64,
+    /// Number of columns of the grid
+    #[garde(range(min = 1, max = MAX_GRID_SIZE))]
+    width: usize,
+    /// Number of rows of the grid
+    #[garde(range(min = 1, max = MAX_GRID_SIZE))]
+    height: usize,
+    #[garde(length(min = 1, max = MAX_CLUE_COUNT), dive, custom(validate_grid(self.width, self.height)))]
+    clues: Vec<Clue>,
+}
+
+/// Presenting a crossword whose clues players solve on their own devices
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct State {
+    config: SlideConfig,
+
+    // State
+    /// Clues each player solved, with when they solved them
+    solved: HashMap<Id, HashMap<usize, SystemTime>>,
+    /// Clues solved by at least one player, filled in on the host grid
+    revealed: BTreeSet<usize>,
+    /// Instant where the grid was first displayed
+    answer_start: Option<SystemTime>,
+    /// Stage of the slide
+    state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+}
+
+impl SlideConfig {
+    pub fn new(
+        title: String,
+        time_limit: Duration,
+        points_awarded: u64,
+        width: usize,
+        height: usize,
+        clues: Vec<Clue>,
+    ) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            time_limit,
+            points_awarded,
+            width,
+            height,
+            clues,
+        }
+    }
+
+    /// trims the title, hints and answers
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        for clue in &mut self.clues {
+            clue.hint = clue.hint.trim().to_owned();
+            clue.answer = clue.answer.trim().to_owned();
+        }
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    pub fn to_state(&self, generation: u64) -> State {
+        State {
+            config: self.clone(),
+            solved: HashMap::new(),
+            revealed: BTreeSet::new(),
+            answer_start: None,
+            state: SlideState::default(),
+            generation,
+        }
+    }
+}
+
+/// Messages sent to the listeners to update their pre-existing state with the slide state
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum UpdateMessage {
+    /// Announcement of the grid and its clues
+    GridAnnouncement {
+        /// Index of the slide (0-indexing)
+        index: usize,
+        /// Total count of slides
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
+        width: usize,
+        height: usize,
+        clues: Vec<ClueView>,
+        /// Time to solve the clues
+        #[serde(flatten)]
+        countdown: Countdown,
+    },
+    /// (PLAYER ONLY) Whether the word submitted for the clue was right
+    ClueChecked { clue: usize, correct: bool },
+    /// (HOST ONLY) A clue was solved for the first time, filling it in the grid
+    ClueSolved { clue: usize, answer: String },
+    /// Every answer along with how many players solved each clue
+    AnswersResults {
+        answers: Vec<String>,
+        solved_counts: Vec<usize>,
+        /// (PLAYER ONLY) Clues the player solved
+        own_solved: Option<Vec<usize>>,
+        /// (HOST ONLY) When clues were solved over the answering phase
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AlarmMessage {
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+}
+
+/// Messages sent to the listeners who lack preexisting state to synchronize their state.
+///
+/// See [`UpdateMessage`] for explaination of these fields.
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum SyncMessage {
+    /// Grid with the clues solved so far, every solved clue for the host and their own for players
+    GridAnnouncement {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        width: usize,
+        height: usize,
+        clues: Vec<ClueView>,
+        /// Remaining time to solve the clues
+        #[serde(flatten)]
+        countdown: Countdown,
+        filled: Vec<(usize, String)>,
+    },
+    AnswersResults {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        width: usize,
+        height: usize,
+        clues: Vec<ClueView>,
+        answers: Vec<String>,
+        solved_counts: Vec<usize>,
+        own_solved: Option<Vec<usize>>,
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+impl State {
+    pub fn play<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        self.send_grid_announcements(
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            index,
+            count,
+        );
+    }
+
+    fn calculate_score(
+        full_duration: Duration,
+        taken_duration: Duration,
+        full_points_awarded: u64,
+    ) -> u64 {
+        (full_points_awarded as f64
+            * (1. - (taken_duration.as_secs_f64() / full_duration.as_secs_f64() / 2.)))
+            as u64
+    }
+
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
+    }
+
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// words are only accepted while the grid is shown and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn clue_views(&self) -> Vec<ClueView> {
+        self.config.clues.iter().map(Clue::view).collect_vec()
+    }
+
+    fn send_grid_announcements<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        mut schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        if self.change_state(SlideState::Unstarted, SlideState::Answers) {
+            self.answer_start = Some(clock.now());
+
+            let clues = self.clue_views();
+
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::GridAnnouncement {
+                            index,
+                            count,
+                            title: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                            width: self.config.width,
+                            height: self.config.height,
+                            clues: clues.clone(),
+                            countdown: Countdown::new(clock, self.config.time_limit),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+
+            schedule_message(
+                AlarmMessage::ProceedFromSlideIntoSlide {
+                    index,
+                    to: SlideState::AnswersResults,
+                    generation: self.generation,
+                }
+                .into(),
+                self.config.time_limit,
+            )
+        }
+    }
+
+    fn change_state(&mut self, before: SlideState, after: SlideState) -> bool {
+        if self.state == before {
+            self.state = after;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// answer a bot submits, the word of a random clue or nothing matching any of them
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        let clue = rng.usize(..self.config.clues.len());
+        Some(IncomingPlayerMessage::ClueAnswer {
+            clue,
+            answer: if correct {
+                self.config.clues[clue].answer.clone()
+            } else {
+                String::new()
+            },
+        })
+    }
+
+    /// whether the answers are being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
+    fn answers(&self) -> Vec<String> {
+        self.config
+            .clues
+            .iter()
+            .map(|clue| clean_word(&clue.answer))
+            .collect_vec()
+    }
+
+    /// number of players who solved each clue
+    fn solved_counts(&self) -> Vec<usize> {
+        let counts = self
+            .solved
+            .values()
+            .flat_map(HashMap::keys)
+            .copied()
+            .counts();
+        (0..self.config.clues.len())
+            .map(|clue| counts.get(&clue).copied().unwrap_or_default())
+            .collect_vec()
+    }
+
+    /// clues solved by the player, in order
+    fn own_solved(&self, watcher_id: Id, watcher_kind: ValueKind) -> Option<Vec<usize>> {
+        (watcher_kind == ValueKind::Player).then(|| {
+            self.solved
+                .get(&watcher_id)
+                .map(|solved| solved.keys().copied().sorted().collect_vec())
+                .unwrap_or_default()
+        })
+    }
+
+    fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) {
+        if self.change_state(SlideState::Answers, SlideState::AnswersResults) {
+            let answers = self.answers();
+            let solved_counts = self.solved_counts();
+
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            answers: answers.clone(),
+                            solved_counts: solved_counts.clone(),
+                            own_solved: self.own_solved(watcher_id, watcher_kind),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+        }
+    }
+
+    /// when clues were solved, `None` until the grid is shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self.answer_start?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.solved.values().flat_map(HashMap::values).copied(),
+        ))
+    }
+
+    /// how long each player took to solve their latest clue once the grid was shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.solved
+            .iter()
+            .filter_map(|(id, solved)| {
+                let latest = solved.values().max()?;
+                Some((*id, latest.duration_since(answer_start).unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// clues every player solved and whether they solved the whole grid
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, Vec<usize>, bool)> + '_ {
+        self.solved.iter().map(|(id, solved)| {
+            (
+                *id,
+                solved.keys().copied().sorted().collect_vec(),
+                solved.len() == self.config.clues.len(),
+            )
+        })
+    }
+
+    fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+    ) {
+        let starting_instant = self.timer(clock);
+
+        leaderboard.add_scores(
+            &self
+                .solved
+                .iter()
+                .map(|(id, solved)| {
+                    (
+                        *id,
+                        solved
+                            .values()
+                            .map(|instant| {
+                                State::calculate_score(
+                                    self.config.time_limit,
+                                    instant.duration_since(starting_instant).unwrap_or_default(),
+                                    self.config.points_awarded,
+                                )
+                            })
+                            .sum::<u64>(),
+                    )
+                })
+                .into_grouping_map_by(|(id, _)| {
+                    let player_id = *id;
+                    match &team_manager {
+                        Some(team_manager) => team_manager.get_team(player_id).unwrap_or(player_id),
+                        None => player_id,
+                    }
+                })
+                .min_by_key(|_, (_, score)| *score)
+                .into_iter()
+                .map(|(id, (_, score))| (id, score))
+                .chain(
+                    {
+                        match &team_manager {
+                            Some(team_manager) => team_manager.all_ids(),
+                            None => watchers
+                                .present_players(tunnel_finder)
+                                .into_iter()
+                                .collect_vec(),
+                        }
+                    }
+                    .into_iter()
+                    .map(|id| (id, 0)),
+                )
+                .unique_by(|(id, _)| *id)
+                .collect_vec(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        _team_manager: Option<&TeamManager>,
+        _watchers: &Watchers,
+        _tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> SyncMessage {
+        match self.state {
+            SlideState::Unstarted | SlideState::Answers => SyncMessage::GridAnnouncement {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                width: self.config.width,
+                height: self.config.height,
+                clues: self.clue_views(),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
+                filled: {
+                    let filled: Vec<usize> = match watcher_kind {
+                        ValueKind::Host => self.revealed.iter().copied().collect_vec(),
+                        _ => self
+                            .own_solved(watcher_id, watcher_kind)
+                            .unwrap_or_default(),
+                    };
+                    filled
+                        .into_iter()
+                        .map(|clue| (clue, clean_word(&self.config.clues[clue].answer)))
+                        .collect_vec()
+                },
+            },
+            SlideState::AnswersResults => SyncMessage::AnswersResults {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                width: self.config.width,
+                height: self.config.height,
+                clues: self.clue_views(),
+                answers: self.answers(),
+                solved_counts: self.solved_counts(),
+                own_solved: self.own_solved(watcher_id, watcher_kind),
+                timeline: self.host_timeline(watcher_kind),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        message: IncomingMessage,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> Result<bool, Error> {
+        match message {
+            IncomingMessage::Host(IncomingHostMessage::Next) => match self.state {
+                SlideState::Unstarted => {
+                    self.send_grid_announcements(
+                        watchers,
+                        schedule_message,
+                        tunnel_finder,
+                        clock,
+                        index,
+                        count,
+                    );
+                }
+                SlideState::Answers => {
+                    self.send_answers_results(watchers, tunnel_finder);
+                }
+                SlideState::AnswersResults => {
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder, clock);
+                    return Ok(true);
+                }
+            },
+            IncomingMessage::Player(IncomingPlayerMessage::ClueAnswer { clue, answer }) => {
+                let Some(expected) = self.config.clues.get(clue) else {
+                    return Err(Error::InvalidAnswer);
+                };
+                self.check_answer_window(clock)?;
+
+                let at = clock.now();
+                let correct = clean_word(&answer) == clean_word(&expected.answer);
+                let expected = clean_word(&expected.answer);
+
+                watchers.send_message(
+                    &game::UpdateMessage::AnswerRecorded { index, at }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+                watchers.send_message(
+                    &UpdateMessage::ClueChecked { clue, correct }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+
+                if !correct {
+                    return Ok(false);
+                }
+
+                self.solved
+                    .entry(watcher_id)
+                    .or_default()
+                    .entry(clue)
+                    .or_insert(at);
+
+                if self.revealed.insert(clue) {
+                    watchers.announce_specific(
+                        ValueKind::Host,
+                        &UpdateMessage::ClueSolved {
+                            clue,
+                            answer: expected,
+                        }
+                        .into(),
+                        &tunnel_finder,
+                    );
+                }
+
+                let clue_count = self.config.clues.len();
+                let finished: HashSet<_> = self
+                    .solved
+                    .iter()
+                    .filter(|(_, solved)| solved.len() == clue_count)
+                    .map(|(id, _)| *id)
+                    .collect();
+                if watchers
+                    .present_players(&tunnel_finder)
+                    .is_subset(&finished)
+                {
+                    self.send_answers_results(watchers, &tunnel_finder);
+                }
+            }
+            _ => return Err(Error::WrongPhase),
+        };
+
+        Ok(false)
+    }
+
+    pub fn receive_alarm<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+        message: crate::AlarmMessage,
+    ) -> bool {
+        if let crate::AlarmMessage::Crossword(AlarmMessage::ProceedFromSlideIntoSlide {
+            to: SlideState::AnswersResults,
+            generation,
+            ..
+        }) = message
+        {
+            if generation == self.generation {
+                self.send_answers_results(watchers, tunnel_finder);
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,186 @@
+//! Arithmetic expressions evaluated with exact fractions, so answers like `1/2`, `0.5` and `2/4`
+//! are recognized as the same value
+
+use num_integer::Integer;
+
+/// deepest nesting of parentheses and signs accepted, keeping evaluation bounded
+const MAX_DEPTH: usize = 32;
+
+/// Exact fraction, always stored with a positive denominator and in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = numerator.gcd(&denominator);
+        let sign = denominator.signum();
+        Some(Self {
+            numerator: numerator.checked_div(divisor)?.checked_mul(sign)?,
+            denominator: denominator.checked_div(divisor)?.checked_mul(sign)?,
+        })
+    }
+
+    fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    fn add(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator
+                .checked_mul(other.denominator)?
+                .checked_add(other.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+
+    fn neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(other.numerator)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(other.denominator)?,
+            self.denominator.checked_mul(other.numerator)?,
+        )
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while self.input.get(self.position)?.is_ascii_whitespace() {
+            self.position += 1;
+        }
+        self.input.get(self.position).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expression(&mut self) -> Option<Rational> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(b'+') {
+                value = value.add(self.term()?)?;
+            } else if self.eat(b'-') {
+                value = value.add(self.term()?.neg()?)?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<Rational> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat(b'*') {
+                value = value.mul(self.factor()?)?;
+            } else if self.eat(b'/') {
+                value = value.div(self.factor()?)?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Option<Rational> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return None;
+        }
+
+        let value = if self.eat(b'-') {
+            self.factor()?.neg()?
+        } else if self.eat(b'+') {
+            self.factor()?
+        } else if self.eat(b'(') {
+            let value = self.expression()?;
+            if !self.eat(b')') {
+                return None;
+            }
+            value
+        } else {
+            self.number()?
+        };
+
+        self.depth -= 1;
+        Some(value)
+    }
+
+    /// decimal number like `12`, `0.5` or `.5`
+    fn number(&mut self) -> Option<Rational> {
+        self.peek()?;
+
+        let mut numerator = Rational::integer(0);
+        let mut denominator = 1i128;
+        let mut digits = 0;
+        let mut after_point = false;
+
+        while let Some(&byte) = self.input.get(self.position) {
+            match byte {
+                b'0'..=b'9' => {
+                    numerator = numerator
+                        .mul(Rational::integer(10))?
+                        .add(Rational::integer(i128::from(byte - b'0')))?;
+                    if after_point {
+                        denominator = denominator.checked_mul(10)?;
+                    }
+                    digits += 1;
+                }
+                b'.' if !after_point => after_point = true,
+                _ => break,
+            }
+            self.position += 1;
+        }
+
+        if digits == 0 {
+            return None;
+        }
+        numerator.div(Rational::integer(denominator))
+    }
+}
+
+/// exact value of the arithmetic expression, `None` if it isn't one or it can't be computed,
+/// like when dividing by zero
+pub fn evaluate(input: &str) -> Option<Rational> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.expression()?;
+    match parser.peek() {
+        None => Some(value),
+        Some(_) => None,
+    }
+}
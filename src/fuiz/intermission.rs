@@ -0,0 +1,347 @@
+use std::time::{self, Duration};
+
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use web_time::SystemTime;
+
+use crate::{
+    clock::{Clock, Countdown},
+    leaderboard::Leaderboard,
+    session::Tunnel,
+    watcher::{Id, ValueKind, Watchers},
+};
+
+use super::{
+    super::game::{Error, IncomingHostMessage, IncomingMessage},
+    config::{canonicalize_host_notes, host_notes, MAX_HOST_NOTES_LENGTH},
+    media::Media,
+};
+
+/// Phase of the slide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SlideState {
+    /// Unstarted, exists to distinguish between started and unstarted slide
+    #[default]
+    Unstarted,
+    /// Counting down the break
+    Break,
+    /// Break is over, waiting for the host to resume the game
+    Over,
+}
+
+type ValidationResult = garde::Result;
+
+const CONFIG: crate::config::fuiz::intermission::IntermissionConfig =
+    crate::CONFIG.fuiz.intermission;
+
+const MAX_TITLE_LENGTH: usize = CONFIG.max_title_length.unsigned_abs() as usize;
+const MIN_DURATION: u64 = CONFIG.min_duration.unsigned_abs();
+const MAX_DURATION: u64 = CONFIG.max_duration.unsigned_abs();
+
+fn validate_duration(val: &Duration) -> ValidationResult {
+    if (MIN_DURATION..=MAX_DURATION).contains(&val.as_secs()) {
+        Ok(())
+    } else {
+        Err(garde::Error::new(format!(
+            "duration is outside of the bounds [{MIN_DURATION},{MAX_DURATION}]",
+        )))
+    }
+}
+
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, Validate)]
+pub struct SlideConfig {
+    /// Text shown during the break, e.g. "5 minute break, back at :45"
+    #[garde(length(chars, max = MAX_TITLE_LENGTH))]
+    title: String,
+    /// Accompanying media
+    #[garde(dive)]
+    media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
+    /// Length of the break
+    #[garde(custom(|v, _| validate_duration(v)))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    duration: Duration,
+    /// Moves on to the next slide when the break is over instead of waiting for the host
+    #[garde(skip)]
+    #[serde(default)]
+    auto_resume: bool,
+}
+
+/// A timed break between questions, the game isn't considered idle while it lasts
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct State {
+    config: SlideConfig,
+
+    // State
+    /// Instant where the break started
+    break_start: Option<SystemTime>,
+    /// Stage of the slide
+    state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+}
+
+impl SlideConfig {
+    pub fn new(title: String, duration: Duration, auto_resume: bool) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            duration,
+            auto_resume,
+        }
+    }
+
+    /// trims the text of the break
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    pub fn to_state(&self, generation: u64) -> State {
+        State {
+            config: self.clone(),
+            break_start: None,
+            state: SlideState::Unstarted,
+            generation,
+        }
+    }
+}
+
+/// Messages sent to the listeners to update their pre-existing state with the slide state
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum UpdateMessage {
+    /// Announcement of the break
+    BreakAnnouncement {
+        /// Index of the slide (0-indexing)
+        index: usize,
+        /// Total count of slides
+        count: usize,
+        /// Text shown during the break
+        title: String,
+        /// Accompanying media
+        media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
+        /// Time until the break is over
+        #[serde(flatten)]
+        countdown: Countdown,
+    },
+    /// The break is over, the host resumes the game when ready
+    BreakOver,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AlarmMessage {
+    EndBreak {
+        index: usize,
+        #[serde(default)]
+        generation: u64,
+    },
+}
+
+/// Messages sent to the listeners who lack preexisting state to synchronize their state.
+///
+/// See [`UpdateMessage`] for explaination of these fields.
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum SyncMessage {
+    /// Announcement of the break
+    BreakAnnouncement {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        /// Remaining time of the break
+        #[serde(flatten)]
+        countdown: Countdown,
+    },
+    /// The break is over, the host resumes the game when ready
+    BreakOver {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+    },
+}
+
+impl State {
+    pub fn play<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        mut schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        if self.state != SlideState::Unstarted {
+            return;
+        }
+        self.state = SlideState::Break;
+        self.break_start = Some(clock.now());
+
+        watchers.announce_with(
+            |_, watcher_kind| {
+                Some(
+                    UpdateMessage::BreakAnnouncement {
+                        index,
+                        count,
+                        title: self.config.title.clone(),
+                        media: self.config.media.clone(),
+                        countdown: Countdown::new(clock, self.config.duration),
+                        host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                    }
+                    .into(),
+                )
+            },
+            tunnel_finder,
+        );
+
+        schedule_message(
+            AlarmMessage::EndBreak {
+                index,
+                generation: self.generation,
+            }
+            .into(),
+            self.config.duration,
+        );
+    }
+
+    /// whether the break is still running, the game is expected to be quiet meanwhile
+    pub fn on_break(&self) -> bool {
+        self.state == SlideState::Break
+    }
+
+    /// breaks earn nobody points, the empty round keeps slides and scores aligned
+    fn finish(&mut self, leaderboard: &mut Leaderboard) -> bool {
+        self.state = SlideState::Over;
+        leaderboard.add_scores(&[]);
+        true
+    }
+
+    fn remaining(&self, clock: &dyn Clock) -> Duration {
+        let elapsed = self
+            .break_start
+            .and_then(|start| clock.now().duration_since(start).ok())
+            .unwrap_or_default();
+        self.config.duration.saturating_sub(elapsed)
+    }
+
+    pub fn state_message(
+        &self,
+        _watcher_id: Id,
+        watcher_kind: ValueKind,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> SyncMessage {
+        match self.state {
+            SlideState::Unstarted | SlideState::Break => SyncMessage::BreakAnnouncement {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(clock, self.remaining(clock)),
+            },
+            SlideState::Over => SyncMessage::BreakOver {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        _watcher_id: Id,
+        message: IncomingMessage,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> Result<bool, Error> {
+        match message {
+            IncomingMessage::Host(IncomingHostMessage::Next) => match self.state {
+                SlideState::Unstarted => {
+                    self.play(
+                        watchers,
+                        schedule_message,
+                        tunnel_finder,
+                        clock,
+                        index,
+                        count,
+                    );
+                    Ok(false)
+                }
+                // the host may cut the break short
+                SlideState::Break | SlideState::Over => Ok(self.finish(leaderboard)),
+            },
+            _ => Err(Error::WrongPhase),
+        }
+    }
+
+    pub fn receive_alarm<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        tunnel_finder: F,
+        message: crate::AlarmMessage,
+    ) -> bool {
+        let crate::AlarmMessage::Intermission(AlarmMessage::EndBreak { generation, .. }) = message
+        else {
+            return false;
+        };
+
+        if generation != self.generation || self.state != SlideState::Break {
+            return false;
+        }
+
+        if self.config.auto_resume {
+            return self.finish(leaderboard);
+        }
+
+        self.state = SlideState::Over;
+        watchers.announce(&UpdateMessage::BreakOver.into(), tunnel_finder);
+
+        false
+    }
+}
@@ -13,6 +13,14 @@ const CORKBOARD_CONFIG: crate::config::fuiz::corkboard::CorkboardConfig =
 const ID_LENGTH: usize = CORKBOARD_CONFIG.id_length.unsigned_abs() as usize;
 const MAX_ALT_LENGTH: usize = CORKBOARD_CONFIG.max_alt_length.unsigned_abs() as usize;
 
+impl Media {
+    pub fn canonicalize(&mut self) {
+        match self {
+            Self::Image(Image::Corkboard { alt, .. }) => *alt = alt.trim().to_owned(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Validate)]
 pub enum Image {
     Corkboard {
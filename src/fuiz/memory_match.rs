@@ -0,0 +1,743 @@
+//! Memory match: the host turns over face-down cards one at a time and players race to name
+//! the positions of matching pairs
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{self, Duration},
+};
+
+use garde::Validate;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use web_time::SystemTime;
+
+use crate::{
+    clock::{Clock, Countdown},
+    leaderboard::Leaderboard,
+    session::Tunnel,
+    teams::TeamManager,
+    watcher::{Id, ValueKind, Watchers},
+};
+
+use super::{
+    super::game::{self, Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{
+        canonicalize_host_notes, host_notes, AnswerTimeline, TextOrMedia, MAX_HOST_NOTES_LENGTH,
+    },
+    media::Media,
+};
+
+/// Phase of the slide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SlideState {
+    /// Unstarted, exists to distinguish between started and unstarted slide
+    #[default]
+    Unstarted,
+    /// Showing the face-down cards and accepting pairs
+    Answers,
+    /// Showing every card and who matched them
+    AnswersResults,
+}
+
+type ValidationResult = garde::Result;
+
+const CONFIG: crate::config::fuiz::memory_match::MemoryMatchConfig =
+    crate::CONFIG.fuiz.memory_match;
+
+const MAX_TITLE_LENGTH: usize = CONFIG.max_title_length.unsigned_abs() as usize;
+const MIN_TIME_LIMIT: u64 = CONFIG.min_time_limit.unsigned_abs();
+const MAX_TIME_LIMIT: u64 = CONFIG.max_time_limit.unsigned_abs();
+const MIN_PAIR_COUNT: usize = CONFIG.min_pair_count.unsigned_abs() as usize;
+const MAX_PAIR_COUNT: usize = CONFIG.max_pair_count.unsigned_abs() as usize;
+
+fn validate_time_limit(val: &Duration) -> ValidationResult {
+    if (MIN_TIME_LIMIT..=MAX_TIME_LIMIT).contains(&val.as_secs()) {
+        Ok(())
+    } else {
+        Err(garde::Error::new(format!(
+            "time_limit is outside of the bounds [{MIN_TIME_LIMIT},{MAX_TIME_LIMIT}]",
+        )))
+    }
+}
+
+/// Two cards that belong together, like a word and its translation
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct Pair {
+    #[garde(dive)]
+    first: TextOrMedia,
+    #[garde(dive)]
+    second: TextOrMedia,
+}
+
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, Validate)]
+pub struct SlideConfig {
+    /// Title shown above the cards
+    #[garde(length(chars, max = MAX_TITLE_LENGTH))]
+    title: String,
+    /// Accompanying media
+    #[garde(dive)]
+    media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
+    /// Time where players can match pairs
+    #[garde(custom(|v, _| validate_time_limit(v)))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    time_limit: Duration,
+    /// Maximum number of points awarded for each pair, decreases linearly to half the amount by the end of the slide
+    #[garde(skip)]
+    points_awarded: u64,
+    /// Pairs laid out face-down in a shuffled grid
+    #[garde(length(min = MIN_PAIR_COUNT, max = MAX_PAIR_COUNT), dive)]
+    pairs: Vec<Pair>,
+}
+
+/// Presenting face-down cards turned over by the host
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct State {
+    config: SlideConfig,
+
+    // State
+    /// Side of a pair at each position of the grid, `2 * pair + side`
+    layout: Vec<usize>,
+    /// Cards the host turned over at least once
+    revealed: BTreeSet<usize>,
+    /// Player who matched each pair first, with when they did
+    matched: HashMap<usize, (Id, SystemTime)>,
+    /// Instant where the cards were first displayed
+    answer_start: Option<SystemTime>,
+    /// Stage of the slide
+    state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+}
+
+impl SlideConfig {
+    pub fn new(title: String, time_limit: Duration, points_awarded: u64, pairs: Vec<Pair>) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            time_limit,
+            points_awarded,
+            pairs,
+        }
+    }
+
+    /// trims the title and the text of the cards
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        for pair in &mut self.pairs {
+            pair.first.canonicalize();
+            pair.second.canonicalize();
+        }
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    pub fn to_state(&self, generation: u64, seed: u64) -> State {
+        let mut layout = (0..self.pairs.len() * 2).collect_vec();
+        fastrand::Rng::with_seed(seed).shuffle(&mut layout);
+
+        State {
+            config: self.clone(),
+            layout,
+            revealed: BTreeSet::new(),
+            matched: HashMap::new(),
+            answer_start: None,
+            state: SlideState::default(),
+            generation,
+        }
+    }
+}
+
+/// Messages sent to the listeners to update their pre-existing state with the slide state
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum UpdateMessage {
+    /// Announcement of the face-down cards
+    BoardAnnouncement {
+        /// Index of the slide (0-indexing)
+        index: usize,
+        /// Total count of slides
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
+        card_count: usize,
+        /// Time to match the pairs
+        #[serde(flatten)]
+        countdown: Countdown,
+    },
+    /// The host turned over the card at this position
+    CardRevealed { card: usize, content: TextOrMedia },
+    /// The cards at these positions were matched and stay face-up
+    PairMatched { cards: (usize, usize) },
+    /// (PLAYER ONLY) Whether the pair submitted by the player earned them points
+    PairChecked {
+        cards: (usize, usize),
+        matched: bool,
+    },
+    /// Every card along with how many pairs each player matched
+    AnswersResults {
+        cards: Vec<TextOrMedia>,
+        /// (PLAYER ONLY) Number of pairs matched by the player
+        own_matches: Option<usize>,
+        /// (HOST ONLY) When pairs were matched over the answering phase
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AlarmMessage {
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+}
+
+/// Messages sent to the listeners who lack preexisting state to synchronize their state.
+///
+/// See [`UpdateMessage`] for explaination of these fields.
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum SyncMessage {
+    /// Face-down cards along with the ones turned over and matched so far
+    BoardAnnouncement {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        card_count: usize,
+        /// Remaining time to match the pairs
+        #[serde(flatten)]
+        countdown: Countdown,
+        revealed: Vec<(usize, TextOrMedia)>,
+        matched: Vec<(usize, usize)>,
+    },
+    AnswersResults {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        cards: Vec<TextOrMedia>,
+        own_matches: Option<usize>,
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+impl State {
+    pub fn play<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        self.send_board_announcements(
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            index,
+            count,
+        );
+    }
+
+    fn calculate_score(
+        full_duration: Duration,
+        taken_duration: Duration,
+        full_points_awarded: u64,
+    ) -> u64 {
+        (full_points_awarded as f64
+            * (1. - (taken_duration.as_secs_f64() / full_duration.as_secs_f64() / 2.)))
+            as u64
+    }
+
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
+    }
+
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// pairs and reveals are only accepted while the cards are shown and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// content of the card at the position
+    fn card(&self, card: usize) -> TextOrMedia {
+        let side = self.layout[card];
+        let pair = &self.config.pairs[side / 2];
+        if side.is_multiple_of(2) {
+            pair.first.clone()
+        } else {
+            pair.second.clone()
+        }
+    }
+
+    /// pair the card at the position belongs to
+    fn pair_of(&self, card: usize) -> usize {
+        self.layout[card] / 2
+    }
+
+    /// positions of the two cards of every matched pair
+    fn matched_cards(&self) -> Vec<(usize, usize)> {
+        self.matched
+            .keys()
+            .sorted()
+            .filter_map(|pair| {
+                self.layout
+                    .iter()
+                    .positions(|side| side / 2 == *pair)
+                    .collect_tuple()
+            })
+            .collect_vec()
+    }
+
+    fn send_board_announcements<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        mut schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        if self.change_state(SlideState::Unstarted, SlideState::Answers) {
+            self.answer_start = Some(clock.now());
+
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::BoardAnnouncement {
+                            index,
+                            count,
+                            title: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                            card_count: self.layout.len(),
+                            countdown: Countdown::new(clock, self.config.time_limit),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+
+            schedule_message(
+                AlarmMessage::ProceedFromSlideIntoSlide {
+                    index,
+                    to: SlideState::AnswersResults,
+                    generation: self.generation,
+                }
+                .into(),
+                self.config.time_limit,
+            )
+        }
+    }
+
+    fn change_state(&mut self, before: SlideState, after: SlideState) -> bool {
+        if self.state == before {
+            self.state = after;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// pair a bot submits, an unmatched pair whose cards were both turned over or two random
+    /// cards, `None` if it should be right but nothing can be matched yet
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        let (first, second) = if correct {
+            (0..self.config.pairs.len())
+                .filter(|pair| !self.matched.contains_key(pair))
+                .filter_map(|pair| {
+                    self.layout
+                        .iter()
+                        .positions(|side| side / 2 == pair)
+                        .collect_tuple::<(usize, usize)>()
+                })
+                .find(|(first, second)| {
+                    self.revealed.contains(first) && self.revealed.contains(second)
+                })?
+        } else {
+            (
+                rng.usize(..self.layout.len()),
+                rng.usize(..self.layout.len()),
+            )
+        };
+        Some(IncomingPlayerMessage::PairAnswer(first, second))
+    }
+
+    /// whether every card is being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
+    fn cards(&self) -> Vec<TextOrMedia> {
+        (0..self.layout.len())
+            .map(|card| self.card(card))
+            .collect_vec()
+    }
+
+    /// number of pairs the player matched first
+    fn matches_of(&self, watcher_id: Id) -> usize {
+        self.matched
+            .values()
+            .filter(|(id, _)| *id == watcher_id)
+            .count()
+    }
+
+    fn own_matches(&self, watcher_id: Id, watcher_kind: ValueKind) -> Option<usize> {
+        (watcher_kind == ValueKind::Player).then(|| self.matches_of(watcher_id))
+    }
+
+    fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) {
+        if self.change_state(SlideState::Answers, SlideState::AnswersResults) {
+            let cards = self.cards();
+
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            cards: cards.clone(),
+                            own_matches: self.own_matches(watcher_id, watcher_kind),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+        }
+    }
+
+    /// when pairs were matched, `None` until the cards are shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self.answer_start?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.matched.values().map(|(_, instant)| *instant),
+        ))
+    }
+
+    /// how long each player took to match their latest pair once the cards were shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.matched
+            .values()
+            .into_grouping_map_by(|(id, _)| *id)
+            .max_by_key(|_, (_, instant)| *instant)
+            .into_iter()
+            .map(|(id, (_, instant))| {
+                (id, instant.duration_since(answer_start).unwrap_or_default())
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// number of pairs every player who matched one got
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, usize)> + '_ {
+        self.matched
+            .values()
+            .map(|(id, _)| *id)
+            .unique()
+            .map(|id| (id, self.matches_of(id)))
+    }
+
+    fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+    ) {
+        let starting_instant = self.timer(clock);
+
+        leaderboard.add_scores(
+            &self
+                .matched
+                .values()
+                .map(|(id, instant)| {
+                    (
+                        *id,
+                        State::calculate_score(
+                            self.config.time_limit,
+                            instant.duration_since(starting_instant).unwrap_or_default(),
+                            self.config.points_awarded,
+                        ),
+                    )
+                })
+                .into_grouping_map_by(|(id, _)| *id)
+                .fold(0, |total, _, (_, score)| total + score)
+                .into_iter()
+                .into_grouping_map_by(|(id, _)| {
+                    let player_id = *id;
+                    match &team_manager {
+                        Some(team_manager) => team_manager.get_team(player_id).unwrap_or(player_id),
+                        None => player_id,
+                    }
+                })
+                .min_by_key(|_, (_, score)| *score)
+                .into_iter()
+                .map(|(id, (_, score))| (id, score))
+                .chain(
+                    {
+                        match &team_manager {
+                            Some(team_manager) => team_manager.all_ids(),
+                            None => watchers
+                                .present_players(tunnel_finder)
+                                .into_iter()
+                                .collect_vec(),
+                        }
+                    }
+                    .into_iter()
+                    .map(|id| (id, 0)),
+                )
+                .unique_by(|(id, _)| *id)
+                .collect_vec(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        _team_manager: Option<&TeamManager>,
+        _watchers: &Watchers,
+        _tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> SyncMessage {
+        match self.state {
+            SlideState::Unstarted | SlideState::Answers => SyncMessage::BoardAnnouncement {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                card_count: self.layout.len(),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
+                revealed: self
+                    .revealed
+                    .iter()
+                    .map(|card| (*card, self.card(*card)))
+                    .collect_vec(),
+                matched: self.matched_cards(),
+            },
+            SlideState::AnswersResults => SyncMessage::AnswersResults {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                cards: self.cards(),
+                own_matches: self.own_matches(watcher_id, watcher_kind),
+                timeline: self.host_timeline(watcher_kind),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        message: IncomingMessage,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> Result<bool, Error> {
+        match message {
+            IncomingMessage::Host(IncomingHostMessage::Next) => match self.state {
+                SlideState::Unstarted => {
+                    self.send_board_announcements(
+                        watchers,
+                        schedule_message,
+                        tunnel_finder,
+                        clock,
+                        index,
+                        count,
+                    );
+                }
+                SlideState::Answers => {
+                    self.send_answers_results(watchers, tunnel_finder);
+                }
+                SlideState::AnswersResults => {
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder, clock);
+                    return Ok(true);
+                }
+            },
+            IncomingMessage::Host(IncomingHostMessage::Index(card)) => {
+                if card >= self.layout.len() || self.matched.contains_key(&self.pair_of(card)) {
+                    return Err(Error::InvalidAnswer);
+                }
+                self.check_answer_window(clock)?;
+
+                self.revealed.insert(card);
+                watchers.announce(
+                    &UpdateMessage::CardRevealed {
+                        card,
+                        content: self.card(card),
+                    }
+                    .into(),
+                    tunnel_finder,
+                );
+            }
+            IncomingMessage::Player(IncomingPlayerMessage::PairAnswer(first, second)) => {
+                if first == second
+                    || !self.revealed.contains(&first)
+                    || !self.revealed.contains(&second)
+                {
+                    return Err(Error::InvalidAnswer);
+                }
+                self.check_answer_window(clock)?;
+
+                let at = clock.now();
+                let pair = self.pair_of(first);
+                let matched = pair == self.pair_of(second) && !self.matched.contains_key(&pair);
+
+                watchers.send_message(
+                    &game::UpdateMessage::AnswerRecorded { index, at }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+                watchers.send_message(
+                    &UpdateMessage::PairChecked {
+                        cards: (first, second),
+                        matched,
+                    }
+                    .into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+
+                if !matched {
+                    return Ok(false);
+                }
+
+                self.matched.insert(pair, (watcher_id, at));
+                watchers.announce(
+                    &UpdateMessage::PairMatched {
+                        cards: (first, second),
+                    }
+                    .into(),
+                    &tunnel_finder,
+                );
+
+                if self.matched.len() == self.config.pairs.len() {
+                    self.send_answers_results(watchers, &tunnel_finder);
+                }
+            }
+            _ => return Err(Error::WrongPhase),
+        };
+
+        Ok(false)
+    }
+
+    pub fn receive_alarm<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+        message: crate::AlarmMessage,
+    ) -> bool {
+        if let crate::AlarmMessage::MemoryMatch(AlarmMessage::ProceedFromSlideIntoSlide {
+            to: SlideState::AnswersResults,
+            generation,
+            ..
+        }) = message
+        {
+            if generation == self.generation {
+                self.send_answers_results(watchers, tunnel_finder);
+            }
+        }
+
+        false
+    }
+}
@@ -1,5 +1,14 @@
 pub mod config;
+pub mod convert;
+pub mod crossword;
+pub mod expression;
+pub mod intermission;
 pub mod media;
+pub mod memory_match;
 pub mod multiple_choice;
 pub mod order;
+pub mod plugin;
+pub mod preview;
+pub mod source;
 pub mod type_answer;
+pub mod typing;
@@ -10,15 +10,19 @@ use serde_with::skip_serializing_none;
 use web_time::SystemTime;
 
 use crate::{
+    clock::{Clock, Countdown},
     leaderboard::Leaderboard,
     session::Tunnel,
-    teams::TeamManager,
+    teams::{AnswerSplit, TeamManager},
     watcher::{Id, ValueKind, Watchers},
 };
 
 use super::{
-    super::game::{IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
-    config::TextOrMedia,
+    super::game::{self, Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{
+        announce_remaining_time, canonicalize_host_notes, host_notes, next_time_correction,
+        AnswerCountThrottle, AnswerTimeline, OwnAnswer, TextOrMedia, MAX_HOST_NOTES_LENGTH,
+    },
     media::Media,
 };
 
@@ -83,6 +87,10 @@ pub struct SlideConfig {
     /// Accompanying media
     #[garde(dive)]
     media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
     /// Time before answers get displayed
     #[garde(custom(|v, _| validate_introduce_question(v)))]
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
@@ -97,6 +105,10 @@ pub struct SlideConfig {
     /// Accompanying answers
     #[garde(length(max = MAX_ANSWER_COUNT))]
     answers: Vec<AnswerChoice>,
+    /// Overrides how the answers are shown to the members of a team for this question
+    #[garde(skip)]
+    #[serde(default)]
+    answer_split: Option<AnswerSplit>,
 }
 
 /// Presenting a multiple choice question that presents a question then the answers with optional accompanying media
@@ -114,15 +126,66 @@ pub struct State {
     answer_start: Option<SystemTime>,
     /// Stage of the slide
     state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+    /// answer counts sent to the host
+    #[serde(default)]
+    answer_count: AnswerCountThrottle,
 }
 
 impl SlideConfig {
-    pub fn to_state(&self) -> State {
+    pub fn new(
+        title: String,
+        introduce_question: Duration,
+        time_limit: Duration,
+        points_awarded: u64,
+        answers: Vec<AnswerChoice>,
+    ) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            introduce_question,
+            time_limit,
+            points_awarded,
+            answers,
+            answer_split: None,
+        }
+    }
+
+    /// trims the text of the question and its answers
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        for answer in &mut self.answers {
+            answer.content.canonicalize();
+        }
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    /// marks every answer as wrong, leaving only how players answered
+    pub fn clear_correct_answers(&mut self) {
+        for answer in &mut self.answers {
+            answer.correct = false;
+        }
+    }
+
+    pub fn to_state(&self, generation: u64) -> State {
         State {
             config: self.clone(),
             user_answers: HashMap::new(),
             answer_start: None,
             state: SlideState::Unstarted,
+            generation,
+            answer_count: AnswerCountThrottle::default(),
         }
     }
 }
@@ -151,32 +214,49 @@ pub enum UpdateMessage {
         question: String,
         /// Accompanying media
         media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
         /// Time before answers will be release
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
     },
     /// Announcements of the possible answers for the players to choose
     AnswersAnnouncement {
         /// Time before the answering phase ends
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
         /// Possible answers to choose from
         answers: Vec<PossiblyHidden<TextOrMedia>>,
     },
     /// (HOST ONLY): Number of players who answered the question
     AnswersCount(usize),
+    /// Time left to answer, sent now and then so devices don't drift apart on long questions
+    RemainingTime(Countdown),
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
         /// Same answers for the question displayed
         answers: Vec<TextOrMedia>,
         /// Correctness and statistics about the answers
         results: Vec<AnswerChoiceResult>,
+        /// (PLAYER ONLY) What the player answered and whether it was correct
+        own_answer: Option<OwnAnswer<usize>>,
+        /// (HOST ONLY) When answers arrived over the answering phase
+        timeline: Option<AnswerTimeline>,
     },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlarmMessage {
-    ProceedFromSlideIntoSlide { index: usize, to: SlideState },
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+    /// sends the host the answer count held back by [`AnswerCountThrottle`]
+    FlushAnswersCount { index: usize, generation: u64 },
+    /// broadcasts the time left to answer, see [`next_time_correction`]
+    CorrectRemainingTime { index: usize, generation: u64 },
 }
 
 /// Messages sent to the listeners who lack preexisting state to synchronize their state.
@@ -192,9 +272,10 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         /// Remaining time for the question to be displayed without its answers
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
     },
     /// Announcements of the possible answers for the players to choose
     AnswersAnnouncement {
@@ -202,11 +283,14 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         /// Remaining time before the answering phase ends
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
         answers: Vec<PossiblyHidden<TextOrMedia>>,
         answered_count: usize,
+        /// answer recorded for the watcher so far
+        answered: Option<usize>,
     },
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
@@ -214,8 +298,11 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         answers: Vec<TextOrMedia>,
         results: Vec<AnswerChoiceResult>,
+        own_answer: Option<OwnAnswer<usize>>,
+        timeline: Option<AnswerTimeline>,
     },
 }
 
@@ -234,6 +321,7 @@ pub struct AnswerChoiceResult {
 }
 
 impl State {
+    #[allow(clippy::too_many_arguments)]
     pub fn play<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -244,6 +332,7 @@ impl State {
         watchers: &Watchers,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
@@ -252,6 +341,7 @@ impl State {
             watchers,
             schedule_message,
             tunnel_finder,
+            clock,
             index,
             count,
         );
@@ -267,14 +357,40 @@ impl State {
             as u64
     }
 
-    fn start_timer(&mut self) {
-        self.answer_start = Some(SystemTime::now());
+    fn start_timer(&mut self, clock: &dyn Clock) {
+        self.answer_start = Some(clock.now());
     }
 
-    fn timer(&self) -> SystemTime {
-        self.answer_start.unwrap_or(SystemTime::now())
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
     }
 
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// answers are only accepted during the answering phase and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn answered_in_time(&self, instant: SystemTime) -> bool {
+        self.answer_deadline()
+            .is_some_and(|deadline| instant <= deadline)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn send_question_announcements<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -285,19 +401,25 @@ impl State {
         watchers: &Watchers,
         mut schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
         if self.change_state(SlideState::Unstarted, SlideState::Question) {
-            watchers.announce(
-                &UpdateMessage::QuestionAnnouncement {
-                    index,
-                    count,
-                    question: self.config.title.clone(),
-                    media: self.config.media.clone(),
-                    duration: self.config.introduce_question,
-                }
-                .into(),
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::QuestionAnnouncement {
+                            index,
+                            count,
+                            question: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            countdown: Countdown::new(clock, self.config.introduce_question),
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 &tunnel_finder,
             );
 
@@ -307,6 +429,7 @@ impl State {
                     watchers,
                     schedule_message,
                     tunnel_finder,
+                    clock,
                     index,
                 );
             } else {
@@ -314,6 +437,7 @@ impl State {
                     AlarmMessage::ProceedFromSlideIntoSlide {
                         index,
                         to: SlideState::Answers,
+                        generation: self.generation,
                     }
                     .into(),
                     self.config.introduce_question,
@@ -332,16 +456,17 @@ impl State {
         watchers: &Watchers,
         mut schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
     ) {
         if self.change_state(SlideState::Question, SlideState::Answers) {
-            self.start_timer();
+            self.start_timer(clock);
 
             watchers.announce_with(
                 |id, kind| {
                     Some(
                         UpdateMessage::AnswersAnnouncement {
-                            duration: self.config.time_limit,
+                            countdown: Countdown::new(clock, self.config.time_limit),
                             answers: self.get_answers_for_player(
                                 id,
                                 kind,
@@ -364,12 +489,14 @@ impl State {
                                 {
                                     match &team_manager {
                                         Some(team_manager) => team_manager
-                                            .team_index(id, |id| watchers.has_watcher(id))
+                                            .team_index(id, |id| {
+                                                watchers.is_alive(id, &tunnel_finder)
+                                            })
                                             .unwrap_or(0),
                                         None => 0,
                                     }
                                 },
-                                team_manager.is_some(),
+                                self.answer_split(team_manager),
                             ),
                         }
                         .into(),
@@ -382,10 +509,22 @@ impl State {
                 AlarmMessage::ProceedFromSlideIntoSlide {
                     index,
                     to: SlideState::AnswersResults,
+                    generation: self.generation,
                 }
                 .into(),
                 self.config.time_limit,
-            )
+            );
+
+            if let Some(delay) = next_time_correction(self.config.time_limit) {
+                schedule_message(
+                    AlarmMessage::CorrectRemainingTime {
+                        index,
+                        generation: self.generation,
+                    }
+                    .into(),
+                    delay,
+                );
+            }
         }
     }
 
@@ -403,6 +542,36 @@ impl State {
         self.state
     }
 
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// answer a bot submits, picked among the right or wrong choices
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        let matching = self
+            .config
+            .answers
+            .iter()
+            .positions(|answer| answer.correct == correct)
+            .collect_vec();
+        let choices = if matching.is_empty() {
+            (0..self.config.answers.len()).collect_vec()
+        } else {
+            matching
+        };
+        rng.choice(choices).map(IncomingPlayerMessage::IndexAnswer)
+    }
+
+    /// whether the correct answers are being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
     fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
         watchers: &Watchers,
@@ -414,54 +583,112 @@ impl State {
                 .iter()
                 .map(|(_, (answer, _))| *answer)
                 .counts();
-            watchers.announce(
-                &UpdateMessage::AnswersResults {
-                    answers: self
-                        .config
-                        .answers
-                        .iter()
-                        .map(|a| a.content.clone())
-                        .collect_vec(),
-                    results: self
-                        .config
-                        .answers
-                        .iter()
-                        .enumerate()
-                        .map(|(i, a)| AnswerChoiceResult {
-                            correct: a.correct,
-                            count: *answer_count.get(&i).unwrap_or(&0),
-                        })
-                        .collect_vec(),
-                }
-                .into(),
+            let answers = self
+                .config
+                .answers
+                .iter()
+                .map(|a| a.content.clone())
+                .collect_vec();
+            let results = self
+                .config
+                .answers
+                .iter()
+                .enumerate()
+                .map(|(i, a)| AnswerChoiceResult {
+                    correct: a.correct,
+                    count: *answer_count.get(&i).unwrap_or(&0),
+                })
+                .collect_vec();
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            answers: answers.clone(),
+                            results: results.clone(),
+                            own_answer: self.own_answer(watcher_id),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 tunnel_finder,
             );
         }
     }
 
+    /// when answers arrived, `None` until answers are shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self
+            .answer_start
+            .filter(|_| matches!(self.state, SlideState::Answers | SlideState::AnswersResults))?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.user_answers.values().map(|(_, instant)| *instant),
+        ))
+    }
+
+    /// how long each player took to answer once answers were shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.user_answers
+            .iter()
+            .map(|(id, (_, instant))| {
+                (
+                    *id,
+                    instant.duration_since(answer_start).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// what every player answered and whether it earns points
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, OwnAnswer<usize>)> + '_ {
+        self.user_answers
+            .keys()
+            .filter_map(|id| Some((*id, self.own_answer(*id)?)))
+    }
+
+    /// what the watcher answered and whether it earns points
+    fn own_answer(&self, watcher_id: Id) -> Option<OwnAnswer<usize>> {
+        let (answer, instant) = self.user_answers.get(&watcher_id)?;
+        Some(OwnAnswer {
+            answer: *answer,
+            correct: self.config.answers.get(*answer).is_some_and(|x| x.correct)
+                && self.answered_in_time(*instant),
+        })
+    }
+
     fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
         leaderboard: &mut Leaderboard,
         watchers: &Watchers,
         team_manager: Option<&TeamManager>,
         tunnel_finder: F,
+        clock: &dyn Clock,
     ) {
-        let starting_instant = self.timer();
+        let starting_instant = self.timer(clock);
 
         leaderboard.add_scores(
             &self
                 .user_answers
                 .iter()
                 .map(|(id, (answer, instant))| {
-                    let correct = self.config.answers.get(*answer).is_some_and(|x| x.correct);
+                    let correct = self.config.answers.get(*answer).is_some_and(|x| x.correct)
+                        && self.answered_in_time(*instant);
                     (
                         *id,
                         if correct {
                             State::calculate_score(
                                 self.config.time_limit,
-                                instant
-                                    .duration_since(starting_instant)
-                                    .expect("future is past the past"),
+                                instant.duration_since(starting_instant).unwrap_or_default(),
                                 self.config.points_awarded,
                             )
                         } else {
@@ -484,9 +711,8 @@ impl State {
                         match &team_manager {
                             Some(team_manager) => team_manager.all_ids(),
                             None => watchers
-                                .specific_vec(ValueKind::Player, tunnel_finder)
+                                .present_players(tunnel_finder)
                                 .into_iter()
-                                .map(|(x, _, _)| x)
                                 .collect_vec(),
                         }
                     }
@@ -498,19 +724,27 @@ impl State {
         );
     }
 
+    /// how the answers are shown to teams on this slide, `None` outside of team games
+    fn answer_split(&self, team_manager: Option<&TeamManager>) -> Option<AnswerSplit> {
+        team_manager.map(|team_manager| {
+            self.config
+                .answer_split
+                .unwrap_or(team_manager.answer_split)
+        })
+    }
+
     fn get_answers_for_player(
         &self,
         _id: Id,
         watcher_kind: ValueKind,
         team_size: usize,
         team_index: usize,
-        is_team: bool,
+        answer_split: Option<AnswerSplit>,
     ) -> Vec<PossiblyHidden<TextOrMedia>> {
         match watcher_kind {
             ValueKind::Host | ValueKind::Unassigned => {
-                if is_team {
-                    std::iter::repeat(PossiblyHidden::Hidden)
-                        .take(self.config.answers.len())
+                if answer_split.is_some() {
+                    std::iter::repeat_n(PossiblyHidden::Hidden, self.config.answers.len())
                         .collect_vec()
                 } else {
                     self.config
@@ -522,6 +756,25 @@ impl State {
             }
             ValueKind::Player => match self.config.answers.len() {
                 0 => Vec::new(),
+                _ if matches!(answer_split, None | Some(AnswerSplit::Shared)) => self
+                    .config
+                    .answers
+                    .iter()
+                    .map(|answer_choice| PossiblyHidden::Visible(answer_choice.content.clone()))
+                    .collect_vec(),
+                answer_count if answer_split == Some(AnswerSplit::Captain) => {
+                    if team_index == 0 {
+                        self.config
+                            .answers
+                            .iter()
+                            .map(|answer_choice| {
+                                PossiblyHidden::Visible(answer_choice.content.clone())
+                            })
+                            .collect_vec()
+                    } else {
+                        std::iter::repeat_n(PossiblyHidden::Hidden, answer_count).collect_vec()
+                    }
+                }
                 answer_count => {
                     let adjusted_team_index = team_index % answer_count;
 
@@ -542,6 +795,7 @@ impl State {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
         watcher_id: Id,
@@ -549,6 +803,7 @@ impl State {
         team_manager: Option<&TeamManager>,
         watchers: &Watchers,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) -> SyncMessage {
@@ -558,18 +813,31 @@ impl State {
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
-                duration: self.config.introduce_question
-                    - self.timer().elapsed().expect("system clock went backwards"),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.introduce_question.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
             },
             SlideState::Answers => SyncMessage::AnswersAnnouncement {
                 index,
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
-                duration: {
-                    self.config.time_limit
-                        - self.timer().elapsed().expect("system clock went backwards")
-                },
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(clock, {
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    )
+                }),
                 answers: self.get_answers_for_player(
                     watcher_id,
                     watcher_kind,
@@ -590,22 +858,22 @@ impl State {
                     {
                         match &team_manager {
                             Some(team_manager) => team_manager
-                                .team_index(watcher_id, |id| watchers.has_watcher(id))
+                                .team_index(watcher_id, |id| watchers.is_alive(id, &tunnel_finder))
                                 .unwrap_or(0),
                             None => 0,
                         }
                     },
-                    team_manager.is_some(),
+                    self.answer_split(team_manager),
                 ),
                 answered_count: {
-                    let left_set: HashSet<_> = watchers
-                        .specific_vec(ValueKind::Player, &tunnel_finder)
-                        .iter()
-                        .map(|(w, _, _)| w.to_owned())
-                        .collect();
+                    let left_set = watchers.present_players(&tunnel_finder);
                     let right_set: HashSet<_> = self.user_answers.keys().copied().collect();
                     left_set.intersection(&right_set).count()
                 },
+                answered: self
+                    .user_answers
+                    .get(&watcher_id)
+                    .map(|(answer, _)| *answer),
             },
             SlideState::AnswersResults => {
                 let answer_count = self
@@ -619,6 +887,7 @@ impl State {
                     count,
                     question: self.config.title.clone(),
                     media: self.config.media.clone(),
+                    host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
                     answers: self
                         .config
                         .answers
@@ -635,11 +904,14 @@ impl State {
                             count: *answer_count.get(&i).unwrap_or(&0),
                         })
                         .collect_vec(),
+                    own_answer: self.own_answer(watcher_id),
+                    timeline: self.host_timeline(watcher_kind),
                 }
             }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_message<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -653,9 +925,10 @@ impl State {
         team_manager: Option<&TeamManager>,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         match message {
             IncomingMessage::Host(IncomingHostMessage::Next) => match self.state() {
                 SlideState::Unstarted => {
@@ -664,6 +937,7 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                         count,
                     );
@@ -674,42 +948,120 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                     );
                 }
                 SlideState::Answers => self.send_answers_results(watchers, tunnel_finder),
                 SlideState::AnswersResults => {
-                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder);
-                    return true;
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder, clock);
+                    return Ok(true);
                 }
             },
-            IncomingMessage::Player(IncomingPlayerMessage::IndexAnswer(v))
-                if v < self.config.answers.len() =>
-            {
-                self.user_answers.insert(watcher_id, (v, SystemTime::now()));
-                let left_set: HashSet<_> = watchers
-                    .specific_vec(ValueKind::Player, &tunnel_finder)
-                    .iter()
-                    .map(|(w, _, _)| w.to_owned())
-                    .collect();
+            IncomingMessage::Player(IncomingPlayerMessage::IndexAnswer(v)) => {
+                if v >= self.config.answers.len() {
+                    return Err(Error::InvalidAnswer);
+                }
+                // the captain is the first connected member, so a team keeps one if theirs left
+                if self.answer_split(team_manager) == Some(AnswerSplit::Captain)
+                    && team_manager.and_then(|team_manager| {
+                        team_manager
+                            .team_index(watcher_id, |id| watchers.is_alive(id, &tunnel_finder))
+                    }) != Some(0)
+                {
+                    return Err(Error::NotAllowed);
+                }
+                self.check_answer_window(clock)?;
+
+                let at = clock.now();
+                self.user_answers.insert(watcher_id, (v, at));
+                watchers.send_message(
+                    &game::UpdateMessage::AnswerRecorded { index, at }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+                let left_set = watchers.present_players(&tunnel_finder);
                 let right_set: HashSet<_> = self.user_answers.keys().copied().collect();
                 if left_set.is_subset(&right_set) {
                     self.send_answers_results(watchers, &tunnel_finder);
                 } else {
-                    watchers.announce_specific(
-                        ValueKind::Host,
-                        &UpdateMessage::AnswersCount(left_set.intersection(&right_set).count())
-                            .into(),
+                    self.announce_answers_count(
+                        left_set.intersection(&right_set).count(),
+                        left_set.len(),
+                        watchers,
+                        schedule_message,
                         &tunnel_finder,
+                        clock,
+                        index,
                     );
                 }
             }
-            _ => (),
+            _ => return Err(Error::WrongPhase),
         };
 
-        false
+        Ok(false)
+    }
+
+    /// number of present players who answered
+    pub fn answered_count<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> usize {
+        let players = watchers.present_players(tunnel_finder);
+        self.user_answers
+            .keys()
+            .filter(|id| players.contains(id))
+            .count()
+    }
+
+    /// updates a reconnecting host would otherwise be missing, the latest answer count might
+    /// have been held back
+    pub fn catch_up_updates<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_kind: ValueKind,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> Vec<crate::UpdateMessage> {
+        if watcher_kind != ValueKind::Host || self.state != SlideState::Answers {
+            return Vec::new();
+        }
+        vec![UpdateMessage::AnswersCount(self.answered_count(watchers, tunnel_finder)).into()]
     }
 
+    /// tells the host how many players answered, batched through [`AnswerCountThrottle`]
+    #[allow(clippy::too_many_arguments)]
+    fn announce_answers_count<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        answered: usize,
+        players: usize,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+    ) {
+        self.answer_count.announce(
+            answered,
+            players,
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            |answered| UpdateMessage::AnswersCount(answered).into(),
+            AlarmMessage::FlushAnswersCount {
+                index,
+                generation: self.generation,
+            }
+            .into(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_alarm<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -721,15 +1073,66 @@ impl State {
         team_manager: Option<&TeamManager>,
         schedule_message: &mut S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         message: crate::AlarmMessage,
         index: usize,
         _count: usize,
     ) -> bool {
+        if let crate::AlarmMessage::MultipleChoice(AlarmMessage::CorrectRemainingTime {
+            index,
+            generation,
+        }) = &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                if let Some(delay) = announce_remaining_time(
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    self.config.time_limit,
+                    self.timer(clock),
+                    |countdown| UpdateMessage::RemainingTime(countdown).into(),
+                ) {
+                    schedule_message(
+                        AlarmMessage::CorrectRemainingTime {
+                            index: *index,
+                            generation: *generation,
+                        }
+                        .into(),
+                        delay,
+                    );
+                }
+            }
+            return false;
+        }
+
+        if let crate::AlarmMessage::MultipleChoice(AlarmMessage::FlushAnswersCount {
+            generation,
+            ..
+        }) = &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                let answered = self.answered_count(watchers, &tunnel_finder);
+                self.answer_count.announce_flush(
+                    answered,
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    |answered| UpdateMessage::AnswersCount(answered).into(),
+                );
+            }
+            return false;
+        }
+
         if let crate::AlarmMessage::MultipleChoice(AlarmMessage::ProceedFromSlideIntoSlide {
             index: _,
             to,
+            generation,
         }) = message
         {
+            if generation != self.generation {
+                return false;
+            }
+
             match to {
                 SlideState::Answers => {
                     self.send_answers_announcements(
@@ -737,6 +1140,7 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                     );
                 }
@@ -10,6 +10,7 @@ use serde_with::skip_serializing_none;
 use web_time::SystemTime;
 
 use crate::{
+    clock::{Clock, Countdown},
     leaderboard::Leaderboard,
     session::Tunnel,
     teams::TeamManager,
@@ -17,7 +18,11 @@ use crate::{
 };
 
 use super::{
-    super::game::{IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    super::game::{self, Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{
+        announce_remaining_time, canonicalize_host_notes, host_notes, next_time_correction,
+        AnswerCountThrottle, AnswerTimeline, OwnAnswer, MAX_HOST_NOTES_LENGTH,
+    },
     media::Media,
 };
 
@@ -94,6 +99,10 @@ pub struct SlideConfig {
     /// Accompanying media
     #[garde(dive)]
     media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
     /// Time before the question is displayed
     #[garde(custom(|v, _| validate_introduce_question(v)))]
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
@@ -113,6 +122,10 @@ pub struct SlideConfig {
     /// From and to labels for the order
     #[garde(dive)]
     axis_labels: AxisLabels,
+    /// polls how players order the answers, there is no correct order to reveal
+    #[garde(skip)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    survey: bool,
 }
 
 /// Presenting a multiple choice question that presents a question then the answers with optional accompanying media
@@ -131,16 +144,63 @@ pub struct State {
     answer_start: Option<SystemTime>,
     /// Stage of the slide
     state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+    /// answer counts sent to the host
+    #[serde(default)]
+    answer_count: AnswerCountThrottle,
+    /// drives the shuffle of the answers
+    #[serde(default)]
+    seed: u64,
+}
+
+impl AxisLabels {
+    fn canonicalize(&mut self) {
+        for label in [&mut self.from, &mut self.to] {
+            *label = label
+                .as_deref()
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .map(str::to_owned);
+        }
+    }
 }
 
 impl SlideConfig {
-    pub fn to_state(&self) -> State {
+    /// trims the text of the question, its answers and labels, dropping labels left empty
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        for answer in &mut self.answers {
+            *answer = answer.trim().to_owned();
+        }
+        self.axis_labels.canonicalize();
+    }
+
+    /// turns the slide into a poll, results show how players ordered the answers instead
+    pub fn clear_correct_answers(&mut self) {
+        self.survey = true;
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    pub fn to_state(&self, generation: u64, seed: u64) -> State {
         State {
             config: self.clone(),
             shuffled_answers: Vec::new(),
             user_answers: HashMap::new(),
             answer_start: None,
             state: SlideState::Unstarted,
+            generation,
+            answer_count: AnswerCountThrottle::default(),
+            seed,
         }
     }
 }
@@ -160,9 +220,11 @@ pub enum UpdateMessage {
         question: String,
         /// Accompanying media
         media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
         /// Time before answers will be release
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
     },
     /// Announcement of the question with its answers
     AnswersAnnouncement {
@@ -171,23 +233,40 @@ pub enum UpdateMessage {
         /// Answers in a shuffled order
         answers: Vec<String>,
         /// Time where players can answer the question
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
     },
     /// (HOST ONLY): Number of players who answered the question
     AnswersCount(usize),
+    /// Time left to answer, sent now and then so devices don't drift apart on long questions
+    RemainingTime(Countdown),
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
-        /// Correct answers
+        /// Correct answers, in the order they were shown to players for a survey
         answers: Vec<String>,
         /// Statistics of how many players got it right and wrong
         results: (usize, usize),
+        /// (SURVEY ONLY) For each answer, how many players put it at each position
+        distribution: Option<Vec<Vec<usize>>>,
+        /// (PLAYER ONLY) What the player answered and whether it was correct
+        own_answer: Option<OwnAnswer<Vec<String>>>,
+        /// (HOST ONLY) When answers arrived over the answering phase
+        timeline: Option<AnswerTimeline>,
     },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlarmMessage {
-    ProceedFromSlideIntoSlide { index: usize, to: SlideState },
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+    /// sends the host the answer count held back by [`AnswerCountThrottle`]
+    FlushAnswersCount { index: usize, generation: u64 },
+    /// broadcasts the time left to answer, see [`next_time_correction`]
+    CorrectRemainingTime { index: usize, generation: u64 },
 }
 
 /// Messages sent to the listeners who lack preexisting state to synchronize their state.
@@ -203,9 +282,10 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         /// Remaining time for the question to be displayed without its answers
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
     },
     /// Announcement of the question with its answers
     AnswersAnnouncement {
@@ -214,10 +294,13 @@ pub enum SyncMessage {
         question: String,
         axis_labels: AxisLabels,
         media: Option<Media>,
+        host_notes: Option<String>,
         answers: Vec<String>,
         /// Time where players can answer the question
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
+        /// answer recorded for the watcher so far
+        answered: Option<Vec<String>>,
     },
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
@@ -226,8 +309,12 @@ pub enum SyncMessage {
         question: String,
         axis_labels: AxisLabels,
         media: Option<Media>,
+        host_notes: Option<String>,
         answers: Vec<String>,
         results: (usize, usize),
+        distribution: Option<Vec<Vec<usize>>>,
+        own_answer: Option<OwnAnswer<Vec<String>>>,
+        timeline: Option<AnswerTimeline>,
     },
 }
 
@@ -241,10 +328,18 @@ impl State {
         watchers: &Watchers,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
-        self.send_question_announcements(watchers, schedule_message, tunnel_finder, index, count);
+        self.send_question_announcements(
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            index,
+            count,
+        );
     }
 
     fn calculate_score(
@@ -257,12 +352,37 @@ impl State {
             as u64
     }
 
-    fn start_timer(&mut self) {
-        self.answer_start = Some(SystemTime::now());
+    fn start_timer(&mut self, clock: &dyn Clock) {
+        self.answer_start = Some(clock.now());
     }
 
-    fn timer(&self) -> SystemTime {
-        self.answer_start.unwrap_or(SystemTime::now())
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
+    }
+
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// answers are only accepted during the answering phase and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn answered_in_time(&self, instant: SystemTime) -> bool {
+        self.answer_deadline()
+            .is_some_and(|deadline| instant <= deadline)
     }
 
     fn send_question_announcements<
@@ -274,19 +394,25 @@ impl State {
         watchers: &Watchers,
         mut schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
         if self.change_state(SlideState::Unstarted, SlideState::Question) {
-            watchers.announce(
-                &UpdateMessage::QuestionAnnouncement {
-                    index,
-                    count,
-                    question: self.config.title.clone(),
-                    media: self.config.media.clone(),
-                    duration: self.config.introduce_question,
-                }
-                .into(),
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::QuestionAnnouncement {
+                            index,
+                            count,
+                            question: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            countdown: Countdown::new(clock, self.config.introduce_question),
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 &tunnel_finder,
             );
 
@@ -294,6 +420,7 @@ impl State {
                 self.send_answers_announcements(
                     watchers,
                     tunnel_finder,
+                    clock,
                     schedule_message,
                     index,
                     count,
@@ -303,6 +430,7 @@ impl State {
                     AlarmMessage::ProceedFromSlideIntoSlide {
                         index,
                         to: SlideState::Answers,
+                        generation: self.generation,
                     }
                     .into(),
                     self.config.introduce_question,
@@ -319,21 +447,22 @@ impl State {
         &mut self,
         watchers: &Watchers,
         tunnel_finder: F,
+        clock: &dyn Clock,
         mut schedule_message: S,
         index: usize,
         _count: usize,
     ) {
         if self.change_state(SlideState::Question, SlideState::Answers) {
             self.shuffled_answers.clone_from(&self.config.answers);
-            fastrand::shuffle(&mut self.shuffled_answers);
+            fastrand::Rng::with_seed(self.seed).shuffle(&mut self.shuffled_answers);
 
-            self.start_timer();
+            self.start_timer(clock);
 
             watchers.announce(
                 &UpdateMessage::AnswersAnnouncement {
                     axis_labels: self.config.axis_labels.clone(),
                     answers: self.shuffled_answers.clone(),
-                    duration: self.config.time_limit,
+                    countdown: Countdown::new(clock, self.config.time_limit),
                 }
                 .into(),
                 tunnel_finder,
@@ -343,10 +472,22 @@ impl State {
                 AlarmMessage::ProceedFromSlideIntoSlide {
                     index,
                     to: SlideState::AnswersResults,
+                    generation: self.generation,
                 }
                 .into(),
                 self.config.time_limit,
             );
+
+            if let Some(delay) = next_time_correction(self.config.time_limit) {
+                schedule_message(
+                    AlarmMessage::CorrectRemainingTime {
+                        index,
+                        generation: self.generation,
+                    }
+                    .into(),
+                    delay,
+                );
+            }
         }
     }
 
@@ -364,52 +505,173 @@ impl State {
         self.state
     }
 
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// answer a bot submits, the correct order or a shuffled one
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        let mut answer = self.config.answers.clone();
+        // answers all alike can't be put in a wrong order
+        let different = answer.iter().position(|a| *a != answer[0]);
+        if let (false, Some(different)) = (correct, different) {
+            rng.shuffle(&mut answer);
+            if answer == self.config.answers {
+                answer.swap(0, different);
+            }
+        }
+        Some(IncomingPlayerMessage::StringArrayAnswer(answer))
+    }
+
+    /// whether the correct answers are being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
     fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
         watchers: &Watchers,
         tunnel_finder: F,
     ) {
         if self.change_state(SlideState::Answers, SlideState::AnswersResults) {
-            let correct_count = self
-                .user_answers
-                .iter()
-                .filter(|(_, (answers, _))| answers == &self.config.answers)
-                .count();
-
-            watchers.announce(
-                &UpdateMessage::AnswersResults {
-                    answers: self.config.answers.iter().cloned().collect_vec(),
-                    results: (correct_count, self.user_answers.len() - correct_count),
-                }
-                .into(),
+            let results = self.results();
+            let distribution = self.distribution();
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            answers: self.revealed_answers().to_vec(),
+                            results,
+                            distribution: distribution.clone(),
+                            own_answer: self.own_answer(watcher_id),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 tunnel_finder,
             );
         }
     }
 
+    /// when answers arrived, `None` until answers are shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self
+            .answer_start
+            .filter(|_| matches!(self.state, SlideState::Answers | SlideState::AnswersResults))?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.user_answers.values().map(|(_, instant)| *instant),
+        ))
+    }
+
+    /// how long each player took to answer once answers were shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.user_answers
+            .iter()
+            .map(|(id, (_, instant))| {
+                (
+                    *id,
+                    instant.duration_since(answer_start).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// what every player answered and whether it earns points
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, OwnAnswer<Vec<String>>)> + '_ {
+        self.user_answers
+            .keys()
+            .filter_map(|id| Some((*id, self.own_answer(*id)?)))
+    }
+
+    /// what the watcher answered and whether it earns points
+    fn own_answer(&self, watcher_id: Id) -> Option<OwnAnswer<Vec<String>>> {
+        let (answer, instant) = self.user_answers.get(&watcher_id)?;
+        Some(OwnAnswer {
+            answer: answer.clone(),
+            correct: !self.config.survey
+                && answer == &self.config.answers
+                && self.answered_in_time(*instant),
+        })
+    }
+
+    /// answers shown with the results, a survey keeps the order players saw so none looks right
+    fn revealed_answers(&self) -> &[String] {
+        if self.config.survey {
+            &self.shuffled_answers
+        } else {
+            &self.config.answers
+        }
+    }
+
+    /// how many players got the order right and wrong, nobody does in a survey
+    fn results(&self) -> (usize, usize) {
+        let correct_count = self
+            .user_answers
+            .iter()
+            .filter(|(_, (answers, _))| !self.config.survey && answers == &self.config.answers)
+            .count();
+        (correct_count, self.user_answers.len() - correct_count)
+    }
+
+    /// for each of the revealed answers, how many players put it at each position, only for a
+    /// survey
+    fn distribution(&self) -> Option<Vec<Vec<usize>>> {
+        self.config.survey.then(|| {
+            self.revealed_answers()
+                .iter()
+                .map(|answer| {
+                    (0..self.config.answers.len())
+                        .map(|position| {
+                            self.user_answers
+                                .values()
+                                .filter(|(order, _)| order.get(position) == Some(answer))
+                                .count()
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
     fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
         leaderboard: &mut Leaderboard,
         watchers: &Watchers,
         team_manager: Option<&TeamManager>,
         tunnel_finder: F,
+        clock: &dyn Clock,
     ) {
-        let starting_instant = self.timer();
+        let starting_instant = self.timer(clock);
 
         leaderboard.add_scores(
             &self
                 .user_answers
                 .iter()
                 .map(|(id, (answers, instant))| {
-                    let correct = answers == &self.config.answers;
+                    let correct =
+                        answers == &self.config.answers && self.answered_in_time(*instant);
                     (
                         *id,
                         if correct {
                             State::calculate_score(
                                 self.config.time_limit,
-                                instant
-                                    .duration_since(starting_instant)
-                                    .expect("future is past the past"),
+                                instant.duration_since(starting_instant).unwrap_or_default(),
                                 self.config.points_awarded,
                             )
                         } else {
@@ -432,9 +694,8 @@ impl State {
                         match &team_manager {
                             Some(team_manager) => team_manager.all_ids(),
                             None => watchers
-                                .specific_vec(ValueKind::Player, tunnel_finder)
+                                .present_players(tunnel_finder)
                                 .into_iter()
-                                .map(|(x, _, _)| x)
                                 .collect_vec(),
                         }
                     }
@@ -446,13 +707,15 @@ impl State {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
-        _watcher_id: Id,
-        _watcher_kind: ValueKind,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
         _team_manager: Option<&TeamManager>,
         _watchers: &Watchers,
         _tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) -> SyncMessage {
@@ -462,8 +725,16 @@ impl State {
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
-                duration: self.config.introduce_question
-                    - self.timer().elapsed().expect("system clock went backwards"),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.introduce_question.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
             },
             SlideState::Answers => SyncMessage::AnswersAnnouncement {
                 index,
@@ -471,9 +742,21 @@ impl State {
                 question: self.config.title.clone(),
                 axis_labels: self.config.axis_labels.clone(),
                 media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
                 answers: self.shuffled_answers.clone(),
-                duration: self.config.time_limit
-                    - self.timer().elapsed().expect("system clock went backwards"),
+                answered: self
+                    .user_answers
+                    .get(&watcher_id)
+                    .map(|(answer, _)| answer.clone()),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
             },
             SlideState::AnswersResults => SyncMessage::AnswersResults {
                 index,
@@ -481,19 +764,17 @@ impl State {
                 question: self.config.title.clone(),
                 axis_labels: self.config.axis_labels.clone(),
                 media: self.config.media.clone(),
-                answers: self.config.answers.clone(),
-                results: {
-                    let correct_count = self
-                        .user_answers
-                        .iter()
-                        .filter(|(_, (answers, _))| answers == &self.config.answers)
-                        .count();
-                    (correct_count, self.user_answers.len() - correct_count)
-                },
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                answers: self.revealed_answers().to_vec(),
+                results: self.results(),
+                distribution: self.distribution(),
+                own_answer: self.own_answer(watcher_id),
+                timeline: self.host_timeline(watcher_kind),
             },
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_message<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -507,9 +788,10 @@ impl State {
         team_manager: Option<&TeamManager>,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         match message {
             IncomingMessage::Host(IncomingHostMessage::Next) => match self.state() {
                 SlideState::Unstarted => {
@@ -517,6 +799,7 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                         count,
                     );
@@ -525,6 +808,7 @@ impl State {
                     self.send_answers_announcements(
                         watchers,
                         tunnel_finder,
+                        clock,
                         schedule_message,
                         index,
                         count,
@@ -534,35 +818,102 @@ impl State {
                     self.send_answers_results(watchers, tunnel_finder);
                 }
                 SlideState::AnswersResults => {
-                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder);
-                    return true;
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder, clock);
+                    return Ok(true);
                 }
             },
             IncomingMessage::Player(IncomingPlayerMessage::StringArrayAnswer(v)) => {
-                self.user_answers.insert(watcher_id, (v, SystemTime::now()));
-                let left_set: HashSet<_> = watchers
-                    .specific_vec(ValueKind::Player, &tunnel_finder)
-                    .iter()
-                    .map(|(w, _, _)| w.to_owned())
-                    .collect();
+                self.check_answer_window(clock)?;
+
+                let at = clock.now();
+                self.user_answers.insert(watcher_id, (v, at));
+                watchers.send_message(
+                    &game::UpdateMessage::AnswerRecorded { index, at }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+                let left_set = watchers.present_players(&tunnel_finder);
                 let right_set: HashSet<_> = self.user_answers.keys().copied().collect();
                 if left_set.is_subset(&right_set) {
                     self.send_answers_results(watchers, &tunnel_finder);
                 } else {
-                    watchers.announce_specific(
-                        ValueKind::Host,
-                        &UpdateMessage::AnswersCount(left_set.intersection(&right_set).count())
-                            .into(),
+                    self.announce_answers_count(
+                        left_set.intersection(&right_set).count(),
+                        left_set.len(),
+                        watchers,
+                        schedule_message,
                         &tunnel_finder,
+                        clock,
+                        index,
                     );
                 }
             }
-            _ => (),
+            _ => return Err(Error::WrongPhase),
         };
 
-        false
+        Ok(false)
+    }
+
+    /// number of present players who answered
+    pub fn answered_count<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> usize {
+        let players = watchers.present_players(tunnel_finder);
+        self.user_answers
+            .keys()
+            .filter(|id| players.contains(id))
+            .count()
     }
 
+    /// updates a reconnecting host would otherwise be missing, the latest answer count might
+    /// have been held back
+    pub fn catch_up_updates<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_kind: ValueKind,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> Vec<crate::UpdateMessage> {
+        if watcher_kind != ValueKind::Host || self.state != SlideState::Answers {
+            return Vec::new();
+        }
+        vec![UpdateMessage::AnswersCount(self.answered_count(watchers, tunnel_finder)).into()]
+    }
+
+    /// tells the host how many players answered, batched through [`AnswerCountThrottle`]
+    #[allow(clippy::too_many_arguments)]
+    fn announce_answers_count<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        answered: usize,
+        players: usize,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+    ) {
+        self.answer_count.announce(
+            answered,
+            players,
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            |answered| UpdateMessage::AnswersCount(answered).into(),
+            AlarmMessage::FlushAnswersCount {
+                index,
+                generation: self.generation,
+            }
+            .into(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_alarm<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -574,20 +925,70 @@ impl State {
         _team_manager: Option<&TeamManager>,
         schedule_message: &mut S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         message: crate::AlarmMessage,
         index: usize,
         count: usize,
     ) -> bool {
+        if let crate::AlarmMessage::Order(AlarmMessage::CorrectRemainingTime {
+            index,
+            generation,
+        }) = &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                if let Some(delay) = announce_remaining_time(
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    self.config.time_limit,
+                    self.timer(clock),
+                    |countdown| UpdateMessage::RemainingTime(countdown).into(),
+                ) {
+                    schedule_message(
+                        AlarmMessage::CorrectRemainingTime {
+                            index: *index,
+                            generation: *generation,
+                        }
+                        .into(),
+                        delay,
+                    );
+                }
+            }
+            return false;
+        }
+
+        if let crate::AlarmMessage::Order(AlarmMessage::FlushAnswersCount { generation, .. }) =
+            &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                let answered = self.answered_count(watchers, &tunnel_finder);
+                self.answer_count.announce_flush(
+                    answered,
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    |answered| UpdateMessage::AnswersCount(answered).into(),
+                );
+            }
+            return false;
+        }
+
         if let crate::AlarmMessage::Order(AlarmMessage::ProceedFromSlideIntoSlide {
             index: _,
             to,
+            generation,
         }) = message
         {
+            if generation != self.generation {
+                return false;
+            }
+
             match to {
                 SlideState::Answers => {
                     self.send_answers_announcements(
                         watchers,
                         tunnel_finder,
+                        clock,
                         schedule_message,
                         index,
                         count,
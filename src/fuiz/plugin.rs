@@ -0,0 +1,387 @@
+//! Extension point letting downstream crates add their own slide types.
+//!
+//! A slide type is registered once through [`register`] under a unique kind, quizzes then refer
+//! to it with [`super::config::SlideConfig::Custom`]. Custom slides talk to the game through a
+//! [`SlideContext`] instead of the tunnels themselves, their messages reach clients wrapped in
+//! [`CustomMessage`].
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{LazyLock, PoisonError, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, SystemTime};
+
+use crate::{
+    clock::Clock,
+    game::{Error, IncomingMessage},
+    leaderboard::Leaderboard,
+    session::Tunnel,
+    watcher::{Id, ValueKind, Watchers},
+};
+
+/// What a custom slide can do while handling an event
+pub trait SlideContext {
+    /// sends a message to every watcher
+    fn announce(&self, message: serde_json::Value);
+    /// sends a message to a single watcher
+    fn send(&self, watcher_id: Id, message: serde_json::Value);
+    /// players currently connected
+    fn players(&self) -> Vec<Id>;
+    fn now(&self) -> SystemTime;
+    /// calls [`CustomSlide::receive_alarm`] with the payload once the delay elapsed
+    fn schedule(&mut self, payload: serde_json::Value, delay: Duration);
+    /// adds the points earned on the slide, expected once before the slide finishes
+    fn add_scores(&mut self, scores: &[(Id, u64)]);
+}
+
+/// A running slide of a custom type
+pub trait CustomSlide: Debug + Send + Sync {
+    fn play(&mut self, context: &mut dyn SlideContext, index: usize, count: usize);
+
+    /// returns `Ok(true)` once the slide is finished and the game should move on
+    fn receive_message(
+        &mut self,
+        context: &mut dyn SlideContext,
+        watcher_id: Id,
+        message: IncomingMessage,
+    ) -> Result<bool, Error>;
+
+    /// returns whether the slide is finished and the game should move on
+    fn receive_alarm(&mut self, context: &mut dyn SlideContext, payload: serde_json::Value)
+        -> bool;
+
+    /// message bringing a reconnecting watcher up to date
+    fn state_message(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        now: SystemTime,
+        index: usize,
+        count: usize,
+    ) -> serde_json::Value;
+
+    /// whether the correct answers are being shown
+    fn answers_revealed(&self) -> bool {
+        false
+    }
+
+    /// persisted form of the slide, handed back to [`SlidePlugin::restore`]
+    fn save(&self) -> serde_json::Value;
+
+    fn clone_box(&self) -> Box<dyn CustomSlide>;
+}
+
+/// Constructors of a custom slide type
+#[derive(Debug, Clone, Copy)]
+pub struct SlidePlugin {
+    /// unique name quizzes refer to
+    pub kind: &'static str,
+    /// checks a slide configuration before a game is created with it
+    pub validate: fn(&serde_json::Value) -> Result<(), String>,
+    /// starts a slide from its configuration
+    pub create: fn(&serde_json::Value) -> Result<Box<dyn CustomSlide>, String>,
+    /// brings back a slide saved with [`CustomSlide::save`]
+    pub restore: fn(&serde_json::Value) -> Result<Box<dyn CustomSlide>, String>,
+}
+
+static REGISTRY: LazyLock<RwLock<HashMap<&'static str, SlidePlugin>>> =
+    LazyLock::new(Default::default);
+
+/// makes a slide type available to every game, replacing any plugin of the same kind
+pub fn register(plugin: SlidePlugin) {
+    // plugins are plain copies, a panic while the lock was held can't leave one half written
+    REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(plugin.kind, plugin);
+}
+
+pub fn find(kind: &str) -> Option<SlidePlugin> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(kind)
+        .copied()
+}
+
+/// Configuration of a slide of a registered type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomConfig {
+    pub kind: String,
+    pub config: serde_json::Value,
+}
+
+pub fn validate_config(config: &CustomConfig, _: &()) -> garde::Result {
+    let plugin = find(&config.kind)
+        .ok_or_else(|| garde::Error::new(format!("unknown slide kind {}", config.kind)))?;
+    (plugin.validate)(&config.config).map_err(garde::Error::new)
+}
+
+impl CustomConfig {
+    /// starts the slide, fails if its plugin is no longer registered or rejects the configuration
+    pub fn to_state(&self, generation: u64) -> Result<State, String> {
+        let slide = find(&self.kind)
+            .ok_or_else(|| format!("unknown slide kind {}", self.kind))
+            .and_then(|plugin| (plugin.create)(&self.config))?;
+
+        Ok(State {
+            kind: self.kind.clone(),
+            generation,
+            slide,
+        })
+    }
+}
+
+/// Message of a custom slide as sent to clients
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomMessage {
+    pub kind: String,
+    pub message: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmMessage {
+    pub index: usize,
+    pub generation: u64,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateSerde {
+    kind: String,
+    generation: u64,
+    state: serde_json::Value,
+}
+
+/// A running custom slide along with what the game needs to route its alarms
+#[derive(Debug)]
+pub struct State {
+    kind: String,
+    generation: u64,
+    slide: Box<dyn CustomSlide>,
+}
+
+impl Clone for State {
+    fn clone(&self) -> Self {
+        Self {
+            kind: self.kind.clone(),
+            generation: self.generation,
+            slide: self.slide.clone_box(),
+        }
+    }
+}
+
+impl Serialize for State {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StateSerde {
+            kind: self.kind.clone(),
+            generation: self.generation,
+            state: self.slide.save(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for State {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let StateSerde {
+            kind,
+            generation,
+            state,
+        } = StateSerde::deserialize(deserializer)?;
+
+        let plugin = find(&kind)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown slide kind {kind}")))?;
+        let slide = (plugin.restore)(&state).map_err(serde::de::Error::custom)?;
+
+        Ok(Self {
+            kind,
+            generation,
+            slide,
+        })
+    }
+}
+
+/// [`SlideContext`] backed by the game's watchers and tunnels
+struct Context<'a, T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(crate::AlarmMessage, Duration)> {
+    kind: &'a str,
+    watchers: &'a Watchers,
+    tunnel_finder: F,
+    clock: &'a dyn Clock,
+    schedule_message: S,
+    leaderboard: Option<&'a mut Leaderboard>,
+    index: usize,
+    generation: u64,
+}
+
+impl<T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(crate::AlarmMessage, Duration)> SlideContext
+    for Context<'_, T, F, S>
+{
+    fn announce(&self, message: serde_json::Value) {
+        self.watchers.announce(
+            &crate::UpdateMessage::Custom(CustomMessage {
+                kind: self.kind.to_owned(),
+                message,
+            }),
+            &self.tunnel_finder,
+        );
+    }
+
+    fn send(&self, watcher_id: Id, message: serde_json::Value) {
+        self.watchers.send_message(
+            &crate::UpdateMessage::Custom(CustomMessage {
+                kind: self.kind.to_owned(),
+                message,
+            }),
+            watcher_id,
+            &self.tunnel_finder,
+        );
+    }
+
+    fn players(&self) -> Vec<Id> {
+        self.watchers
+            .specific_vec(ValueKind::Player, &self.tunnel_finder)
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect()
+    }
+
+    fn now(&self) -> SystemTime {
+        self.clock.now()
+    }
+
+    fn schedule(&mut self, payload: serde_json::Value, delay: Duration) {
+        (self.schedule_message)(
+            crate::AlarmMessage::Custom(AlarmMessage {
+                index: self.index,
+                generation: self.generation,
+                payload,
+            }),
+            delay,
+        );
+    }
+
+    fn add_scores(&mut self, scores: &[(Id, u64)]) {
+        if let Some(leaderboard) = &mut self.leaderboard {
+            leaderboard.add_scores(scores);
+        }
+    }
+}
+
+impl State {
+    pub fn answers_revealed(&self) -> bool {
+        self.slide.answers_revealed()
+    }
+
+    pub fn play<T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(crate::AlarmMessage, Duration)>(
+        &mut self,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        let mut context = Context {
+            kind: &self.kind,
+            watchers,
+            tunnel_finder,
+            clock,
+            schedule_message,
+            leaderboard: None,
+            index,
+            generation: self.generation,
+        };
+        self.slide.play(&mut context, index, count);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        message: IncomingMessage,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+    ) -> Result<bool, Error> {
+        let mut context = Context {
+            kind: &self.kind,
+            watchers,
+            tunnel_finder,
+            clock,
+            schedule_message,
+            leaderboard: Some(leaderboard),
+            index,
+            generation: self.generation,
+        };
+        self.slide
+            .receive_message(&mut context, watcher_id, message)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_alarm<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, Duration),
+    >(
+        &mut self,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        schedule_message: &mut S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        message: crate::AlarmMessage,
+        index: usize,
+    ) -> bool {
+        let crate::AlarmMessage::Custom(AlarmMessage {
+            generation,
+            payload,
+            ..
+        }) = message
+        else {
+            return false;
+        };
+
+        if generation != self.generation {
+            return false;
+        }
+
+        let mut context = Context {
+            kind: &self.kind,
+            watchers,
+            tunnel_finder,
+            clock,
+            schedule_message,
+            leaderboard: Some(leaderboard),
+            index,
+            generation: self.generation,
+        };
+        self.slide.receive_alarm(&mut context, payload)
+    }
+
+    pub fn state_message(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> CustomMessage {
+        CustomMessage {
+            kind: self.kind.clone(),
+            message: self
+                .slide
+                .state_message(watcher_id, watcher_kind, clock.now(), index, count),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Faithful previews of a slide for quiz editors.
+//!
+//! [`preview`] plays the slide in a throwaway game with one host and one player and records what
+//! each of them receives after every step, along with the state a client reconnecting at that
+//! point would be sent, so editors render exactly what the engine would send.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+use serde::Serialize;
+use web_time::Duration;
+
+use crate::{
+    clock::VirtualClock,
+    game::{
+        Game, IncomingMessage, IncomingPlayerMessage, IncomingUnassignedMessage, Options, State,
+    },
+    session::{CloseReason, Tunnel},
+    watcher::{Id, ValueKind},
+    AlarmMessage,
+};
+
+use super::config::{Fuiz, SlideConfig};
+
+/// most steps recorded, guards against custom slides that never finish
+const MAX_STEPS: usize = 64;
+
+/// What moved the slide forward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Trigger {
+    Start,
+    Alarm,
+    Answer,
+    HostNext,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Step {
+    pub trigger: Trigger,
+    /// messages the host received during the step
+    pub host: Vec<serde_json::Value>,
+    /// messages the player received during the step
+    pub player: Vec<serde_json::Value>,
+    /// state sent to a host reconnecting after the step
+    pub host_sync: serde_json::Value,
+    /// state sent to a player reconnecting after the step
+    pub player_sync: serde_json::Value,
+}
+
+#[derive(Default)]
+struct Inboxes {
+    connected: HashSet<Id>,
+    received: HashMap<Id, Vec<serde_json::Value>>,
+}
+
+struct RecordingTunnel {
+    inboxes: Rc<RefCell<Inboxes>>,
+    watcher_id: Id,
+}
+
+impl Tunnel for RecordingTunnel {
+    fn send_raw(&self, message: Arc<str>) {
+        let message = serde_json::from_str(&message).expect("engine only sends valid JSON");
+        self.inboxes
+            .borrow_mut()
+            .received
+            .entry(self.watcher_id)
+            .or_default()
+            .push(message);
+    }
+
+    fn close(self, _: CloseReason) {
+        self.inboxes.borrow_mut().connected.remove(&self.watcher_id);
+    }
+}
+
+fn tunnel_finder(inboxes: &Rc<RefCell<Inboxes>>) -> impl Fn(Id) -> Option<RecordingTunnel> + '_ {
+    |watcher_id| {
+        inboxes
+            .borrow()
+            .connected
+            .contains(&watcher_id)
+            .then(|| RecordingTunnel {
+                inboxes: inboxes.clone(),
+                watcher_id,
+            })
+    }
+}
+
+fn schedule(
+    alarms: &RefCell<Vec<(Duration, AlarmMessage)>>,
+    elapsed: Duration,
+) -> impl FnMut(AlarmMessage, Duration) + '_ {
+    move |alarm, delay| alarms.borrow_mut().push((elapsed + delay, alarm))
+}
+
+/// plays the slide until the game leaves it, the player submitting the answer as soon as it's
+/// accepted or never answering if there is none
+pub fn preview(
+    slide: SlideConfig,
+    options: Options,
+    mut answer: Option<IncomingPlayerMessage>,
+) -> Vec<Step> {
+    let host = Id::new();
+    let player = Id::new();
+
+    let clock = Arc::new(VirtualClock::default());
+    let mut game = Game::new(Fuiz::new(String::new(), vec![slide]), options, host);
+    game.set_clock(clock.clone());
+
+    let inboxes = Rc::new(RefCell::new(Inboxes::default()));
+    inboxes.borrow_mut().connected.extend([host, player]);
+    let alarms = RefCell::new(Vec::new());
+    let mut elapsed = Duration::ZERO;
+
+    let finder = tunnel_finder(&inboxes);
+
+    if game.add_unassigned(player, &finder).is_ok() {
+        // names are picked at random or by the player depending on the options
+        let _ = game.try_receive_message(
+            player,
+            IncomingMessage::Unassigned(IncomingUnassignedMessage::NameRequest(
+                "Player".to_owned(),
+            )),
+            schedule(&alarms, elapsed),
+            &finder,
+        );
+    }
+    inboxes.borrow_mut().received.clear();
+
+    let record = |trigger: Trigger, game: &Game| {
+        let mut inboxes = inboxes.borrow_mut();
+        let host_messages = inboxes.received.remove(&host).unwrap_or_default();
+        let player_messages = inboxes.received.remove(&player).unwrap_or_default();
+        drop(inboxes);
+
+        let sync = |watcher_id, kind| {
+            serde_json::to_value(game.state_message(watcher_id, kind, &finder))
+                .expect("default serializer cannot fail")
+        };
+
+        Step {
+            trigger,
+            host_sync: sync(host, ValueKind::Host),
+            player_sync: sync(player, ValueKind::Player),
+            host: host_messages,
+            player: player_messages,
+        }
+    };
+
+    game.play(schedule(&alarms, elapsed), &finder);
+    let mut steps = vec![record(Trigger::Start, &game)];
+
+    while steps.len() < MAX_STEPS && matches!(game.state, State::Slide(_)) {
+        if let Some(message) = answer.clone() {
+            if game
+                .try_answer(player, message, schedule(&alarms, elapsed), &finder)
+                .is_ok()
+            {
+                answer = None;
+                steps.push(record(Trigger::Answer, &game));
+                continue;
+            }
+        }
+
+        let next_alarm = {
+            let mut alarms = alarms.borrow_mut();
+            let earliest = alarms
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (due, _))| *due)
+                .map(|(index, _)| index);
+            earliest.map(|index| alarms.remove(index))
+        };
+
+        let trigger = match next_alarm {
+            Some((due, alarm)) => {
+                clock.advance(due.saturating_sub(elapsed));
+                elapsed = elapsed.max(due);
+                game.receive_alarm(alarm, schedule(&alarms, elapsed), &finder);
+                Trigger::Alarm
+            }
+            None => {
+                if game
+                    .try_advance(host, schedule(&alarms, elapsed), &finder)
+                    .is_err()
+                {
+                    break;
+                }
+                Trigger::HostNext
+            }
+        };
+
+        let step = record(trigger, &game);
+        // alarms of phases already left change nothing
+        if trigger != Trigger::Alarm || !step.host.is_empty() || !step.player.is_empty() {
+            steps.push(step);
+        }
+    }
+
+    steps
+}
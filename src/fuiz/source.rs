@@ -0,0 +1,105 @@
+//! Where a game gets its slides from.
+//!
+//! By default a game plays the slides of its [`Fuiz`] in order. A [`QuestionSource`] set with
+//! [`crate::game::Game::set_question_source`] replaces that, handing out slides one at a time as
+//! the game reaches them, which allows question banks, generators and endless games.
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::leaderboard::Leaderboard;
+
+use super::config::{Fuiz, SlideConfig};
+
+/// Provider of the slides a game plays
+pub trait QuestionSource: Debug + Send + Sync {
+    /// slide to play at this index, `None` ends the game
+    fn next(&mut self, index: usize, leaderboard: &Leaderboard) -> Option<SlideConfig>;
+
+    /// number of slides that will be played, `None` if not known in advance
+    fn count(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl QuestionSource for Fuiz {
+    fn next(&mut self, index: usize, _: &Leaderboard) -> Option<SlideConfig> {
+        self.slides.get(index).cloned()
+    }
+
+    fn count(&self) -> Option<usize> {
+        Some(Fuiz::len(self))
+    }
+}
+
+const CONFIG: crate::config::fuiz::adaptive::AdaptiveConfig = crate::CONFIG.fuiz.adaptive;
+
+const WINDOW: usize = CONFIG.window.unsigned_abs() as usize;
+const RAISE_ABOVE: f64 = CONFIG.raise_above_percent.unsigned_abs() as f64 / 100.;
+const LOWER_BELOW: f64 = CONFIG.lower_below_percent.unsigned_abs() as f64 / 100.;
+
+/// Picks each slide from a difficulty level following how well the room did on the last few
+/// slides, moving up when most scores earned points and down when few did
+#[derive(Debug, Clone)]
+pub struct Adaptive {
+    /// slides left to play in each level, easiest level first
+    levels: Vec<VecDeque<SlideConfig>>,
+    level: usize,
+    /// slides handed out since the level last changed, only those are judged
+    played_at_level: usize,
+    count: usize,
+}
+
+impl Adaptive {
+    /// plays `count` slides out of the levels, ordered from easiest to hardest, starting in the
+    /// middle one
+    pub fn new(levels: Vec<Fuiz>, count: usize) -> Self {
+        let available = levels.iter().map(Fuiz::len).sum::<usize>();
+
+        Self {
+            level: levels.len().saturating_sub(1) / 2,
+            played_at_level: 0,
+            levels: levels.into_iter().map(|fuiz| fuiz.slides.into()).collect(),
+            count: count.min(available),
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    fn adjust(&mut self, leaderboard: &Leaderboard) {
+        let Some(accuracy) = leaderboard.recent_accuracy(self.played_at_level.min(WINDOW)) else {
+            return;
+        };
+
+        if accuracy > RAISE_ABOVE && self.level + 1 < self.levels.len() {
+            self.level += 1;
+            self.played_at_level = 0;
+        } else if accuracy < LOWER_BELOW && self.level > 0 {
+            self.level -= 1;
+            self.played_at_level = 0;
+        }
+    }
+}
+
+impl QuestionSource for Adaptive {
+    fn next(&mut self, index: usize, leaderboard: &Leaderboard) -> Option<SlideConfig> {
+        if index >= self.count {
+            return None;
+        }
+
+        self.adjust(leaderboard);
+
+        // closest level with slides left, preferring the easier one on ties
+        let level = (0..self.levels.len())
+            .filter(|level| !self.levels[*level].is_empty())
+            .min_by_key(|level| (level.abs_diff(self.level), *level))?;
+
+        self.played_at_level += 1;
+        self.levels[level].pop_front()
+    }
+
+    fn count(&self) -> Option<usize> {
+        Some(self.count)
+    }
+}
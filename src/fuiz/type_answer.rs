@@ -10,6 +10,7 @@ use serde_with::skip_serializing_none;
 use web_time::SystemTime;
 
 use crate::{
+    clock::{Clock, Countdown},
     leaderboard::Leaderboard,
     session::Tunnel,
     teams::TeamManager,
@@ -17,7 +18,12 @@ use crate::{
 };
 
 use super::{
-    super::game::{IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    super::game::{self, Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{
+        announce_remaining_time, canonicalize_host_notes, host_notes, next_time_correction,
+        AnswerCountThrottle, AnswerTimeline, OwnAnswer, MAX_HOST_NOTES_LENGTH,
+    },
+    expression,
     media::Media,
 };
 
@@ -73,6 +79,34 @@ fn validate_introduce_question(val: &Duration) -> ValidationResult {
     validate_duration::<MIN_INTRODUCE_QUESTION, MAX_INTRODUCE_QUESTION>("introduce_question", val)
 }
 
+/// answers compared by value must all be arithmetic expressions
+fn validate_answers(
+    check: &AnswerCheck,
+) -> impl FnOnce(&Vec<String>, &()) -> ValidationResult + '_ {
+    move |answers, _| match check {
+        AnswerCheck::Text => Ok(()),
+        AnswerCheck::Expression => match answers
+            .iter()
+            .find(|answer| expression::evaluate(answer).is_none())
+        {
+            Some(answer) => Err(garde::Error::new(format!(
+                "{answer} is not an arithmetic expression"
+            ))),
+            None => Ok(()),
+        },
+    }
+}
+
+/// How submitted answers are compared to the accepted ones
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerCheck {
+    /// same text, ignoring surrounding spaces and case unless case-sensitive
+    #[default]
+    Text,
+    /// same value once evaluated as arithmetic expressions, so `1/2`, `0.5` and `2/4` match
+    Expression,
+}
+
 #[serde_with::serde_as]
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, serde::Deserialize, Validate)]
@@ -83,6 +117,10 @@ pub struct SlideConfig {
     /// Accompanying media
     #[garde(dive)]
     media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
     /// Time before the answers are displayed
     #[garde(custom(|v, _| validate_introduce_question(v)))]
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
@@ -96,12 +134,20 @@ pub struct SlideConfig {
     #[garde(skip)]
     points_awarded: u64,
     /// Accompanying answers
-    #[garde(length(max = MAX_ANSWER_COUNT), inner(length(chars, max = MAX_ANSWER_TEXT_LENGTH)))]
+    #[garde(
+        length(max = MAX_ANSWER_COUNT),
+        inner(length(chars, max = MAX_ANSWER_TEXT_LENGTH)),
+        custom(validate_answers(&self.check))
+    )]
     answers: Vec<String>,
     /// Case-sensitive check for answers
     #[garde(skip)]
     #[serde(default)]
     case_sensitive: bool,
+    /// How answers are compared
+    #[garde(skip)]
+    #[serde(default)]
+    check: AnswerCheck,
 }
 
 /// Presenting a multiple choice question that presents a question then the answers with optional accompanying media
@@ -119,15 +165,69 @@ pub struct State {
     answer_start: Option<SystemTime>,
     /// Stage of the slide
     state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+    /// answer counts sent to the host
+    #[serde(default)]
+    answer_count: AnswerCountThrottle,
 }
 
 impl SlideConfig {
-    pub fn to_state(&self) -> State {
+    pub fn new(
+        title: String,
+        introduce_question: Duration,
+        time_limit: Duration,
+        points_awarded: u64,
+        answers: Vec<String>,
+        case_sensitive: bool,
+    ) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            introduce_question,
+            time_limit,
+            points_awarded,
+            answers,
+            case_sensitive,
+            check: AnswerCheck::Text,
+        }
+    }
+
+    /// trims the text of the question and its answers, dropping answers left empty
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        self.answers = self
+            .answers
+            .iter()
+            .map(|answer| answer.trim().to_owned())
+            .filter(|answer| !answer.is_empty())
+            .collect();
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    /// drops the accepted answers, leaving only how players answered
+    pub fn clear_correct_answers(&mut self) {
+        self.answers.clear();
+    }
+
+    pub fn to_state(&self, generation: u64) -> State {
         State {
             config: self.clone(),
             user_answers: Default::default(),
             answer_start: Default::default(),
             state: Default::default(),
+            generation,
+            answer_count: Default::default(),
         }
     }
 }
@@ -147,14 +247,18 @@ pub enum UpdateMessage {
         question: String,
         /// Accompanying media
         media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
         /// Time before answers will be release
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
         /// Accept answers from players
         accept_answers: bool,
     },
     /// (HOST ONLY): Number of players who answered the question
     AnswersCount(usize),
+    /// Time left to answer, sent now and then so devices don't drift apart on long questions
+    RemainingTime(Countdown),
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
         /// Correct answers
@@ -163,12 +267,25 @@ pub enum UpdateMessage {
         results: Vec<(String, usize)>,
         /// Case-sensitive check for answers
         case_sensitive: bool,
+        /// (PLAYER ONLY) What the player answered and whether it was correct
+        own_answer: Option<OwnAnswer<String>>,
+        /// (HOST ONLY) When answers arrived over the answering phase
+        timeline: Option<AnswerTimeline>,
     },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlarmMessage {
-    ProceedFromSlideIntoSlide { index: usize, to: SlideState },
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+    /// sends the host the answer count held back by [`AnswerCountThrottle`]
+    FlushAnswersCount { index: usize, generation: u64 },
+    /// broadcasts the time left to answer, see [`next_time_correction`]
+    CorrectRemainingTime { index: usize, generation: u64 },
 }
 
 /// Messages sent to the listeners who lack preexisting state to synchronize their state.
@@ -184,10 +301,13 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         /// Remaining time for the question to be displayed without its answers
-        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
-        duration: Duration,
+        #[serde(flatten)]
+        countdown: Countdown,
         accept_answers: bool,
+        /// answer recorded for the watcher so far
+        answered: Option<String>,
     },
     /// Results of the game including correct answers and statistics of how many they got chosen
     AnswersResults {
@@ -195,9 +315,12 @@ pub enum SyncMessage {
         count: usize,
         question: String,
         media: Option<Media>,
+        host_notes: Option<String>,
         answers: Vec<String>,
         results: Vec<(String, usize)>,
         case_sensitive: bool,
+        own_answer: Option<OwnAnswer<String>>,
+        timeline: Option<AnswerTimeline>,
     },
 }
 
@@ -219,10 +342,18 @@ impl State {
         watchers: &Watchers,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
-        self.send_question_announcements(watchers, schedule_message, tunnel_finder, index, count);
+        self.send_question_announcements(
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            index,
+            count,
+        );
     }
 
     fn calculate_score(
@@ -235,12 +366,37 @@ impl State {
             as u64
     }
 
-    fn start_timer(&mut self) {
-        self.answer_start = Some(SystemTime::now());
+    fn start_timer(&mut self, clock: &dyn Clock) {
+        self.answer_start = Some(clock.now());
     }
 
-    fn timer(&self) -> SystemTime {
-        self.answer_start.unwrap_or(SystemTime::now())
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
+    }
+
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// answers are only accepted during the answering phase and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn answered_in_time(&self, instant: SystemTime) -> bool {
+        self.answer_deadline()
+            .is_some_and(|deadline| instant <= deadline)
     }
 
     fn send_question_announcements<
@@ -252,6 +408,7 @@ impl State {
         watchers: &Watchers,
         mut schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
@@ -261,24 +418,30 @@ impl State {
                     watchers,
                     schedule_message,
                     tunnel_finder,
+                    clock,
                     index,
                     count,
                 );
                 return;
             }
 
-            self.start_timer();
+            self.start_timer(clock);
 
-            watchers.announce(
-                &UpdateMessage::QuestionAnnouncement {
-                    index,
-                    count,
-                    question: self.config.title.clone(),
-                    media: self.config.media.clone(),
-                    duration: self.config.introduce_question,
-                    accept_answers: false,
-                }
-                .into(),
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::QuestionAnnouncement {
+                            index,
+                            count,
+                            question: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            countdown: Countdown::new(clock, self.config.introduce_question),
+                            accept_answers: false,
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 tunnel_finder,
             );
 
@@ -286,6 +449,7 @@ impl State {
                 AlarmMessage::ProceedFromSlideIntoSlide {
                     index,
                     to: SlideState::Answers,
+                    generation: self.generation,
                 }
                 .into(),
                 self.config.introduce_question,
@@ -302,22 +466,28 @@ impl State {
         watchers: &Watchers,
         mut schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) {
         if self.change_state(SlideState::Question, SlideState::Answers) {
-            self.start_timer();
+            self.start_timer(clock);
 
-            watchers.announce(
-                &UpdateMessage::QuestionAnnouncement {
-                    index,
-                    count,
-                    question: self.config.title.clone(),
-                    media: self.config.media.clone(),
-                    duration: self.config.time_limit,
-                    accept_answers: true,
-                }
-                .into(),
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::QuestionAnnouncement {
+                            index,
+                            count,
+                            question: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            countdown: Countdown::new(clock, self.config.time_limit),
+                            accept_answers: true,
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 tunnel_finder,
             );
 
@@ -325,10 +495,22 @@ impl State {
                 AlarmMessage::ProceedFromSlideIntoSlide {
                     index,
                     to: SlideState::AnswersResults,
+                    generation: self.generation,
                 }
                 .into(),
                 self.config.time_limit,
-            )
+            );
+
+            if let Some(delay) = next_time_correction(self.config.time_limit) {
+                schedule_message(
+                    AlarmMessage::CorrectRemainingTime {
+                        index,
+                        generation: self.generation,
+                    }
+                    .into(),
+                    delay,
+                );
+            }
         }
     }
 
@@ -346,67 +528,157 @@ impl State {
         self.state
     }
 
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// answer a bot submits, one of the accepted ones or nothing matching them
+    pub fn bot_answer(
+        &self,
+        correct: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Option<IncomingPlayerMessage> {
+        let answer = match rng.choice(&self.config.answers) {
+            Some(answer) if correct => answer.clone(),
+            _ => String::new(),
+        };
+        Some(IncomingPlayerMessage::StringAnswer(answer))
+    }
+
+    /// whether the correct answers are being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
     fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
         watchers: &Watchers,
         tunnel_finder: F,
     ) {
         if self.change_state(SlideState::Answers, SlideState::AnswersResults) {
-            watchers.announce(
-                &UpdateMessage::AnswersResults {
-                    answers: self
-                        .config
-                        .answers
-                        .iter()
-                        .map(|answer| clean_answer(answer, self.config.case_sensitive))
-                        .collect_vec(),
-                    results: self
-                        .user_answers
-                        .iter()
-                        .map(|(_, (answer, _))| clean_answer(answer, self.config.case_sensitive))
-                        .counts()
-                        .into_iter()
-                        .map(|(i, c)| (i.to_owned(), c))
-                        .collect_vec(),
-                    case_sensitive: self.config.case_sensitive,
-                }
-                .into(),
+            let answers = self
+                .config
+                .answers
+                .iter()
+                .map(|answer| clean_answer(answer, self.config.case_sensitive))
+                .collect_vec();
+            let results = self
+                .user_answers
+                .iter()
+                .map(|(_, (answer, _))| clean_answer(answer, self.config.case_sensitive))
+                .counts()
+                .into_iter()
+                .map(|(i, c)| (i.to_owned(), c))
+                .collect_vec();
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            answers: answers.clone(),
+                            results: results.clone(),
+                            case_sensitive: self.config.case_sensitive,
+                            own_answer: self.own_answer(watcher_id),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
                 tunnel_finder,
             );
         }
     }
 
+    /// when answers arrived, `None` until answers are shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self
+            .answer_start
+            .filter(|_| matches!(self.state, SlideState::Answers | SlideState::AnswersResults))?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.user_answers.values().map(|(_, instant)| *instant),
+        ))
+    }
+
+    /// how long each player took to answer once answers were shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.user_answers
+            .iter()
+            .map(|(id, (_, instant))| {
+                (
+                    *id,
+                    instant.duration_since(answer_start).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// what every player answered and whether it earns points
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, OwnAnswer<String>)> + '_ {
+        self.user_answers
+            .keys()
+            .filter_map(|id| Some((*id, self.own_answer(*id)?)))
+    }
+
+    /// what the watcher answered and whether it earns points
+    fn own_answer(&self, watcher_id: Id) -> Option<OwnAnswer<String>> {
+        let (answer, instant) = self.user_answers.get(&watcher_id)?;
+        Some(OwnAnswer {
+            correct: self.is_accepted(answer) && self.answered_in_time(*instant),
+            answer: clean_answer(answer, self.config.case_sensitive),
+        })
+    }
+
+    /// whether the answer matches one of the accepted answers
+    fn is_accepted(&self, answer: &str) -> bool {
+        match self.config.check {
+            AnswerCheck::Text => {
+                let answer = clean_answer(answer, self.config.case_sensitive);
+                self.config
+                    .answers
+                    .iter()
+                    .any(|correct| clean_answer(correct, self.config.case_sensitive) == answer)
+            }
+            AnswerCheck::Expression => expression::evaluate(answer).is_some_and(|value| {
+                self.config
+                    .answers
+                    .iter()
+                    .any(|correct| expression::evaluate(correct) == Some(value))
+            }),
+        }
+    }
+
     fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
         leaderboard: &mut Leaderboard,
         watchers: &Watchers,
         team_manager: Option<&TeamManager>,
         tunnel_finder: F,
+        clock: &dyn Clock,
     ) {
-        let starting_instant = self.timer();
-
-        let cleaned_answers: HashSet<_> = self
-            .config
-            .answers
-            .iter()
-            .map(|answer| clean_answer(answer, self.config.case_sensitive))
-            .collect();
+        let starting_instant = self.timer(clock);
 
         leaderboard.add_scores(
             &self
                 .user_answers
                 .iter()
                 .map(|(id, (answer, instant))| {
-                    let correct =
-                        cleaned_answers.contains(&clean_answer(answer, self.config.case_sensitive));
+                    let correct = self.is_accepted(answer) && self.answered_in_time(*instant);
                     (
                         *id,
                         if correct {
                             State::calculate_score(
                                 self.config.time_limit,
-                                instant
-                                    .duration_since(starting_instant)
-                                    .expect("future is past the past"),
+                                instant.duration_since(starting_instant).unwrap_or_default(),
                                 self.config.points_awarded,
                             )
                         } else {
@@ -429,9 +701,8 @@ impl State {
                         match &team_manager {
                             Some(team_manager) => team_manager.all_ids(),
                             None => watchers
-                                .specific_vec(ValueKind::Player, tunnel_finder)
+                                .present_players(tunnel_finder)
                                 .into_iter()
-                                .map(|(x, _, _)| x)
                                 .collect_vec(),
                         }
                     }
@@ -443,13 +714,15 @@ impl State {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
-        _watcher_id: Id,
-        _watcher_kind: ValueKind,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
         _team_manager: Option<&TeamManager>,
         _watchers: &Watchers,
         _tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
     ) -> SyncMessage {
@@ -459,24 +732,46 @@ impl State {
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
-                duration: self.config.introduce_question
-                    - self.timer().elapsed().expect("system clock went backwards"),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.introduce_question.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
                 accept_answers: false,
+                answered: None,
             },
             SlideState::Answers => SyncMessage::QuestionAnnouncement {
                 index,
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
-                duration: self.config.time_limit
-                    - self.timer().elapsed().expect("system clock went backwards"),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
                 accept_answers: true,
+                answered: self
+                    .user_answers
+                    .get(&watcher_id)
+                    .map(|(answer, _)| answer.clone()),
             },
             SlideState::AnswersResults => SyncMessage::AnswersResults {
                 index,
                 count,
                 question: self.config.title.clone(),
                 media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
                 answers: self
                     .config
                     .answers
@@ -492,10 +787,13 @@ impl State {
                     .map(|(i, c)| (i.to_owned(), c))
                     .collect_vec(),
                 case_sensitive: self.config.case_sensitive,
+                own_answer: self.own_answer(watcher_id),
+                timeline: self.host_timeline(watcher_kind),
             },
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_message<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -509,9 +807,10 @@ impl State {
         team_manager: Option<&TeamManager>,
         schedule_message: S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         index: usize,
         count: usize,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         match message {
             IncomingMessage::Host(IncomingHostMessage::Next) => match self.state() {
                 SlideState::Unstarted => {
@@ -519,6 +818,7 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                         count,
                     );
@@ -528,6 +828,7 @@ impl State {
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                         count,
                     );
@@ -536,35 +837,102 @@ impl State {
                     self.send_answers_results(watchers, tunnel_finder);
                 }
                 SlideState::AnswersResults => {
-                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder);
-                    return true;
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder, clock);
+                    return Ok(true);
                 }
             },
             IncomingMessage::Player(IncomingPlayerMessage::StringAnswer(v)) => {
-                self.user_answers.insert(watcher_id, (v, SystemTime::now()));
-                let left_set: HashSet<_> = watchers
-                    .specific_vec(ValueKind::Player, &tunnel_finder)
-                    .iter()
-                    .map(|(w, _, _)| w.to_owned())
-                    .collect();
+                self.check_answer_window(clock)?;
+
+                let at = clock.now();
+                self.user_answers.insert(watcher_id, (v, at));
+                watchers.send_message(
+                    &game::UpdateMessage::AnswerRecorded { index, at }.into(),
+                    watcher_id,
+                    &tunnel_finder,
+                );
+                let left_set = watchers.present_players(&tunnel_finder);
                 let right_set: HashSet<_> = self.user_answers.keys().copied().collect();
                 if left_set.is_subset(&right_set) {
                     self.send_answers_results(watchers, &tunnel_finder);
                 } else {
-                    watchers.announce_specific(
-                        ValueKind::Host,
-                        &UpdateMessage::AnswersCount(left_set.intersection(&right_set).count())
-                            .into(),
+                    self.announce_answers_count(
+                        left_set.intersection(&right_set).count(),
+                        left_set.len(),
+                        watchers,
+                        schedule_message,
                         &tunnel_finder,
+                        clock,
+                        index,
                     );
                 }
             }
-            _ => (),
+            _ => return Err(Error::WrongPhase),
         };
 
-        false
+        Ok(false)
+    }
+
+    /// number of present players who answered
+    pub fn answered_count<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> usize {
+        let players = watchers.present_players(tunnel_finder);
+        self.user_answers
+            .keys()
+            .filter(|id| players.contains(id))
+            .count()
+    }
+
+    /// updates a reconnecting host would otherwise be missing, the latest answer count might
+    /// have been held back
+    pub fn catch_up_updates<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_kind: ValueKind,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) -> Vec<crate::UpdateMessage> {
+        if watcher_kind != ValueKind::Host || self.state != SlideState::Answers {
+            return Vec::new();
+        }
+        vec![UpdateMessage::AnswersCount(self.answered_count(watchers, tunnel_finder)).into()]
+    }
+
+    /// tells the host how many players answered, batched through [`AnswerCountThrottle`]
+    #[allow(clippy::too_many_arguments)]
+    fn announce_answers_count<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        answered: usize,
+        players: usize,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+    ) {
+        self.answer_count.announce(
+            answered,
+            players,
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            |answered| UpdateMessage::AnswersCount(answered).into(),
+            AlarmMessage::FlushAnswersCount {
+                index,
+                generation: self.generation,
+            }
+            .into(),
+        );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn receive_alarm<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
@@ -576,21 +944,72 @@ impl State {
         _team_manager: Option<&TeamManager>,
         schedule_message: &mut S,
         tunnel_finder: F,
+        clock: &dyn Clock,
         message: crate::AlarmMessage,
         index: usize,
         count: usize,
     ) -> bool {
+        if let crate::AlarmMessage::TypeAnswer(AlarmMessage::CorrectRemainingTime {
+            index,
+            generation,
+        }) = &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                if let Some(delay) = announce_remaining_time(
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    self.config.time_limit,
+                    self.timer(clock),
+                    |countdown| UpdateMessage::RemainingTime(countdown).into(),
+                ) {
+                    schedule_message(
+                        AlarmMessage::CorrectRemainingTime {
+                            index: *index,
+                            generation: *generation,
+                        }
+                        .into(),
+                        delay,
+                    );
+                }
+            }
+            return false;
+        }
+
+        if let crate::AlarmMessage::TypeAnswer(AlarmMessage::FlushAnswersCount {
+            generation, ..
+        }) = &message
+        {
+            if *generation == self.generation && self.state == SlideState::Answers {
+                let answered = self.answered_count(watchers, &tunnel_finder);
+                self.answer_count.announce_flush(
+                    answered,
+                    watchers,
+                    &tunnel_finder,
+                    clock,
+                    |answered| UpdateMessage::AnswersCount(answered).into(),
+                );
+            }
+            return false;
+        }
+
         if let crate::AlarmMessage::TypeAnswer(AlarmMessage::ProceedFromSlideIntoSlide {
             index: _,
             to,
+            generation,
         }) = message
         {
+            if generation != self.generation {
+                return false;
+            }
+
             match to {
                 SlideState::Answers => {
                     self.send_accepting_answers(
                         watchers,
                         schedule_message,
                         tunnel_finder,
+                        clock,
                         index,
                         count,
                     );
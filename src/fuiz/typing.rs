@@ -0,0 +1,704 @@
+//! Typing challenge: players copy a sentence as fast as they can, sending what they typed so far
+//! as [`IncomingPlayerMessage::StringAnswer`] while the host follows their speed live
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{self, Duration},
+};
+
+use garde::Validate;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use web_time::SystemTime;
+
+use crate::{
+    clock::{Clock, Countdown},
+    leaderboard::Leaderboard,
+    session::Tunnel,
+    teams::TeamManager,
+    watcher::{Id, ValueKind, Watchers},
+    TruncatedVec,
+};
+
+use super::{
+    super::game::{Error, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage},
+    config::{canonicalize_host_notes, host_notes, AnswerTimeline, MAX_HOST_NOTES_LENGTH},
+    media::Media,
+};
+
+/// Phase of the slide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SlideState {
+    /// Unstarted, exists to distinguish between started and unstarted slide
+    #[default]
+    Unstarted,
+    /// Showing the sentence and accepting what players typed
+    Answers,
+    /// Showing the speed of every player
+    AnswersResults,
+}
+
+type ValidationResult = garde::Result;
+
+const CONFIG: crate::config::fuiz::typing::TypingConfig = crate::CONFIG.fuiz.typing;
+
+const MAX_TITLE_LENGTH: usize = CONFIG.max_title_length.unsigned_abs() as usize;
+const MAX_SENTENCE_LENGTH: usize = CONFIG.max_sentence_length.unsigned_abs() as usize;
+const MIN_TIME_LIMIT: u64 = CONFIG.min_time_limit.unsigned_abs();
+const MAX_TIME_LIMIT: u64 = CONFIG.max_time_limit.unsigned_abs();
+const MAX_TARGET_WPM: u64 = CONFIG.max_target_wpm.unsigned_abs();
+/// number of fastest players shown to the host
+const FEED_SIZE: usize = CONFIG.feed_size.unsigned_abs() as usize;
+
+/// characters counted as one word when measuring speed
+const CHARACTERS_PER_WORD: f64 = 5.;
+
+fn validate_time_limit(val: &Duration) -> ValidationResult {
+    if (MIN_TIME_LIMIT..=MAX_TIME_LIMIT).contains(&val.as_secs()) {
+        Ok(())
+    } else {
+        Err(garde::Error::new(format!(
+            "time_limit is outside of the bounds [{MIN_TIME_LIMIT},{MAX_TIME_LIMIT}]",
+        )))
+    }
+}
+
+fn default_target_wpm() -> u64 {
+    60
+}
+
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, Validate)]
+pub struct SlideConfig {
+    /// Instructions shown above the sentence
+    #[garde(length(chars, max = MAX_TITLE_LENGTH))]
+    title: String,
+    /// Accompanying media
+    #[garde(dive)]
+    media: Option<Media>,
+    /// Talking points for the presenter, only ever sent to the host
+    #[garde(length(chars, max = MAX_HOST_NOTES_LENGTH))]
+    #[serde(default)]
+    host_notes: Option<String>,
+    /// Time where players can type
+    #[garde(custom(|v, _| validate_time_limit(v)))]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    time_limit: Duration,
+    /// Maximum number of points awarded, earned by typing accurately at the target speed
+    #[garde(skip)]
+    points_awarded: u64,
+    /// Sentence players copy
+    #[garde(length(chars, min = 1, max = MAX_SENTENCE_LENGTH))]
+    sentence: String,
+    /// Words per minute earning the full points
+    #[garde(range(min = 1, max = MAX_TARGET_WPM))]
+    #[serde(default = "default_target_wpm")]
+    target_wpm: u64,
+}
+
+/// Presenting a sentence to type as fast as possible
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct State {
+    config: SlideConfig,
+
+    // State
+    /// Latest text typed by each player with when it arrived
+    typed: HashMap<Id, (String, SystemTime)>,
+    /// Instant where the sentence was first displayed
+    answer_start: Option<SystemTime>,
+    /// Stage of the slide
+    state: SlideState,
+    /// Distinguishes this run of the slide from earlier ones so their alarms are ignored
+    #[serde(default)]
+    generation: u64,
+}
+
+impl SlideConfig {
+    pub fn new(
+        title: String,
+        time_limit: Duration,
+        points_awarded: u64,
+        sentence: String,
+        target_wpm: u64,
+    ) -> Self {
+        Self {
+            title,
+            media: None,
+            host_notes: None,
+            time_limit,
+            points_awarded,
+            sentence,
+            target_wpm,
+        }
+    }
+
+    /// trims the title and the sentence
+    pub fn canonicalize(&mut self) {
+        self.title = self.title.trim().to_owned();
+        if let Some(media) = &mut self.media {
+            media.canonicalize();
+        }
+        canonicalize_host_notes(&mut self.host_notes);
+        self.sentence = self.sentence.trim().to_owned();
+    }
+
+    /// drops the notes meant for the host
+    pub fn clear_host_notes(&mut self) {
+        self.host_notes = None;
+    }
+
+    pub fn to_state(&self, generation: u64) -> State {
+        State {
+            config: self.clone(),
+            typed: HashMap::new(),
+            answer_start: None,
+            state: SlideState::default(),
+            generation,
+        }
+    }
+}
+
+/// Speed and accuracy of what a player typed
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct TypingResult {
+    /// Accurate words per minute
+    pub wpm: u64,
+    /// Share of the sentence typed correctly, from 0 to 100
+    pub accuracy: u64,
+}
+
+/// Messages sent to the listeners to update their pre-existing state with the slide state
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum UpdateMessage {
+    /// Announcement of the sentence to type
+    ChallengeAnnouncement {
+        /// Index of the slide (0-indexing)
+        index: usize,
+        /// Total count of slides
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        /// (HOST ONLY) Talking points for the presenter
+        host_notes: Option<String>,
+        sentence: String,
+        /// Time to type the sentence
+        #[serde(flatten)]
+        countdown: Countdown,
+    },
+    /// (HOST ONLY) Fastest players so far with their accurate words per minute
+    SpeedFeed(TruncatedVec<(String, u64)>),
+    /// (PLAYER ONLY) Speed of the player so far
+    OwnSpeed(TypingResult),
+    /// Fastest players along with the result of the player
+    AnswersResults {
+        speeds: TruncatedVec<(String, u64)>,
+        /// (PLAYER ONLY) What the player achieved
+        own_result: Option<TypingResult>,
+        /// (HOST ONLY) When players sent what they typed over the answering phase
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AlarmMessage {
+    ProceedFromSlideIntoSlide {
+        index: usize,
+        to: SlideState,
+        #[serde(default)]
+        generation: u64,
+    },
+}
+
+/// Messages sent to the listeners who lack preexisting state to synchronize their state.
+///
+/// See [`UpdateMessage`] for explaination of these fields.
+#[serde_with::serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub enum SyncMessage {
+    ChallengeAnnouncement {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        sentence: String,
+        /// Remaining time to type the sentence
+        #[serde(flatten)]
+        countdown: Countdown,
+        /// text typed by the watcher so far
+        typed: Option<String>,
+    },
+    AnswersResults {
+        index: usize,
+        count: usize,
+        title: String,
+        media: Option<Media>,
+        host_notes: Option<String>,
+        sentence: String,
+        speeds: TruncatedVec<(String, u64)>,
+        own_result: Option<TypingResult>,
+        timeline: Option<AnswerTimeline>,
+    },
+}
+
+/// number of characters typed at the same position as in the sentence
+fn accurate_characters(sentence: &str, typed: &str) -> usize {
+    sentence
+        .chars()
+        .zip(typed.chars())
+        .filter(|(expected, actual)| expected == actual)
+        .count()
+}
+
+impl State {
+    pub fn play<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        self.send_challenge_announcements(
+            watchers,
+            schedule_message,
+            tunnel_finder,
+            clock,
+            index,
+            count,
+        );
+    }
+
+    fn timer(&self, clock: &dyn Clock) -> SystemTime {
+        self.answer_start.unwrap_or(clock.now())
+    }
+
+    /// authoritative end of the answering window, independent of when the results alarm fires
+    fn answer_deadline(&self) -> Option<SystemTime> {
+        self.answer_start
+            .map(|answer_start| answer_start + self.config.time_limit)
+    }
+
+    /// text is only accepted while the sentence is shown and until the deadline
+    fn check_answer_window(&self, clock: &dyn Clock) -> Result<(), Error> {
+        if self.state != SlideState::Answers {
+            Err(Error::WrongPhase)
+        } else if self
+            .answer_deadline()
+            .is_none_or(|deadline| clock.now() > deadline)
+        {
+            Err(Error::TooLate)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// speed and accuracy of the text typed by the player, measured up to when it arrived
+    fn result_of(&self, watcher_id: Id) -> Option<TypingResult> {
+        let (typed, instant) = self.typed.get(&watcher_id)?;
+        let answer_start = self.answer_start?;
+        let accurate = accurate_characters(&self.config.sentence, typed);
+        let minutes = instant
+            .duration_since(answer_start)
+            .unwrap_or_default()
+            .as_secs_f64()
+            / 60.;
+
+        Some(TypingResult {
+            wpm: if minutes > 0. {
+                (accurate as f64 / CHARACTERS_PER_WORD / minutes) as u64
+            } else {
+                0
+            },
+            accuracy: (accurate * 100 / self.config.sentence.chars().count().max(1)) as u64,
+        })
+    }
+
+    fn own_result(&self, watcher_id: Id, watcher_kind: ValueKind) -> Option<TypingResult> {
+        self.result_of(watcher_id)
+            .filter(|_| watcher_kind == ValueKind::Player)
+    }
+
+    /// live speeds a reconnecting watcher would otherwise only get with the next keystroke
+    pub fn catch_up_updates(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        watchers: &Watchers,
+    ) -> Vec<crate::UpdateMessage> {
+        if self.state != SlideState::Answers {
+            return Vec::new();
+        }
+        match watcher_kind {
+            ValueKind::Host => vec![UpdateMessage::SpeedFeed(self.speeds(watchers)).into()],
+            ValueKind::Player => self
+                .result_of(watcher_id)
+                .map(|result| UpdateMessage::OwnSpeed(result).into())
+                .into_iter()
+                .collect(),
+            ValueKind::Unassigned => Vec::new(),
+        }
+    }
+
+    /// fastest players with their accurate words per minute
+    fn speeds(&self, watchers: &Watchers) -> TruncatedVec<(String, u64)> {
+        let speeds = self
+            .typed
+            .keys()
+            .filter_map(|id| {
+                Some((
+                    watchers.get_name(*id)?,
+                    self.result_of(*id).map(|result| result.wpm)?,
+                ))
+            })
+            .sorted_by_key(|(_, wpm)| std::cmp::Reverse(*wpm))
+            .collect_vec();
+        let exact_count = speeds.len();
+        TruncatedVec::new(speeds.into_iter(), FEED_SIZE, exact_count)
+    }
+
+    fn send_challenge_announcements<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watchers: &Watchers,
+        mut schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) {
+        if self.change_state(SlideState::Unstarted, SlideState::Answers) {
+            self.answer_start = Some(clock.now());
+
+            watchers.announce_with(
+                |_, watcher_kind| {
+                    Some(
+                        UpdateMessage::ChallengeAnnouncement {
+                            index,
+                            count,
+                            title: self.config.title.clone(),
+                            media: self.config.media.clone(),
+                            host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                            sentence: self.config.sentence.clone(),
+                            countdown: Countdown::new(clock, self.config.time_limit),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+
+            schedule_message(
+                AlarmMessage::ProceedFromSlideIntoSlide {
+                    index,
+                    to: SlideState::AnswersResults,
+                    generation: self.generation,
+                }
+                .into(),
+                self.config.time_limit,
+            )
+        }
+    }
+
+    fn change_state(&mut self, before: SlideState, after: SlideState) -> bool {
+        if self.state == before {
+            self.state = after;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// whether players can answer right now
+    pub fn accepting_answers(&self) -> bool {
+        self.state == SlideState::Answers
+    }
+
+    /// text a bot submits, the whole sentence or nothing of it
+    pub fn bot_answer(&self, correct: bool) -> Option<IncomingPlayerMessage> {
+        Some(IncomingPlayerMessage::StringAnswer(if correct {
+            self.config.sentence.clone()
+        } else {
+            String::new()
+        }))
+    }
+
+    /// whether the speeds are being shown
+    pub fn answers_revealed(&self) -> bool {
+        self.state == SlideState::AnswersResults
+    }
+
+    fn send_answers_results<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+    ) {
+        if self.change_state(SlideState::Answers, SlideState::AnswersResults) {
+            let speeds = self.speeds(watchers);
+
+            watchers.announce_with(
+                |watcher_id, watcher_kind| {
+                    Some(
+                        UpdateMessage::AnswersResults {
+                            speeds: speeds.clone(),
+                            own_result: self.own_result(watcher_id, watcher_kind),
+                            timeline: self.host_timeline(watcher_kind),
+                        }
+                        .into(),
+                    )
+                },
+                tunnel_finder,
+            );
+        }
+    }
+
+    /// when players last sent what they typed, `None` until the sentence is shown
+    pub fn answer_timeline(&self) -> Option<AnswerTimeline> {
+        let answer_start = self.answer_start?;
+        Some(AnswerTimeline::new(
+            answer_start,
+            self.config.time_limit,
+            self.typed.values().map(|(_, instant)| *instant),
+        ))
+    }
+
+    /// how long each player typed once the sentence was shown
+    pub fn response_times(&self) -> Vec<(Id, Duration)> {
+        let Some(answer_start) = self.answer_start else {
+            return Vec::new();
+        };
+        self.typed
+            .iter()
+            .map(|(id, (_, instant))| {
+                (
+                    *id,
+                    instant.duration_since(answer_start).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn host_timeline(&self, watcher_kind: ValueKind) -> Option<AnswerTimeline> {
+        self.answer_timeline()
+            .filter(|_| watcher_kind == ValueKind::Host)
+    }
+
+    /// what every player typed and whether it matches the sentence
+    pub fn submitted_answers(&self) -> impl Iterator<Item = (Id, String, bool)> + '_ {
+        self.typed
+            .iter()
+            .map(|(id, (typed, _))| (*id, typed.clone(), *typed == self.config.sentence))
+    }
+
+    /// points for the speed, full points at the target speed or beyond
+    fn score(&self, result: TypingResult) -> u64 {
+        self.config.points_awarded * result.wpm.min(self.config.target_wpm) / self.config.target_wpm
+    }
+
+    fn add_scores<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        tunnel_finder: F,
+    ) {
+        leaderboard.add_scores(
+            &self
+                .typed
+                .keys()
+                .map(|id| (*id, self.result_of(*id).map_or(0, |r| self.score(r))))
+                .into_grouping_map_by(|(id, _)| {
+                    let player_id = *id;
+                    match &team_manager {
+                        Some(team_manager) => team_manager.get_team(player_id).unwrap_or(player_id),
+                        None => player_id,
+                    }
+                })
+                .min_by_key(|_, (_, score)| *score)
+                .into_iter()
+                .map(|(id, (_, score))| (id, score))
+                .chain(
+                    {
+                        match &team_manager {
+                            Some(team_manager) => team_manager.all_ids(),
+                            None => watchers
+                                .present_players(tunnel_finder)
+                                .into_iter()
+                                .collect_vec(),
+                        }
+                    }
+                    .into_iter()
+                    .map(|id| (id, 0)),
+                )
+                .unique_by(|(id, _)| *id)
+                .collect_vec(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn state_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        _team_manager: Option<&TeamManager>,
+        watchers: &Watchers,
+        _tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> SyncMessage {
+        match self.state {
+            SlideState::Unstarted | SlideState::Answers => SyncMessage::ChallengeAnnouncement {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                sentence: self.config.sentence.clone(),
+                countdown: Countdown::new(
+                    clock,
+                    self.config.time_limit.saturating_sub(
+                        clock
+                            .now()
+                            .duration_since(self.timer(clock))
+                            .unwrap_or_default(),
+                    ),
+                ),
+                typed: self.typed.get(&watcher_id).map(|(typed, _)| typed.clone()),
+            },
+            SlideState::AnswersResults => SyncMessage::AnswersResults {
+                index,
+                count,
+                title: self.config.title.clone(),
+                media: self.config.media.clone(),
+                host_notes: host_notes(self.config.host_notes.as_deref(), watcher_kind),
+                sentence: self.config.sentence.clone(),
+                speeds: self.speeds(watchers),
+                own_result: self.own_result(watcher_id, watcher_kind),
+                timeline: self.host_timeline(watcher_kind),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(crate::AlarmMessage, time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        message: IncomingMessage,
+        leaderboard: &mut Leaderboard,
+        watchers: &Watchers,
+        team_manager: Option<&TeamManager>,
+        schedule_message: S,
+        tunnel_finder: F,
+        clock: &dyn Clock,
+        index: usize,
+        count: usize,
+    ) -> Result<bool, Error> {
+        match message {
+            IncomingMessage::Host(IncomingHostMessage::Next) => match self.state {
+                SlideState::Unstarted => {
+                    self.send_challenge_announcements(
+                        watchers,
+                        schedule_message,
+                        tunnel_finder,
+                        clock,
+                        index,
+                        count,
+                    );
+                }
+                SlideState::Answers => {
+                    self.send_answers_results(watchers, tunnel_finder);
+                }
+                SlideState::AnswersResults => {
+                    self.add_scores(leaderboard, watchers, team_manager, tunnel_finder);
+                    return Ok(true);
+                }
+            },
+            IncomingMessage::Player(IncomingPlayerMessage::StringAnswer(typed)) => {
+                self.check_answer_window(clock)?;
+
+                // the speed of a finished sentence is kept
+                if self
+                    .typed
+                    .get(&watcher_id)
+                    .is_some_and(|(previous, _)| *previous == self.config.sentence)
+                {
+                    return Ok(false);
+                }
+
+                self.typed.insert(watcher_id, (typed, clock.now()));
+
+                if let Some(result) = self.result_of(watcher_id) {
+                    watchers.send_message(
+                        &UpdateMessage::OwnSpeed(result).into(),
+                        watcher_id,
+                        &tunnel_finder,
+                    );
+                }
+
+                let finished: HashSet<_> = self
+                    .typed
+                    .iter()
+                    .filter(|(_, (typed, _))| *typed == self.config.sentence)
+                    .map(|(id, _)| *id)
+                    .collect();
+                if watchers
+                    .present_players(&tunnel_finder)
+                    .is_subset(&finished)
+                {
+                    self.send_answers_results(watchers, &tunnel_finder);
+                } else {
+                    watchers.announce_specific(
+                        ValueKind::Host,
+                        &UpdateMessage::SpeedFeed(self.speeds(watchers)).into(),
+                        &tunnel_finder,
+                    );
+                }
+            }
+            _ => return Err(Error::WrongPhase),
+        };
+
+        Ok(false)
+    }
+
+    pub fn receive_alarm<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watchers: &Watchers,
+        tunnel_finder: F,
+        message: crate::AlarmMessage,
+    ) -> bool {
+        if let crate::AlarmMessage::Typing(AlarmMessage::ProceedFromSlideIntoSlide {
+            to: SlideState::AnswersResults,
+            generation,
+            ..
+        }) = message
+        {
+            if generation == self.generation {
+                self.send_answers_results(watchers, tunnel_finder);
+            }
+        }
+
+        false
+    }
+}
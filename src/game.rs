@@ -1,24 +1,55 @@
-use std::{collections::HashSet, fmt::Debug};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+    sync::Arc,
+};
 
 use garde::Validate;
 use heck::ToTitleCase;
 use itertools::Itertools;
+use rustrict::CensorStr;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use thiserror::Error;
+use web_time::{Duration, SystemTime};
 
 use crate::{
-    fuiz::{config::CurrentSlide, order, type_answer},
+    answer_lock::{AnswerLock, AuditEntry, Check, MAX_AUDIT_ENTRIES},
+    auto_lock::{self, AutoLockOptions},
+    bots::{self, BotOptions},
+    clock::{self, Clock},
+    events::{GameEvent, Observer},
+    fingerprint::Fingerprints,
+    fuiz::{
+        config::{
+            AnswerTimeline, CurrentSlide, FuizInfo, OwnAnswer, Round, RoundScoring,
+            SectionProgress, SlideConfig, SlideState, SubmittedAnswer,
+        },
+        crossword, intermission, memory_match, order, plugin,
+        source::QuestionSource,
+        type_answer, typing,
+    },
+    highlights::Highlights,
+    presence::{self, Presence},
+    recap::{Recap, RecapSlide},
+    reminders::{self, Reminder},
+    results::{self, Payload, PlayerResult},
+    speed_check::{FlaggedPlayer, SpeedCheck},
+    stats::{GameStats, StatsReport},
+    theme::Theme,
+    tombstone::Tombstone,
     watcher::Value,
 };
 
 use super::{
     fuiz::{config::Fuiz, multiple_choice},
-    leaderboard::{Leaderboard, ScoreMessage},
+    leaderboard::{self, Leaderboard, ScoreMessage},
     names::{self, Names},
-    session::Tunnel,
+    ratings::{self, ProfileId},
+    session::{CloseReason, Tunnel},
     teams::{self, TeamManager},
     watcher::{self, Id, PlayerValue, ValueKind, Watchers},
-    AlarmMessage, TruncatedVec,
+    AlarmMessage, ErrorCode, TruncatedVec,
 };
 
 /// Game Phase
@@ -30,9 +61,16 @@ pub enum State {
     TeamDisplay,
     Slide(Box<CurrentSlide>),
     Leaderboard(usize),
+    /// (DOUBLE OR NOTHING ONLY): Players decide whether to stake their score before the final
+    /// slide at this index
+    Stake(usize),
     Done,
 }
 
+/// how long players are kept waiting for a disconnected host before the game is finished
+const HOST_GRACE_PERIOD: Duration =
+    Duration::from_secs(crate::CONFIG.fuiz.host_grace_period.unsigned_abs());
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Validate)]
 pub struct TeamOptions {
     /// maximum initial team size
@@ -41,9 +79,13 @@ pub struct TeamOptions {
     /// whether to assign people to random teams or let them choose their preferences
     #[garde(skip)]
     assign_random: bool,
+    /// how the answers of multiple choice questions are shown to the members of a team
+    #[garde(skip)]
+    #[serde(default)]
+    answer_split: teams::AnswerSplit,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
 pub struct Options {
     /// using random names for players (skips choosing names)
     #[garde(skip)]
@@ -55,13 +97,129 @@ pub struct Options {
     no_leaderboard: bool,
     #[garde(dive)]
     teams: Option<TeamOptions>,
+    /// lowers the number of players allowed in this game below the server limit
+    #[garde(range(min = 1, max = watcher::MAX_PLAYERS))]
+    #[serde(default)]
+    max_players: Option<usize>,
+    /// lowers the number of watchers allowed to wait unassigned below the server limit
+    #[garde(range(min = 1, max = watcher::MAX_UNASSIGNED))]
+    #[serde(default)]
+    max_unassigned: Option<usize>,
+    /// fills the game with players driven by the server, bots play individually so they are
+    /// left out of games with teams
+    #[garde(dive)]
+    #[serde(default)]
+    bots: Option<BotOptions>,
+    /// evens out the scores of players joining once the game is underway
+    #[garde(skip)]
+    #[serde(default)]
+    late_join_handicap: Option<LateJoinHandicap>,
+    /// lets players stake their score on the final slide, doubling it if they get it right and
+    /// halving it otherwise
+    #[garde(skip)]
+    #[serde(default)]
+    double_or_nothing: bool,
+    /// hides player names from everyone but the host on the leaderboard
+    #[garde(skip)]
+    #[serde(default)]
+    anonymous_leaderboard: Option<names::AliasStyle>,
+    /// boosts points earned by trailing players, by this percentage for the last place and
+    /// proportionally less for those ahead of them
+    #[garde(range(min = 1, max = leaderboard::MAX_COMEBACK_BONUS))]
+    #[serde(default)]
+    comeback_bonus_percent: Option<u64>,
+    /// updates the ratings of players who linked a profile once the game is over
+    #[garde(skip)]
+    #[serde(default)]
+    rated: bool,
+    /// receives the results of every player once the game is over
+    #[garde(dive)]
+    #[serde(default)]
+    results_webhook: Option<results::Webhook>,
+    /// lists the game in the public lobby directory while it can be joined
+    #[garde(skip)]
+    #[serde(default)]
+    public: bool,
+    /// asked to watchers before they can pick a name
+    #[garde(length(chars, min = 1, max = MAX_PASSWORD_LENGTH))]
+    #[serde(default)]
+    password: Option<String>,
+    /// locks the game once enough players joined or enough time passed since the first did
+    #[garde(dive)]
+    #[serde(default)]
+    auto_lock: Option<AutoLockOptions>,
+    /// runs the game as a poll: correct answers are dropped, no points are awarded and the
+    /// leaderboard is never shown
+    #[garde(skip)]
+    #[serde(default)]
+    survey_mode: bool,
+    /// styles every screen of the game the same, for hosts and players alike
+    #[garde(dive)]
+    #[serde(default)]
+    theme: Option<Theme>,
+    /// lets a watcher stay connected from several devices at once instead of the latest
+    /// connection superseding the previous one
+    #[garde(skip)]
+    #[serde(default)]
+    multi_device: bool,
+    /// keeps the first answer of every player to a slide, attempts to change it are refused and
+    /// reported to the host
+    #[garde(skip)]
+    #[serde(default)]
+    lock_answers: bool,
+    /// leaves players who answered implausibly fast out of the final positions and highlights
+    #[garde(skip)]
+    #[serde(default)]
+    exclude_flagged: bool,
+    /// warns the host when several players join from the same device
+    #[garde(skip)]
+    #[serde(default)]
+    one_player_per_device: bool,
+    /// seed of the random choices of the game, to reproduce one that was exported
+    #[garde(skip)]
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+impl Options {
+    fn limits(&self) -> watcher::Limits {
+        let defaults = watcher::Limits::default();
+        watcher::Limits {
+            players: self.max_players.unwrap_or(defaults.players),
+            unassigned: self.max_unassigned.unwrap_or(defaults.unassigned),
+        }
+    }
+
+    /// whether scores are kept from players
+    fn hides_leaderboard(&self) -> bool {
+        self.no_leaderboard || self.survey_mode
+    }
+}
+
+/// How players joining once slides were scored are given a chance to catch up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LateJoinHandicap {
+    /// scales the points they earn by the share of scored slides they missed
+    Scale,
+    /// starts them with the median score of the players at the time they joined
+    SeedMedian,
+}
+
+/// Public game as shown in the lobby directory, the server adds the game code
+#[derive(Debug, Serialize, Clone)]
+pub struct LobbyListing {
+    #[serde(flatten)]
+    pub fuiz: FuizInfo,
+    pub player_count: usize,
+    /// whether players would join a game already underway
+    pub started: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 /// one game session
 pub struct Game {
-    /// configuration to create the game
-    fuiz_config: Fuiz,
+    /// configuration to create the game, shared with summary messages
+    fuiz_config: Arc<Fuiz>,
     /// set of watchers listening to message actions
     pub watchers: Watchers,
     /// mapping of names used in the game
@@ -74,6 +232,109 @@ pub struct Game {
     /// indicates if a game is locked so new players aren't able to enter
     locked: bool,
     team_manager: Option<TeamManager>,
+    /// source of time for slide timers, replaced by a virtual clock in tests
+    #[serde(skip, default = "clock::system")]
+    clock: Arc<dyn Clock>,
+    /// last time any watcher interacted with the game
+    #[serde(default = "SystemTime::now")]
+    last_activity: SystemTime,
+    /// last time the host was seen with a live tunnel
+    #[serde(default = "SystemTime::now")]
+    host_last_seen: SystemTime,
+    /// incremented for every slide run, tags alarms so stale ones are ignored
+    #[serde(default)]
+    slide_generation: u64,
+    /// set once the game is closed, replayed to watchers reconnecting afterwards
+    #[serde(default)]
+    closed: Option<GameClosedReason>,
+    /// notified of the milestones of the game
+    #[serde(skip)]
+    observers: Vec<Arc<dyn Observer>>,
+    /// last time each watcher was sent an error reply, limits how often rejections are echoed
+    #[serde(skip)]
+    error_replies: HashMap<Id, SystemTime>,
+    /// hands out slides as the game reaches them, the slides of the fuiz are played otherwise
+    #[serde(skip)]
+    source: Option<Box<dyn QuestionSource>>,
+    /// players, or teams, who staked their score on the final slide
+    #[serde(default)]
+    stakes: HashSet<Id>,
+    /// when answers arrived on each finished slide, shown to the host in the summary
+    #[serde(default)]
+    timelines: Vec<Option<AnswerTimeline>>,
+    /// how long each player took on every slide they answered
+    #[serde(default)]
+    response_times: HashMap<Id, Vec<Duration>>,
+    /// what each player answered on every finished slide, for recaps
+    #[serde(default)]
+    answers: Vec<HashMap<Id, OwnAnswer<SubmittedAnswer>>>,
+    /// profiles presented by returning players, rated once the game is over
+    #[serde(default)]
+    profiles: HashMap<Id, ProfileId>,
+    /// set once the rating changes of the game were saved, they're never applied twice
+    #[serde(default)]
+    ratings_recorded: bool,
+    /// watchers who gave the password of the game
+    #[serde(default)]
+    admitted: HashSet<Id>,
+    /// wrong passwords given by each watcher, with the time of the latest one
+    #[serde(default)]
+    password_attempts: HashMap<Id, (u64, SystemTime)>,
+    /// wrong passwords given by anyone, reconnecting as a new watcher doesn't reset them
+    #[serde(default)]
+    game_password_attempts: Option<(u64, SystemTime)>,
+    /// when the first player joined, starts the delay of the automatic lock
+    #[serde(default)]
+    first_join: Option<SystemTime>,
+    /// set once the automatic lock went off, it never locks the game twice
+    #[serde(default)]
+    auto_locked: bool,
+    /// whether the alarm checking the delay of the automatic lock was scheduled
+    #[serde(default)]
+    auto_lock_scheduled: bool,
+    /// watchers waiting for a spot in a full lobby, first in line first
+    #[serde(default)]
+    join_queue: VecDeque<Id>,
+    /// id of the latest [`IncomingHostMessage::NextOnce`] applied
+    #[serde(default)]
+    last_host_action: Option<u64>,
+    /// phases left by the latest host transitions, emptied once a slide is scored
+    #[serde(default)]
+    undo_stack: Vec<State>,
+    /// operational figures reported to the server
+    #[serde(default)]
+    stats: GameStats,
+    /// last time each player sent a message to their team
+    #[serde(skip)]
+    team_chat_sent: HashMap<Id, SystemTime>,
+    /// choice of the host to show answers on player devices for the current slide
+    #[serde(default)]
+    show_answers_override: Option<bool>,
+    /// last interaction of every player, telling away players from active ones
+    #[serde(default)]
+    presence: Presence,
+    /// answers given to the current slide when they are locked in
+    #[serde(default)]
+    answer_lock: AnswerLock,
+    /// attempts to change a locked answer, oldest first, the first of each player on a slide
+    #[serde(default)]
+    audit: Vec<AuditEntry>,
+    /// latencies of the players and the answers they gave implausibly fast
+    #[serde(default)]
+    speed_check: SpeedCheck,
+    /// devices the watchers connect from, only kept when one player per device is expected
+    #[serde(default)]
+    fingerprints: Fingerprints,
+    /// every random choice of the game derives from it, so snapshots and replays make the same
+    #[serde(default = "random_seed")]
+    seed: u64,
+    /// random generators derived from the seed so far
+    #[serde(default)]
+    draws: u64,
+}
+
+fn random_seed() -> u64 {
+    fastrand::u64(..)
 }
 
 impl Debug for Game {
@@ -90,30 +351,168 @@ pub enum IncomingMessage {
     Host(IncomingHostMessage),
     Unassigned(IncomingUnassignedMessage),
     Player(IncomingPlayerMessage),
+    /// asks for the server time, answered with the same nonce so the client can estimate the
+    /// round trip and the offset of its clock
+    TimeSync(u64),
 }
 
+const PASSWORD_CONFIG: crate::config::fuiz::password::PasswordConfig = crate::CONFIG.fuiz.password;
+
+const MAX_PASSWORD_LENGTH: usize = PASSWORD_CONFIG.max_length.unsigned_abs() as usize;
+const MAX_PASSWORD_ATTEMPTS: u64 = PASSWORD_CONFIG.max_attempts.unsigned_abs();
+const MAX_GAME_PASSWORD_ATTEMPTS: u64 = PASSWORD_CONFIG.max_game_attempts.unsigned_abs();
+const PASSWORD_COOLDOWN: Duration = Duration::from_secs(PASSWORD_CONFIG.cooldown.unsigned_abs());
+
+const TEAM_CHAT_CONFIG: crate::config::fuiz::team_chat::TeamChatConfig =
+    crate::CONFIG.fuiz.team_chat;
+
+/// longest message sent to teammates, in characters
+const MAX_TEAM_CHAT_LENGTH: usize = TEAM_CHAT_CONFIG.max_length.unsigned_abs() as usize;
+/// shortest time between two messages of a player to their team
+const TEAM_CHAT_INTERVAL: Duration =
+    Duration::from_millis(TEAM_CHAT_CONFIG.min_interval_ms.unsigned_abs());
+
+/// most host transitions that can be undone in a row
+const MAX_UNDO_DEPTH: usize = crate::CONFIG.fuiz.undo.max_depth.unsigned_abs() as usize;
+
+/// most watchers waiting for a spot in a full lobby
+const MAX_JOIN_QUEUE_LENGTH: usize =
+    crate::CONFIG.fuiz.join_queue.max_length.unsigned_abs() as usize;
+
+const INCOMING_CONFIG: crate::config::fuiz::incoming::IncomingConfig = crate::CONFIG.fuiz.incoming;
+
+/// longest string accepted inside an incoming message, in bytes
+const MAX_INCOMING_STRING_LENGTH: usize = INCOMING_CONFIG.max_string_length.unsigned_abs() as usize;
+/// most items accepted inside a list of an incoming message
+const MAX_INCOMING_LIST_LENGTH: usize = INCOMING_CONFIG.max_list_length.unsigned_abs() as usize;
+
 impl IncomingMessage {
+    /// whether the strings and lists carried by the message are small enough to be handled,
+    /// anything bigger cannot be a legitimate answer
+    fn within_limits(&self) -> bool {
+        match self {
+            Self::Unassigned(
+                IncomingUnassignedMessage::NameRequest(s) | IncomingUnassignedMessage::Password(s),
+            ) => s.len() <= MAX_INCOMING_STRING_LENGTH,
+            Self::Player(message) => message.within_limits(),
+            Self::Ghost(_) | Self::Host(_) | Self::TimeSync(_) => true,
+        }
+    }
+
+    /// short name of the message, sent back as context when it is rejected
+    fn label(&self) -> &'static str {
+        match self {
+            Self::TimeSync(_) => "TimeSync",
+            Self::Ghost(IncomingGhostMessage::DemandId) => "DemandId",
+            Self::Ghost(IncomingGhostMessage::ClaimId(_)) => "ClaimId",
+            Self::Host(IncomingHostMessage::Next) => "Next",
+            Self::Host(IncomingHostMessage::Index(_)) => "Index",
+            Self::Host(IncomingHostMessage::Lock(_)) => "Lock",
+            Self::Host(IncomingHostMessage::PreviewNext) => "PreviewNext",
+            Self::Host(IncomingHostMessage::NextOnce(_)) => "NextOnce",
+            Self::Host(IncomingHostMessage::Undo) => "Undo",
+            Self::Host(IncomingHostMessage::ShowAnswers(_)) => "ShowAnswers",
+            Self::Host(IncomingHostMessage::Reask) => "Reask",
+            Self::Unassigned(IncomingUnassignedMessage::NameRequest(_)) => "NameRequest",
+            Self::Unassigned(IncomingUnassignedMessage::Password(_)) => "Password",
+            Self::Player(IncomingPlayerMessage::IndexAnswer(_)) => "IndexAnswer",
+            Self::Player(IncomingPlayerMessage::StringAnswer(_)) => "StringAnswer",
+            Self::Player(IncomingPlayerMessage::StringArrayAnswer(_)) => "StringArrayAnswer",
+            Self::Player(IncomingPlayerMessage::ChooseTeammates(_)) => "ChooseTeammates",
+            Self::Player(IncomingPlayerMessage::Stake(_)) => "Stake",
+            Self::Player(IncomingPlayerMessage::Leave) => "Leave",
+            Self::Player(IncomingPlayerMessage::Present) => "Present",
+            Self::Player(IncomingPlayerMessage::TeamChat(_)) => "TeamChat",
+            Self::Player(IncomingPlayerMessage::ClueAnswer { .. }) => "ClueAnswer",
+            Self::Player(IncomingPlayerMessage::PairAnswer(..)) => "PairAnswer",
+            Self::Player(IncomingPlayerMessage::SlideAnswer { .. }) => "SlideAnswer",
+        }
+    }
+
     fn follows(&self, sender_kind: ValueKind) -> bool {
         matches!(
             (self, sender_kind),
             (IncomingMessage::Host(_), ValueKind::Host)
                 | (IncomingMessage::Player(_), ValueKind::Player)
                 | (IncomingMessage::Unassigned(_), ValueKind::Unassigned)
+                | (IncomingMessage::TimeSync(_), _)
         )
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Hash)]
 pub enum IncomingPlayerMessage {
     IndexAnswer(usize),
     StringAnswer(String),
     StringArrayAnswer(Vec<String>),
     ChooseTeammates(Vec<String>),
+    /// whether the player stakes their score on the final slide
+    Stake(bool),
+    /// leaves the game while it is still in the lobby, freeing the spot for someone queued
+    Leave,
+    /// (TEAM ONLY) short text shown to teammates while answers are accepted
+    TeamChat(String),
+    /// answers [`UpdateMessage::PresenceCheck`]
+    Present,
+    /// word for the clue at index `clue` of a crossword
+    ClueAnswer {
+        clue: usize,
+        answer: String,
+    },
+    /// positions of two cards the player believes match
+    PairAnswer(usize, usize),
+    /// answer meant for the slide at `index`, dropped if another slide is running by the time
+    /// it arrives
+    SlideAnswer {
+        index: usize,
+        answer: Box<IncomingPlayerMessage>,
+    },
+}
+
+impl IncomingPlayerMessage {
+    /// the answer carried by the message, checked to be meant for the slide at `index`
+    fn for_slide(self, index: usize) -> Result<Self, Error> {
+        match self {
+            Self::SlideAnswer {
+                index: answer_index,
+                answer,
+            } => match *answer {
+                _ if answer_index != index => Err(Error::WrongSlide),
+                answer @ (Self::IndexAnswer(_)
+                | Self::StringAnswer(_)
+                | Self::StringArrayAnswer(_)
+                | Self::ClueAnswer { .. }
+                | Self::PairAnswer(..)) => Ok(answer),
+                _ => Err(Error::InvalidAnswer),
+            },
+            message => Ok(message),
+        }
+    }
+
+    fn within_limits(&self) -> bool {
+        let string_fits = |s: &String| s.len() <= MAX_INCOMING_STRING_LENGTH;
+        let list_fits =
+            |v: &Vec<String>| v.len() <= MAX_INCOMING_LIST_LENGTH && v.iter().all(string_fits);
+
+        match self {
+            Self::StringAnswer(s) | Self::ClueAnswer { answer: s, .. } => string_fits(s),
+            Self::TeamChat(s) => s.chars().count() <= MAX_TEAM_CHAT_LENGTH,
+            Self::StringArrayAnswer(v) | Self::ChooseTeammates(v) => list_fits(v),
+            Self::SlideAnswer { answer, .. } => answer.within_limits(),
+            Self::IndexAnswer(_)
+            | Self::PairAnswer(..)
+            | Self::Stake(_)
+            | Self::Leave
+            | Self::Present => true,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub enum IncomingUnassignedMessage {
     NameRequest(String),
+    /// password of the game, required before requesting a name when the game has one
+    Password(String),
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -127,6 +526,113 @@ pub enum IncomingHostMessage {
     Next,
     Index(usize),
     Lock(bool),
+    /// asks for the slide coming after the current one, answered only to the host
+    PreviewNext,
+    /// [`IncomingHostMessage::Next`] tagged with an id the client picks for each screen it
+    /// shows, a repeated id is not applied again and the current state is sent back instead
+    NextOnce(u64),
+    /// goes back to the phase before the latest Next, as long as nothing was scored since
+    Undo,
+    /// runs the current slide again from the start, dropping its answers and any points they
+    /// earned
+    Reask,
+    /// shows or hides the answers on player devices once the results of the current slide are
+    /// out, overriding the game option until the next slide
+    ShowAnswers(bool),
+}
+
+/// Why a game stopped accepting watchers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameClosedReason {
+    /// the host went through the whole game
+    Finished,
+    /// the game was inactive for too long and got removed
+    Expired,
+    /// the host left and the game was removed
+    HostLeft,
+    /// the server is going down
+    Shutdown,
+    /// handling a message failed unexpectedly, the game was quarantined
+    Crashed,
+}
+
+impl GameClosedReason {
+    fn close_reason(self) -> CloseReason {
+        match self {
+            Self::Shutdown => CloseReason::ServerShutdown,
+            Self::Crashed => CloseReason::InternalError,
+            Self::Finished | Self::Expired | Self::HostLeft => CloseReason::GameEnded,
+        }
+    }
+}
+
+/// Reason an incoming message was rejected
+#[derive(Error, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("message is not allowed for this watcher")]
+    NotAllowed,
+    #[error("game is locked")]
+    Locked,
+    #[error("message does not apply to the current phase")]
+    WrongPhase,
+    #[error("answer arrived after the time limit")]
+    TooLate,
+    #[error("answer is not valid for this slide")]
+    InvalidAnswer,
+    #[error("answer was meant for another slide")]
+    WrongSlide,
+    #[error("nothing to undo")]
+    NothingToUndo,
+    #[error("messages are sent too quickly")]
+    TooFast,
+    #[error("game has no room for more players")]
+    Full,
+    #[error("message is too large")]
+    TooLarge,
+    #[error("only the host can send this message")]
+    NotHost,
+    #[error("watcher is not part of the game")]
+    UnknownWatcher,
+    #[error("game requires a password")]
+    PasswordRequired,
+    #[error("password is incorrect")]
+    WrongPassword,
+    #[error("too many wrong passwords, try again later")]
+    TooManyAttempts,
+    #[error("answer is locked and cannot be changed")]
+    AnswerLocked,
+    #[error("slide could not be started")]
+    SlideUnavailable,
+    #[error(transparent)]
+    Name(#[from] names::Error),
+}
+
+impl Error {
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Self::NotAllowed => ErrorCode::new("game.not_allowed"),
+            Self::Locked => ErrorCode::new("game.locked"),
+            Self::WrongPhase => ErrorCode::new("game.wrong_phase"),
+            Self::TooLate => ErrorCode::new("game.too_late"),
+            Self::InvalidAnswer => ErrorCode::new("game.invalid_answer"),
+            Self::WrongSlide => ErrorCode::new("game.wrong_slide"),
+            Self::NothingToUndo => ErrorCode::new("game.nothing_to_undo"),
+            Self::TooFast => ErrorCode::new("game.too_fast"),
+            Self::Full => ErrorCode::new("game.full"),
+            Self::TooLarge => ErrorCode::new("game.too_large")
+                .with("max_string_length", MAX_INCOMING_STRING_LENGTH as u64)
+                .with("max_list_length", MAX_INCOMING_LIST_LENGTH as u64),
+            Self::NotHost => ErrorCode::new("game.not_host"),
+            Self::UnknownWatcher => ErrorCode::new("game.unknown_watcher"),
+            Self::PasswordRequired => ErrorCode::new("game.password_required"),
+            Self::WrongPassword => ErrorCode::new("game.wrong_password"),
+            Self::TooManyAttempts => ErrorCode::new("game.too_many_attempts")
+                .with("cooldown_seconds", PASSWORD_COOLDOWN.as_secs()),
+            Self::AnswerLocked => ErrorCode::new("game.answer_locked"),
+            Self::SlideUnavailable => ErrorCode::new("game.slide_unavailable"),
+            Self::Name(e) => e.code(),
+        }
+    }
 }
 
 #[skip_serializing_none]
@@ -136,8 +642,26 @@ pub enum UpdateMessage {
     WaitingScreen(TruncatedVec<String>),
     TeamDisplay(TruncatedVec<String>),
     NameChoose,
+    /// the game has a password, sent to unassigned watchers before they can choose a name
+    PasswordRequest,
+    /// the game is full, position of the watcher in the queue of those waiting for a spot
+    /// (0-indexing)
+    QueuePosition(usize),
+    /// (TEAM ONLY) message from a teammate, sent to every member of the team
+    TeamChat {
+        from: String,
+        text: String,
+    },
+    /// (HOST ONLY) teams with their members, sent whenever membership changes
+    TeamRoster(Vec<teams::RosterTeam>),
+    /// (HOST ONLY) every team was full so a new one was created for a late joiner
+    TeamCreated(String),
+    /// (HOST ONLY) the game is still waiting in the lobby
+    LobbyReminder(Reminder),
+    /// (HOST ONLY) the game locked itself after meeting a condition of the automatic lock
+    AutoLocked,
     NameAssign(String),
-    NameError(names::Error),
+    NameError(ErrorCode),
     Leaderboard {
         leaderboard: LeaderboardMessage,
     },
@@ -150,6 +674,55 @@ pub enum UpdateMessage {
         max_selection: usize,
         available: Vec<(String, bool)>,
     },
+    Error {
+        #[serde(flatten)]
+        error: ErrorCode,
+        context: Option<String>,
+    },
+    GameClosed {
+        reason: GameClosedReason,
+    },
+    /// points a player would stake on the final slide
+    StakeOffer {
+        points: u64,
+    },
+    /// number of players who staked their score so far
+    StakeCount(usize),
+    /// (HOST ONLY) the upcoming slide with its answers, `None` if it's the last one or not
+    /// picked yet
+    NextSlidePreview {
+        index: usize,
+        slide: Option<SlideConfig>,
+    },
+    /// reply to [`IncomingMessage::TimeSync`]
+    TimeSync {
+        nonce: u64,
+        server_time: SystemTime,
+    },
+    /// a new section of the fuiz begins with the upcoming slide
+    SectionStarted {
+        title: String,
+        section: usize,
+        sections: usize,
+    },
+    /// position of the upcoming slide within its section
+    SectionProgress(SectionProgress),
+    /// (PLAYER ONLY) asks a player who hasn't interacted for a few slides whether they're still
+    /// there, answered with [`IncomingPlayerMessage::Present`]
+    PresenceCheck,
+    /// (HOST ONLY) connected players split between those taking part and those away
+    PlayerActivity(presence::Activity),
+    /// (PLAYER ONLY) whether the answers of the current slide are shown on the device
+    ShowAnswers(bool),
+    /// (HOST ONLY) a player tried to change an answer locked in
+    AnswerTampered(AuditEntry),
+    /// (HOST ONLY) names of players who joined from the same device
+    SharedDevice(Vec<String>),
+    /// (PLAYER ONLY) the player's answer to the slide got recorded at that instant
+    AnswerRecorded {
+        index: usize,
+        at: SystemTime,
+    },
 }
 
 #[skip_serializing_none]
@@ -168,6 +741,8 @@ pub enum SyncMessage {
         score: Option<ScoreMessage>,
     },
     Metainfo(MetainfoMessage),
+    /// (HOST ONLY) everything the host screen shows, sent when the host reconnects
+    HostDashboard(Box<HostDashboard>),
     Summary(SummaryMessage),
     NotAllowed,
     FindTeam(String),
@@ -175,6 +750,17 @@ pub enum SyncMessage {
         max_selection: usize,
         available: Vec<(String, bool)>,
     },
+    StakeOffer {
+        index: usize,
+        count: usize,
+        points: u64,
+        staked: bool,
+    },
+    StakeCount {
+        index: usize,
+        count: usize,
+        staked: usize,
+    },
 }
 
 #[skip_serializing_none]
@@ -183,36 +769,141 @@ pub enum SummaryMessage {
     Player {
         score: Option<ScoreMessage>,
         points: Vec<u64>,
-        config: Fuiz,
+        config: Arc<Fuiz>,
     },
     Host {
         stats: Vec<(usize, usize)>,
         player_count: usize,
-        config: Fuiz,
-        options: Options,
+        config: Arc<Fuiz>,
+        options: Box<Options>,
+        /// when answers arrived on each slide, `None` for slides without answers
+        timelines: Vec<Option<AnswerTimeline>>,
+        highlights: Box<Highlights>,
+        /// players who answered implausibly fast
+        flagged: Vec<FlaggedPlayer>,
     },
 }
 
+/// Where a player finished, their summary once paired with the fuiz
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FinalStanding {
+    /// `None` when the game hides the leaderboard
+    pub score: Option<ScoreMessage>,
+    pub points: Vec<u64>,
+}
+
+impl FinalStanding {
+    pub fn summary(self, config: Arc<Fuiz>) -> SummaryMessage {
+        SummaryMessage::Player {
+            score: self.score,
+            points: self.points,
+            config,
+        }
+    }
+}
+
+/// State of the game as the host sees it, in a single message
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+pub struct HostDashboard {
+    /// synchronization of the current phase, with the live updates of a slide underway
+    pub state: super::SyncMessage,
+    /// players who answered the current slide so far
+    pub answered_count: Option<usize>,
+    /// `None` when the leaderboard is hidden
+    pub leaderboard: Option<LeaderboardMessage>,
+    /// every player of the game, connected or not
+    pub player_count: usize,
+    pub activity: presence::Activity,
+    pub locked: bool,
+    pub fuiz: FuizInfo,
+    pub theme: Option<Theme>,
+    /// attempts to change a locked answer, oldest first
+    pub audit: Vec<AuditEntry>,
+}
+
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Clone)]
 pub enum MetainfoMessage {
-    Host { locked: bool },
-    Player { score: u64, show_answers: bool },
+    Host {
+        locked: bool,
+        fuiz: FuizInfo,
+        theme: Option<Theme>,
+    },
+    Player {
+        score: u64,
+        show_answers: bool,
+        fuiz: FuizInfo,
+        theme: Option<Theme>,
+    },
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Clone)]
 pub struct LeaderboardMessage {
     pub current: TruncatedVec<(String, u64)>,
     pub prior: TruncatedVec<(String, u64)>,
+    /// (TEAM ONLY) names of the members of each team in `current`, in the same order
+    pub members: Option<Vec<TruncatedVec<String>>>,
+    /// standings of the round underway, `None` outside of rounds
+    pub round: Option<RoundStandings>,
+}
+
+/// Totals of the slides of the round underway, shown alongside the overall standings
+#[derive(Debug, Serialize, Clone)]
+pub struct RoundStandings {
+    #[serde(flatten)]
+    pub round: Round,
+    pub scores: TruncatedVec<(String, u64)>,
 }
 
 // Convenience methods
 impl Game {
+    /// registers an observer, telling it the game was created before every later event
+    pub fn subscribe(&mut self, observer: Arc<dyn Observer>) {
+        observer.on_event(&GameEvent::Created {
+            slides: self.fuiz_config.len(),
+        });
+        self.observers.push(observer);
+    }
+
+    fn emit(&self, event: &GameEvent) {
+        for observer in &self.observers {
+            observer.on_event(event);
+        }
+    }
+
     fn set_state(&mut self, game_state: State) {
         self.state = game_state;
     }
 
+    /// points and position of the player, excluded players are ranked behind everyone else
     fn score(&self, watcher_id: Id) -> Option<ScoreMessage> {
-        self.leaderboard.score(self.leaderboard_id(watcher_id))
+        let id = self.leaderboard_id(watcher_id);
+        let score = self.leaderboard.score(id)?;
+        if !self.options.exclude_flagged {
+            return Some(score);
+        }
+
+        let excluded_positions = self
+            .speed_check
+            .flagged_ids()
+            .filter_map(|id| self.leaderboard.score(id))
+            .map(|score| score.position)
+            .collect_vec();
+        let excluded_ahead = excluded_positions
+            .iter()
+            .filter(|position| **position < score.position)
+            .count();
+
+        Some(ScoreMessage {
+            position: if self.excluded(id) {
+                self.leaderboard.ranked_count() - excluded_positions.len() + excluded_ahead
+            } else {
+                score.position - excluded_ahead
+            },
+            ..score
+        })
     }
 
     pub fn leaderboard_id(&self, player_id: Id) -> Id {
@@ -259,12 +950,9 @@ impl Game {
 
         let player_names = self
             .watchers
-            .specific_vec(ValueKind::Player, tunnel_finder)
+            .present_players(tunnel_finder)
             .into_iter()
-            .filter_map(|(_, _, x)| match x {
-                Value::Player(player_value) => Some(player_value.name().to_owned()),
-                _ => None,
-            })
+            .filter_map(|id| self.watchers.get_name(id))
             .unique();
 
         TruncatedVec::new(
@@ -274,49 +962,393 @@ impl Game {
         )
     }
 
-    fn leaderboard_message(&self) -> LeaderboardMessage {
-        let [current, prior] = self.leaderboard.last_two_scores_descending();
+    /// the player's answers and points on every slide, only available once the game is over
+    pub fn recap(&self, watcher_id: Id) -> Option<Recap> {
+        if !matches!(self.state, State::Done)
+            || self.watchers.get_watcher_value(watcher_id)?.kind() != ValueKind::Player
+        {
+            return None;
+        }
+
+        let show_real_score = !self.options.hides_leaderboard();
+        let points = self
+            .leaderboard
+            .player_summary(self.leaderboard_id(watcher_id), show_real_score);
+
+        Some(Recap {
+            name: self.names.get_name(&watcher_id),
+            score: self.score(watcher_id).filter(|_| show_real_score),
+            slides: self
+                .answers
+                .iter()
+                .zip_longest(points)
+                .map(|slide| {
+                    let (answers, points) = slide.left_and_right();
+                    RecapSlide {
+                        answer: answers.and_then(|answers| answers.get(&watcher_id).cloned()),
+                        points: points.unwrap_or_default(),
+                    }
+                })
+                .collect(),
+            config: Arc::new(self.fuiz_config.without_host_notes()),
+        })
+    }
+
+    /// whether the player is left out of the standings and highlights
+    fn excluded(&self, player: Id) -> bool {
+        self.options.exclude_flagged && self.speed_check.is_flagged(player)
+    }
+
+    fn flagged_players(&self) -> Vec<FlaggedPlayer> {
+        self.speed_check
+            .flagged(|id| self.names.get_name(&id).unwrap_or("Unknown".to_owned()))
+    }
+
+    /// measured round trip time of the connection of a player, taken off their response times
+    /// before they are judged implausibly fast
+    pub fn record_latency(&mut self, player: Id, round_trip: Duration) {
+        self.speed_check.record_latency(player, round_trip);
+    }
+
+    fn highlights(&self) -> Box<Highlights> {
+        let response_times = self
+            .response_times
+            .iter()
+            .filter(|(id, _)| !self.excluded(**id))
+            .map(|(id, times)| (*id, times.clone()))
+            .collect();
 
-        let id_map = |i| self.names.get_name(&i).unwrap_or("Unknown".to_owned());
+        Box::new(Highlights::new(
+            &self.leaderboard,
+            &response_times,
+            self.timelines.iter().flatten().count(),
+            !self.options.hides_leaderboard(),
+            |id| self.names.get_name(&id).unwrap_or("Unknown".to_owned()),
+        ))
+    }
+
+    /// standings as shown to the watcher, names are replaced by aliases for anyone but the host
+    /// in anonymous games
+    fn leaderboard_message(&self, watcher_kind: ValueKind) -> LeaderboardMessage {
+        let [current, prior] = self
+            .leaderboard
+            .last_two_scores_descending(|id| self.excluded(id));
+
+        let alias_style = self
+            .options
+            .anonymous_leaderboard
+            .filter(|_| watcher_kind != ValueKind::Host);
+
+        let id_map = |i| {
+            match alias_style {
+                Some(style) => self.names.get_alias(&i, style),
+                None => self.names.get_name(&i),
+            }
+            .unwrap_or("Unknown".to_owned())
+        };
 
         let id_score_map = |(id, s)| (id_map(id), s);
 
+        const MEMBERS_LIMIT: usize = 10;
+
+        let members = self.team_manager.as_ref().map(|team_manager| {
+            current
+                .items
+                .iter()
+                .map(|(team_id, _)| {
+                    let members = team_manager.members_of(*team_id);
+                    TruncatedVec::new(
+                        members.iter().map(|id| id_map(*id)),
+                        MEMBERS_LIMIT,
+                        members.len(),
+                    )
+                })
+                .collect_vec()
+        });
+
+        let round = match &self.state {
+            State::Leaderboard(index) => Some(*index),
+            State::Slide(current_slide) => Some(current_slide.index),
+            _ => None,
+        }
+        .and_then(|index| self.fuiz_config.round(index))
+        .map(|round| RoundStandings {
+            round,
+            scores: self
+                .leaderboard
+                .round_scores_descending(|id| self.excluded(id))
+                .map(id_score_map),
+        });
+
         LeaderboardMessage {
             current: current.map(id_score_map),
             prior: prior.map(id_score_map),
+            members,
+            round,
         }
     }
 }
 
 impl Game {
-    pub fn new(fuiz: Fuiz, options: Options, host_id: Id) -> Self {
-        Self {
-            fuiz_config: fuiz,
-            watchers: Watchers::with_host_id(host_id),
+    pub fn new(mut fuiz: Fuiz, options: Options, host_id: Id) -> Self {
+        let leaderboard = options
+            .comeback_bonus_percent
+            .map_or_else(Leaderboard::default, Leaderboard::with_comeback);
+        let leaderboard = if options.survey_mode {
+            fuiz.clear_correct_answers();
+            leaderboard.unscored()
+        } else {
+            leaderboard
+        };
+        let leaderboard = match fuiz.round_scoring() {
+            RoundScoring::Reset => leaderboard.reset_each_round(),
+            RoundScoring::Carry => leaderboard,
+        };
+
+        let seed = options.seed.unwrap_or_else(random_seed);
+
+        let mut game = Self {
+            fuiz_config: Arc::new(fuiz),
+            watchers: Watchers::with_host_id(host_id, options.limits()),
             names: Names::default(),
-            leaderboard: Leaderboard::default(),
+            leaderboard,
             state: State::WaitingScreen,
-            options,
             team_manager: options.teams.map(
                 |TeamOptions {
                      size,
                      assign_random,
-                 }| TeamManager::new(size, assign_random),
+                     answer_split,
+                 }| TeamManager::new(size, assign_random, answer_split),
             ),
+            options,
             locked: false,
+            clock: clock::system(),
+            last_activity: SystemTime::now(),
+            host_last_seen: SystemTime::now(),
+            slide_generation: 0,
+            closed: None,
+            observers: Vec::new(),
+            error_replies: HashMap::new(),
+            source: None,
+            stakes: HashSet::new(),
+            timelines: Vec::new(),
+            response_times: HashMap::new(),
+            answers: Vec::new(),
+            profiles: HashMap::new(),
+            ratings_recorded: false,
+            admitted: HashSet::new(),
+            password_attempts: HashMap::new(),
+            game_password_attempts: None,
+            first_join: None,
+            auto_locked: false,
+            auto_lock_scheduled: false,
+            join_queue: VecDeque::new(),
+            last_host_action: None,
+            undo_stack: Vec::new(),
+            stats: GameStats::default(),
+            team_chat_sent: HashMap::new(),
+            show_answers_override: None,
+            presence: Presence::default(),
+            answer_lock: AnswerLock::default(),
+            audit: Vec::new(),
+            speed_check: SpeedCheck::default(),
+            fingerprints: Fingerprints::default(),
+            seed,
+            draws: 0,
+        };
+
+        if let (Some(bots), None) = (game.options.bots, &game.options.teams) {
+            for number in 1..=bots.count() {
+                let bot = Id::new();
+                let Ok(name) = game.names.set_name(bot, &format!("Bot {number}")) else {
+                    continue;
+                };
+                if game.watchers.add_bot(bot, name).is_err() {
+                    break;
+                }
+            }
+        }
+
+        game
+    }
+
+    /// pulls the slides from the source instead of the fuiz, only has an effect before the game
+    /// starts
+    ///
+    /// slides handed out are recorded in the fuiz so summaries and snapshots still see them, a
+    /// game restored from a snapshot plays those recorded slides only
+    pub fn set_question_source(&mut self, source: Box<dyn QuestionSource>) {
+        if !matches!(self.state, State::WaitingScreen) {
+            return;
+        }
+        Arc::make_mut(&mut self.fuiz_config).slides.clear();
+        self.source = Some(source);
+    }
+
+    /// slide to play at the index, asking the question source the first time it's reached
+    fn next_slide(&mut self, index: usize) -> Option<SlideConfig> {
+        if let Some(slide) = self.fuiz_config.slides.get(index) {
+            return Some(slide.clone());
+        }
+        let source = self.source.as_mut()?;
+        if index != self.fuiz_config.slides.len() {
+            return None;
+        }
+        let slide = source.next(index, &self.leaderboard)?;
+        Arc::make_mut(&mut self.fuiz_config)
+            .slides
+            .push(slide.clone());
+        Some(slide)
+    }
+
+    /// slide the game moves to after the current one including its answers, for the host only,
+    /// slides of a question source are only known once they are picked
+    pub fn next_slide_preview(&self) -> Option<(usize, Option<&SlideConfig>)> {
+        let index = match &self.state {
+            State::WaitingScreen | State::TeamDisplay => 0,
+            State::Slide(current_slide) => current_slide.index + 1,
+            State::Leaderboard(index) => index + 1,
+            State::Stake(index) => *index,
+            State::Done => return None,
+        };
+
+        Some((index, self.fuiz_config.slides.get(index)))
+    }
+
+    /// number of slides shown to watchers, 0 when the question source can't tell in advance
+    fn slide_count(&self) -> usize {
+        match &self.source {
+            Some(source) => source.count().unwrap_or_default(),
+            None => self.fuiz_config.len(),
+        }
+    }
+
+    /// reminds the host of an idle lobby and warns them before it expires, then schedules the
+    /// next check, the owner of the game calls this once after creating it and the checks stop
+    /// by themselves once the game leaves the lobby
+    pub fn schedule_lobby_reminders<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
+        &self,
+        mut schedule_message: S,
+        tunnel_finder: F,
+    ) {
+        if !matches!(self.state, State::WaitingScreen) {
+            return;
+        }
+
+        let (reminder, next) = reminders::check(self.idle_for());
+        if let Some(reminder) = reminder {
+            self.watchers.announce_specific(
+                ValueKind::Host,
+                &UpdateMessage::LobbyReminder(reminder).into(),
+                tunnel_finder,
+            );
+        }
+
+        schedule_message(reminders::AlarmMessage.into(), next);
+    }
+
+    /// fresh game with the same fuiz and options for back-to-back sessions, watchers, scores
+    /// and progress are left behind, as are observers and the question source
+    pub fn clone_config(&self, host_id: Id) -> Self {
+        Self::new(
+            Fuiz::clone(&self.fuiz_config),
+            self.options.clone(),
+            host_id,
+        )
+    }
+
+    /// replaces the clock used for slide timers
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.last_activity = clock.now();
+        self.host_last_seen = clock.now();
+        self.stats = GameStats::new(clock.now());
+        self.clock = clock;
+    }
+
+    /// next random generator derived from the seed of the game
+    fn rng(&mut self) -> fastrand::Rng {
+        self.draws += 1;
+        fastrand::Rng::with_seed(self.seed ^ self.draws.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+
+    /// seed every random choice of the game derives from, exported to reproduce it
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn next_slide_generation(&mut self) -> u64 {
+        self.show_answers_override = None;
+        self.answer_lock.clear();
+        self.slide_generation += 1;
+        self.slide_generation
+    }
+
+    fn touch(&mut self) {
+        self.last_activity = self.clock.now();
+    }
+
+    /// time elapsed since a watcher last joined, reconnected or sent a message, breaks don't
+    /// count as idle
+    pub fn idle_for(&self) -> web_time::Duration {
+        if matches!(&self.state, State::Slide(current_slide) if current_slide.state.on_break()) {
+            return web_time::Duration::ZERO;
+        }
+
+        self.clock
+            .now()
+            .duration_since(self.last_activity)
+            .unwrap_or_default()
+    }
+
+    /// finishes the game with a summary once the host has been gone for longer than the grace
+    /// period, returns whether the game got finished
+    pub fn check_host<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) -> bool {
+        if self.closed.is_some() {
+            return false;
+        }
+
+        let now = self.clock.now();
+
+        if self.watchers.any_alive(ValueKind::Host, &tunnel_finder) {
+            self.host_last_seen = now;
+            return false;
+        }
+
+        if now.duration_since(self.host_last_seen).unwrap_or_default() < HOST_GRACE_PERIOD {
+            return false;
         }
+
+        self.announce_summary(&tunnel_finder);
+        self.close(GameClosedReason::HostLeft, tunnel_finder);
+
+        true
+    }
+
+    /// rough proxy of the memory held by the game, grows with watchers and slides
+    pub fn weight(&self) -> usize {
+        self.watchers.count().max(1) * self.fuiz_config.len().max(1)
     }
 
     /// starts the game
     pub fn play<T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(AlarmMessage, web_time::Duration)>(
         &mut self,
-        schedule_message: S,
+        mut schedule_message: S,
         tunnel_finder: F,
     ) {
-        if let Some(slide) = self.fuiz_config.slides.first() {
+        if self.next_slide(0).is_some() {
+            let mut rng = self.rng();
             if let Some(team_manager) = &mut self.team_manager {
                 if matches!(self.state, State::WaitingScreen) {
-                    team_manager.finalize(&mut self.watchers, &mut self.names, &tunnel_finder);
+                    team_manager.finalize(
+                        &mut self.watchers,
+                        &mut self.names,
+                        &mut rng,
+                        &tunnel_finder,
+                    );
                     self.state = State::TeamDisplay;
                     self.watchers.announce_with(
                         |id, kind| {
@@ -336,72 +1368,304 @@ impl Game {
                         },
                         &tunnel_finder,
                     );
+                    self.announce_team_roster(&tunnel_finder);
                     return;
                 }
             }
 
-            let mut current_slide = CurrentSlide {
-                index: 0,
-                state: slide.to_state(),
-            };
-
-            current_slide.state.play(
-                self.team_manager.as_ref(),
-                &self.watchers,
-                schedule_message,
-                tunnel_finder,
-                0,
-                self.fuiz_config.len(),
-            );
-
-            self.set_state(State::Slide(Box::new(current_slide)));
+            self.start_slide(0, &mut schedule_message, &tunnel_finder);
         } else {
             self.announce_summary(tunnel_finder);
         }
     }
 
-    /// mark the current slide as done
-    pub fn finish_slide<
+    /// plays the slide at the index, offering stakes first when it's the final slide of a double
+    /// or nothing game, returns false when there is no slide left
+    fn start_slide<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
         S: FnMut(AlarmMessage, web_time::Duration),
     >(
         &mut self,
-        schedule_message: S,
-        tunnel_finder: F,
-    ) {
-        if let State::Slide(current_slide) = &self.state {
-            if self.options.no_leaderboard {
-                let next_index = current_slide.index + 1;
-                if let Some(next_slide) = self.fuiz_config.slides.get(next_index) {
-                    let mut state = next_slide.to_state();
+        index: usize,
+        schedule_message: &mut S,
+        tunnel_finder: &F,
+    ) -> bool {
+        let Some(slide) = self.next_slide(index) else {
+            return false;
+        };
 
-                    state.play(
-                        self.team_manager.as_ref(),
-                        &self.watchers,
-                        schedule_message,
-                        &tunnel_finder,
-                        next_index,
-                        self.fuiz_config.len(),
-                    );
+        if self.options.double_or_nothing
+            && !self.options.survey_mode
+            && !matches!(self.state, State::Stake(_))
+            && index + 1 == self.slide_count()
+        {
+            self.offer_stakes(index, tunnel_finder);
+            return true;
+        }
 
-                    self.state = State::Slide(Box::new(CurrentSlide {
-                        index: next_index,
-                        state,
-                    }));
-                } else {
-                    self.announce_summary(tunnel_finder);
-                }
-            } else {
-                self.set_state(State::Leaderboard(current_slide.index));
+        let Some(state) = self.slide_state(index, &slide) else {
+            return self.start_slide(index + 1, schedule_message, tunnel_finder);
+        };
 
-                let leaderboard_message = self.leaderboard_message();
+        if self
+            .fuiz_config
+            .round(index)
+            .is_some_and(|round| round.start == index)
+        {
+            self.leaderboard.start_round();
+        }
+        self.stats.record_slide(state.kind());
+        self.announce_section(index, tunnel_finder);
 
-                self.watchers.announce_with(
-                    |watcher_id, watcher_kind| {
+        self.run_slide(index, state, schedule_message, tunnel_finder);
+        self.emit(&GameEvent::SlideStarted { index });
+        self.check_presence(tunnel_finder);
+
+        true
+    }
+
+    /// fresh state of the slide under a new generation, none when the slide can't be started
+    fn slide_state(&mut self, index: usize, slide: &SlideConfig) -> Option<SlideState> {
+        let seed = self.rng().u64(..);
+        match slide.to_state(self.slide_generation + 1, seed) {
+            Ok(state) => {
+                self.next_slide_generation();
+                Some(state)
+            }
+            Err(e) => {
+                log::error!("skipping slide {index} that could not be started: {e}");
+                None
+            }
+        }
+    }
+
+    /// plays the slide from its start, whether for the first time or asked again
+    fn run_slide<T: Tunnel, F: Fn(Id) -> Option<T>, S: FnMut(AlarmMessage, web_time::Duration)>(
+        &mut self,
+        index: usize,
+        mut state: SlideState,
+        schedule_message: &mut S,
+        tunnel_finder: &F,
+    ) {
+        state.play(
+            self.team_manager.as_ref(),
+            &self.watchers,
+            &mut *schedule_message,
+            tunnel_finder,
+            self.clock.as_ref(),
+            index,
+            self.slide_count(),
+        );
+
+        self.set_state(State::Slide(Box::new(CurrentSlide { index, state })));
+        self.wake_bots(false, schedule_message);
+    }
+
+    /// tells everyone where the slide stands in its section, marking the start of new sections
+    fn announce_section<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, index: usize, tunnel_finder: &F) {
+        let Some(progress) = self.fuiz_config.section_progress(index) else {
+            return;
+        };
+
+        if progress.slide == 0 {
+            self.watchers.announce(
+                &UpdateMessage::SectionStarted {
+                    title: progress.title.clone(),
+                    section: progress.section,
+                    sections: progress.sections,
+                }
+                .into(),
+                tunnel_finder,
+            );
+        }
+        self.watchers.announce(
+            &UpdateMessage::SectionProgress(progress).into(),
+            tunnel_finder,
+        );
+    }
+
+    /// asks players who stopped interacting whether they're still there
+    fn check_presence<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: &F) {
+        let bots: HashSet<_> = self.watchers.bots().collect();
+        let players = self
+            .watchers
+            .present_players(tunnel_finder)
+            .into_iter()
+            .filter(|id| !bots.contains(id));
+
+        let due = self.presence.due(players, self.slide_generation);
+        if due.is_empty() {
+            return;
+        }
+
+        for id in due {
+            self.watchers
+                .send_message(&UpdateMessage::PresenceCheck.into(), id, tunnel_finder);
+        }
+        self.announce_activity(tunnel_finder);
+    }
+
+    /// tells the host how many connected players are taking part
+    fn announce_activity<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, tunnel_finder: &F) {
+        let activity = self
+            .presence
+            .activity(&self.watchers.present_players(tunnel_finder));
+        self.watchers.announce_specific(
+            ValueKind::Host,
+            &UpdateMessage::PlayerActivity(activity).into(),
+            tunnel_finder,
+        );
+    }
+
+    /// shares a message with the teammates of the player while the current slide accepts answers
+    fn send_team_chat<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watcher_id: Id,
+        text: &str,
+        tunnel_finder: F,
+    ) -> Result<(), Error> {
+        let Some(team_manager) = &self.team_manager else {
+            return Err(Error::NotAllowed);
+        };
+        if !matches!(&self.state, State::Slide(current_slide) if current_slide.state.accepting_answers())
+        {
+            return Err(Error::WrongPhase);
+        }
+        let Some(members) = team_manager.team_members(watcher_id) else {
+            return Err(Error::NotAllowed);
+        };
+
+        let text = rustrict::trim_whitespace(text);
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        if self
+            .team_chat_sent
+            .get(&watcher_id)
+            .and_then(|last| now.duration_since(*last).ok())
+            .is_some_and(|elapsed| elapsed < TEAM_CHAT_INTERVAL)
+        {
+            return Err(Error::TooFast);
+        }
+        self.team_chat_sent.insert(watcher_id, now);
+
+        self.watchers.announce_among(
+            &members,
+            &UpdateMessage::TeamChat {
+                from: self.names.get_name(&watcher_id).unwrap_or_default(),
+                text: text.censor(),
+            }
+            .into(),
+            tunnel_finder,
+        );
+
+        Ok(())
+    }
+
+    /// remembers the phase a host transition left, only transitions between slides are kept
+    /// since those leave no alarms or scores behind once undone
+    fn push_undo(&mut self, previous: State) {
+        if self.undo_stack.len() >= MAX_UNDO_DEPTH {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(previous);
+    }
+
+    /// asks every player whether they risk their score on the final slide
+    fn offer_stakes<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, index: usize, tunnel_finder: &F) {
+        self.stakes.clear();
+        self.set_state(State::Stake(index));
+
+        self.watchers.announce_with(
+            |watcher_id, watcher_kind| {
+                Some(match watcher_kind {
+                    ValueKind::Player => UpdateMessage::StakeOffer {
+                        points: self.score(watcher_id).map_or(0, |score| score.points),
+                    }
+                    .into(),
+                    _ => UpdateMessage::StakeCount(0).into(),
+                })
+            },
+            tunnel_finder,
+        );
+    }
+
+    /// doubles the score of everyone who staked it and got the final slide right, halves it for
+    /// those who didn't
+    fn settle_stakes(&mut self) {
+        let adjustments = std::mem::take(&mut self.stakes)
+            .into_iter()
+            .map(|id| {
+                let earned = self.leaderboard.last_earned(id);
+                let staked = self
+                    .leaderboard
+                    .score(id)
+                    .map_or(0, |score| score.points)
+                    .saturating_sub(earned);
+                let staked = i64::try_from(staked).unwrap_or(i64::MAX);
+                (id, if earned > 0 { staked } else { -(staked / 2) })
+            })
+            .collect_vec();
+
+        if !adjustments.is_empty() {
+            self.leaderboard.adjust(&adjustments);
+        }
+    }
+
+    /// mark the current slide as done
+    pub fn finish_slide<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
+        &mut self,
+        mut schedule_message: S,
+        tunnel_finder: F,
+    ) {
+        // scores can't be taken back, neither can the transitions that led to them
+        self.undo_stack.clear();
+        self.settle_stakes();
+
+        if let State::Slide(current_slide) = &self.state {
+            self.timelines.push(current_slide.state.answer_timeline());
+            self.answers.push(current_slide.state.submitted_answers());
+            let response_times = current_slide
+                .state
+                .response_times()
+                .into_iter()
+                .filter(|(id, _)| !self.watchers.bots().contains(id))
+                .collect_vec();
+            self.speed_check.check(current_slide.index, &response_times);
+            for (id, response_time) in current_slide.state.response_times() {
+                self.response_times
+                    .entry(id)
+                    .or_default()
+                    .push(response_time);
+            }
+
+            if self.options.hides_leaderboard() || !current_slide.state.shows_leaderboard() {
+                let next_index = current_slide.index + 1;
+                if !self.start_slide(next_index, &mut schedule_message, &tunnel_finder) {
+                    self.announce_summary(tunnel_finder);
+                }
+            } else {
+                self.set_state(State::Leaderboard(current_slide.index));
+
+                let host_leaderboard = self.leaderboard_message(ValueKind::Host);
+                let unassigned_leaderboard = self.leaderboard_message(ValueKind::Unassigned);
+
+                self.watchers.announce_with(
+                    |watcher_id, watcher_kind| {
                         Some(match watcher_kind {
-                            ValueKind::Host | ValueKind::Unassigned => UpdateMessage::Leaderboard {
-                                leaderboard: leaderboard_message.clone(),
+                            ValueKind::Host => UpdateMessage::Leaderboard {
+                                leaderboard: host_leaderboard.clone(),
+                            }
+                            .into(),
+                            ValueKind::Unassigned => UpdateMessage::Leaderboard {
+                                leaderboard: unassigned_leaderboard.clone(),
                             }
                             .into(),
                             ValueKind::Player => UpdateMessage::Score {
@@ -419,36 +1683,33 @@ impl Game {
     /// sends summary (last slide) to everyone
     fn announce_summary<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
         self.state = State::Done;
+        self.stats.record_end(self.clock.now());
+
+        let player_config = Arc::new(self.fuiz_config.without_host_notes());
 
         self.watchers.announce_with(
             |id, vk| match vk {
                 ValueKind::Host => Some(
                     UpdateMessage::Summary({
-                        let (player_count, stats) =
-                            self.leaderboard.host_summary(!self.options.no_leaderboard);
+                        let (player_count, stats) = self
+                            .leaderboard
+                            .host_summary(!self.options.hides_leaderboard());
 
                         SummaryMessage::Host {
                             stats,
                             player_count,
                             config: self.fuiz_config.clone(),
-                            options: self.options,
+                            options: Box::new(self.options.clone()),
+                            timelines: self.timelines.clone(),
+                            highlights: self.highlights(),
+                            flagged: self.flagged_players(),
                         }
                     })
                     .into(),
                 ),
                 ValueKind::Player => Some(
-                    UpdateMessage::Summary(SummaryMessage::Player {
-                        score: if self.options.no_leaderboard {
-                            None
-                        } else {
-                            self.score(id)
-                        },
-                        points: self
-                            .leaderboard
-                            .player_summary(self.leaderboard_id(id), !self.options.no_leaderboard),
-                        config: self.fuiz_config.clone(),
-                    })
-                    .into(),
+                    UpdateMessage::Summary(self.final_standing(id).summary(player_config.clone()))
+                        .into(),
                 ),
                 ValueKind::Unassigned => None,
             },
@@ -458,21 +1719,93 @@ impl Game {
 
     /// mark the game as done and disconnect players
     pub fn mark_as_done<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
+        self.close(GameClosedReason::Finished, tunnel_finder);
+    }
+
+    /// ends the game for the given reason, every watcher is told why before being disconnected
+    pub fn close<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        reason: GameClosedReason,
+        tunnel_finder: F,
+    ) {
         self.state = State::Done;
+        self.closed = Some(reason);
+        self.stats.record_end(self.clock.now());
 
-        let watchers = self
-            .watchers
-            .vec(&tunnel_finder)
-            .iter()
-            .map(|(x, _, _)| *x)
-            .collect_vec();
+        self.emit(&GameEvent::Finished { reason });
 
-        for watcher in watchers {
-            self.watchers
-                .remove_watcher_session(&watcher, &tunnel_finder);
+        self.watchers
+            .announce(&UpdateMessage::GameClosed { reason }.into(), &tunnel_finder);
+
+        self.watchers
+            .for_each_watcher(&tunnel_finder, |_, session, _| {
+                session.close(reason.close_reason());
+            });
+    }
+
+    /// ends a game left in an unknown state by a panic while it was being handled, watchers are
+    /// told it crashed before being disconnected so its id can be freed right away
+    pub fn quarantine<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
+        self.close(GameClosedReason::Crashed, tunnel_finder);
+    }
+
+    /// operational figures of the game so far
+    pub fn stats(&self) -> StatsReport {
+        self.stats.report(self.clock.now())
+    }
+
+    /// where the player finished, as shown in their summary
+    fn final_standing(&self, watcher_id: Id) -> FinalStanding {
+        FinalStanding {
+            score: if self.options.hides_leaderboard() {
+                None
+            } else {
+                self.score(watcher_id)
+            },
+            points: self.leaderboard.player_summary(
+                self.leaderboard_id(watcher_id),
+                !self.options.hides_leaderboard(),
+            ),
         }
     }
 
+    /// what to remember of the game once it is removed, with the final summary of every host
+    /// and the standing of every player of a finished game
+    pub fn tombstone<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, tunnel_finder: F) -> Tombstone {
+        let mut tombstone = Tombstone::new(self.clock.now(), self.closed);
+
+        // the state of a crashed game cannot be trusted to build summaries
+        if !matches!(self.state, State::Done) || self.closed == Some(GameClosedReason::Crashed) {
+            return tombstone;
+        }
+
+        for host in self.watchers.ids_of(ValueKind::Host) {
+            if let Some(summary) = self
+                .state_message(host, ValueKind::Host, &tunnel_finder)
+                .to_message()
+            {
+                tombstone.add_host(host, summary);
+            }
+        }
+
+        let bots = self.watchers.bots().collect::<HashSet<_>>();
+        tombstone.set_players(
+            Arc::new(self.fuiz_config.without_host_notes()),
+            self.watchers
+                .ids_of(ValueKind::Player)
+                .filter(|id| !bots.contains(id))
+                .map(|id| (id, self.final_standing(id)))
+                .collect(),
+        );
+
+        tombstone
+    }
+
+    /// reason the game was closed, if it was
+    pub fn closed_reason(&self) -> Option<GameClosedReason> {
+        self.closed
+    }
+
     /// send metainfo to player about the game
     fn update_player_with_options<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
@@ -482,7 +1815,9 @@ impl Game {
         self.watchers.send_state(
             &SyncMessage::Metainfo(MetainfoMessage::Player {
                 score: self.score(watcher).map_or(0, |x| x.points),
-                show_answers: self.options.show_answers,
+                show_answers: self.show_answers(),
+                fuiz: self.fuiz_config.info(),
+                theme: self.options.theme.clone(),
             })
             .into(),
             watcher,
@@ -490,23 +1825,151 @@ impl Game {
         );
     }
 
+    /// whether players see the answers on their devices, the host can change it for the current
+    /// slide once its results are out
+    fn show_answers(&self) -> bool {
+        self.show_answers_override
+            .unwrap_or(self.options.show_answers)
+    }
+
+    fn requires_password(&self, watcher: Id) -> bool {
+        self.options.password.is_some() && !self.admitted.contains(&watcher)
+    }
+
+    /// admits the watcher if the password matches, watchers who keep guessing wrong are turned
+    /// away until the cooldown is over, as is everyone once too many wrong guesses pile up
+    fn check_password(&mut self, watcher: Id, password: &str) -> Result<(), Error> {
+        let Some(expected) = &self.options.password else {
+            return Err(Error::WrongPhase);
+        };
+
+        let now = self.clock.now();
+        let recent = |attempts: Option<&(u64, SystemTime)>| match attempts {
+            Some((attempts, last))
+                if now.duration_since(*last).unwrap_or_default() < PASSWORD_COOLDOWN =>
+            {
+                *attempts
+            }
+            _ => 0,
+        };
+        let attempts = recent(self.password_attempts.get(&watcher));
+        let game_attempts = recent(self.game_password_attempts.as_ref());
+        if attempts >= MAX_PASSWORD_ATTEMPTS || game_attempts >= MAX_GAME_PASSWORD_ATTEMPTS {
+            return Err(Error::TooManyAttempts);
+        }
+
+        if password != expected {
+            self.password_attempts.retain(|_, (_, last)| {
+                now.duration_since(*last).unwrap_or_default() < PASSWORD_COOLDOWN
+            });
+            self.password_attempts.insert(watcher, (attempts + 1, now));
+            self.game_password_attempts = Some((game_attempts + 1, now));
+            return Err(Error::WrongPassword);
+        }
+
+        self.password_attempts.remove(&watcher);
+        self.admitted.insert(watcher);
+        Ok(())
+    }
+
+    /// waits for a spot in a full lobby, the queue is only kept during the lobby and while it
+    /// has room, the watcher is told the game is full otherwise
+    fn enqueue<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, watcher: Id, tunnel_finder: F) {
+        if !self.join_queue.contains(&watcher) {
+            if !matches!(self.state, State::WaitingScreen)
+                || self.join_queue.len() >= MAX_JOIN_QUEUE_LENGTH
+            {
+                self.reply_error(watcher, Error::Full, "Join", &tunnel_finder);
+                return;
+            }
+            self.join_queue.push_back(watcher);
+        }
+
+        if let Some(position) = self.join_queue.iter().position(|id| *id == watcher) {
+            self.watchers.send_message(
+                &UpdateMessage::QueuePosition(position).into(),
+                watcher,
+                tunnel_finder,
+            );
+        }
+    }
+
+    fn announce_queue_positions<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, tunnel_finder: F) {
+        for (position, watcher) in self.join_queue.iter().enumerate() {
+            self.watchers.send_message(
+                &UpdateMessage::QueuePosition(position).into(),
+                *watcher,
+                &tunnel_finder,
+            );
+        }
+    }
+
+    /// lets the first queued watchers still around into the spots freed in the lobby
+    fn admit_queued<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
+        while !self.watchers.is_full(ValueKind::Player) {
+            let Some(watcher) = self.join_queue.pop_front() else {
+                break;
+            };
+            let waiting = tunnel_finder(watcher).is_some()
+                && self
+                    .watchers
+                    .get_watcher_value(watcher)
+                    .is_some_and(|value| value.kind() == ValueKind::Unassigned);
+            if waiting {
+                self.handle_unassigned(watcher, &tunnel_finder);
+            }
+        }
+
+        self.announce_queue_positions(tunnel_finder);
+    }
+
+    /// tells whoever is still queued that the game started without them
+    fn close_queue<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
+        for watcher in std::mem::take(&mut self.join_queue) {
+            self.reply_error(watcher, Error::Full, "Join", &tunnel_finder);
+        }
+    }
+
     /// start interactions with unassigned player
     fn handle_unassigned<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
         watcher: Id,
         tunnel_finder: F,
     ) {
+        if self.watchers.is_full(ValueKind::Player) {
+            self.enqueue(watcher, &tunnel_finder);
+            return;
+        }
+
+        if self.requires_password(watcher) {
+            self.watchers.send_message(
+                &UpdateMessage::PasswordRequest.into(),
+                watcher,
+                tunnel_finder,
+            );
+            return;
+        }
+
+        let mut rng = self.rng();
         if let Some(team_manager) = &mut self.team_manager {
-            if let Some(name) = team_manager.add_player(watcher, &mut self.watchers) {
-                self.update_player_with_name(watcher, &name, &tunnel_finder);
+            if let Some(placement) =
+                team_manager.add_player(watcher, &mut self.watchers, &mut self.names, &mut rng)
+            {
+                if placement.new_team {
+                    self.watchers.announce_specific(
+                        ValueKind::Host,
+                        &UpdateMessage::TeamCreated(placement.team_name.clone()).into(),
+                        &tunnel_finder,
+                    );
+                }
+                self.update_player_with_name(watcher, &placement.team_name, &tunnel_finder);
+                self.announce_team_roster(&tunnel_finder);
             }
         }
 
         if self.options.random_names {
             loop {
-                let Some(name) = petname::petname(2, " ") else {
-                    continue;
-                };
+                let name = names::petname(&mut self.rng(), 2);
                 if self
                     .assign_player_name(watcher, &name.to_title_case(), &tunnel_finder)
                     .is_ok()
@@ -534,11 +1997,101 @@ impl Game {
             Value::Player(watcher::PlayerValue::Individual { name: name.clone() }),
         );
 
-        self.update_player_with_name(watcher, &name, tunnel_finder);
+        self.handicap_late_joiner(watcher);
+        // joining counts as taking part, late joiners aren't asked whether they're there
+        self.presence.record(watcher, self.slide_generation);
+
+        self.update_player_with_name(watcher, &name, &tunnel_finder);
+
+        self.emit(&GameEvent::PlayerJoined { id: watcher, name });
+
+        self.first_join.get_or_insert(self.clock.now());
+        self.stats
+            .record_players(self.watchers.specific_count(ValueKind::Player));
+        self.apply_auto_lock(tunnel_finder);
 
         Ok(())
     }
 
+    /// gives a player joining after slides were scored their handicap, if the game has one,
+    /// joining a team that already scored keeps the team's score as it is
+    fn handicap_late_joiner(&mut self, player: Id) {
+        let missed = self.leaderboard.scored_slides();
+        let player = self.leaderboard_id(player);
+        if missed == 0 || self.leaderboard.score(player).is_some() {
+            return;
+        }
+
+        match self.options.late_join_handicap {
+            Some(LateJoinHandicap::Scale) => {
+                let remaining = self.slide_count().saturating_sub(missed);
+                if let Some(percent) = (missed * 100).checked_div(remaining) {
+                    self.leaderboard.boost_late_joiner(player, percent as u64);
+                }
+            }
+            Some(LateJoinHandicap::SeedMedian) => {
+                let median = self.leaderboard.median_score();
+                self.leaderboard
+                    .adjust(&[(player, i64::try_from(median).unwrap_or(i64::MAX))]);
+            }
+            None => {}
+        }
+    }
+
+    /// locks the game when a condition of the automatic lock is met and tells the host
+    fn apply_auto_lock<T: Tunnel, F: Fn(Id) -> Option<T>>(&mut self, tunnel_finder: F) {
+        if self.auto_locked || matches!(self.state, State::Done) {
+            return;
+        }
+        let Some(auto_lock) = self.options.auto_lock else {
+            return;
+        };
+        if !auto_lock.reached(
+            self.watchers.specific_count(ValueKind::Player),
+            self.first_join,
+            self.clock.now(),
+        ) {
+            return;
+        }
+
+        self.locked = true;
+        self.auto_locked = true;
+        self.watchers.announce_specific(
+            ValueKind::Host,
+            &UpdateMessage::AutoLocked.into(),
+            tunnel_finder,
+        );
+    }
+
+    /// schedules the check of the delay of the automatic lock once a player joined
+    fn schedule_auto_lock<S: FnMut(AlarmMessage, web_time::Duration)>(
+        &mut self,
+        mut schedule_message: S,
+    ) {
+        if self.auto_lock_scheduled || self.auto_locked {
+            return;
+        }
+        let (Some(after), Some(first_join)) = (
+            self.options
+                .auto_lock
+                .and_then(|auto_lock| auto_lock.after()),
+            self.first_join,
+        ) else {
+            return;
+        };
+
+        let elapsed = self
+            .clock
+            .now()
+            .duration_since(first_join)
+            .unwrap_or_default();
+        schedule_message(
+            auto_lock::AlarmMessage.into(),
+            after.saturating_sub(elapsed),
+        );
+        self.auto_lock_scheduled = true;
+    }
+
     /// sends messages to the player about their new assigned name
     pub fn update_player_with_name<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &self,
@@ -555,6 +2108,8 @@ impl Game {
         self.update_player_with_options(watcher, &tunnel_finder);
 
         if !name.is_empty() {
+            self.warn_shared_device(watcher, &tunnel_finder);
+
             // Announce to others of user joining
             if matches!(self.state, State::WaitingScreen) {
                 if let Some(team_manager) = &self.team_manager {
@@ -572,40 +2127,395 @@ impl Game {
                     }
                 }
 
-                self.watchers.announce_specific(
-                    ValueKind::Host,
-                    &UpdateMessage::WaitingScreen(self.waiting_screen_names(&tunnel_finder)).into(),
-                    &tunnel_finder,
-                );
-            }
+                self.watchers.announce_specific(
+                    ValueKind::Host,
+                    &UpdateMessage::WaitingScreen(self.waiting_screen_names(&tunnel_finder)).into(),
+                    &tunnel_finder,
+                );
+            }
+        }
+
+        self.watchers.send_state(
+            &self.state_message(watcher, ValueKind::Player, &tunnel_finder),
+            watcher,
+            tunnel_finder,
+        );
+    }
+
+    /// registers a player ahead of their connection under a name they already hold, used to carry
+    /// players over from an earlier game
+    pub fn seed_player(&mut self, watcher: Id, name: &str) -> Result<(), Error> {
+        if !matches!(self.state, State::WaitingScreen) {
+            return Err(Error::WrongPhase);
+        }
+        if self.watchers.has_watcher(watcher) {
+            return Err(Error::NotAllowed);
+        }
+        if self.watchers.is_full(ValueKind::Player) {
+            return Err(Error::Full);
+        }
+
+        let name = self.names.set_name(watcher, name)?;
+
+        self.watchers
+            .add_watcher(
+                watcher,
+                Value::Player(watcher::PlayerValue::Individual { name: name.clone() }),
+            )
+            .map_err(|_| Error::Full)?;
+
+        self.emit(&GameEvent::PlayerJoined { id: watcher, name });
+
+        Ok(())
+    }
+
+    /// every player with their name and total points, connected or not
+    pub fn final_scores(&self) -> Vec<(Id, String, u64)> {
+        self.watchers
+            .players()
+            .map(|(id, player)| {
+                (
+                    id,
+                    player.name().to_owned(),
+                    self.score(id).map_or(0, |score| score.points),
+                )
+            })
+            .collect()
+    }
+
+    /// entry of the game in the public lobby directory, only while a public game can be joined
+    pub fn lobby_listing(&self) -> Option<LobbyListing> {
+        if !self.options.public
+            || self.locked
+            || self.closed.is_some()
+            || matches!(self.state, State::Done)
+            || self.watchers.is_full(ValueKind::Player)
+        {
+            return None;
+        }
+
+        Some(LobbyListing {
+            fuiz: self.fuiz_config.info(),
+            player_count: self.watchers.specific_count(ValueKind::Player),
+            started: !matches!(self.state, State::WaitingScreen),
+        })
+    }
+
+    /// final results of every player, best first, connected or not
+    pub fn results(&self) -> Vec<PlayerResult> {
+        let show_real_score = !self.options.hides_leaderboard();
+
+        self.watchers
+            .players()
+            .map(|(id, player)| {
+                let score = self.score(id);
+                let (answered, correct) = self
+                    .answers
+                    .iter()
+                    .filter_map(|answers| answers.get(&id))
+                    .fold((0, 0), |(answered, correct), answer| {
+                        (answered + 1, correct + usize::from(answer.correct))
+                    });
+
+                PlayerResult {
+                    name: player.name().to_owned(),
+                    team: self
+                        .team_manager
+                        .as_ref()
+                        .and_then(|team_manager| team_manager.get_team(id))
+                        .and_then(|team| self.names.get_name(&team)),
+                    points: score.map_or(0, |score| score.points),
+                    position: score
+                        .map(|score| score.position)
+                        .filter(|_| show_real_score && !self.excluded(id)),
+                    answered,
+                    correct,
+                    flagged: self.speed_check.flagged_slides(id),
+                }
+            })
+            .sorted_by_key(|result| (std::cmp::Reverse(result.points), result.position))
+            .collect()
+    }
+
+    /// request carrying the results to the webhook chosen by the host, once the game is over
+    pub fn results_payload(&self) -> Option<Payload> {
+        if !matches!(self.state, State::Done) {
+            return None;
+        }
+
+        Some(
+            self.options
+                .results_webhook
+                .as_ref()?
+                .payload(&self.results()),
+        )
+    }
+
+    /// notes the device the watcher connects from, fingerprints are compared as given and only
+    /// kept when the game expects one player per device
+    pub fn record_fingerprint<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watcher: Id,
+        fingerprint: String,
+        tunnel_finder: F,
+    ) {
+        if !self.options.one_player_per_device {
+            return;
+        }
+        self.fingerprints.record(watcher, fingerprint);
+        if self.watchers.get_name(watcher).is_some() {
+            self.warn_shared_device(watcher, tunnel_finder);
+        }
+    }
+
+    /// tells the host the player shares their device with other players
+    fn warn_shared_device<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, player: Id, tunnel_finder: F) {
+        let sharing = self
+            .fingerprints
+            .sharing_with(player, self.watchers.ids_of(ValueKind::Player));
+        if sharing.is_empty() {
+            return;
+        }
+
+        let names = std::iter::once(player)
+            .chain(sharing)
+            .filter_map(|id| self.watchers.get_name(id))
+            .collect();
+
+        self.watchers.announce_specific(
+            ValueKind::Host,
+            &UpdateMessage::SharedDevice(names).into(),
+            tunnel_finder,
+        );
+    }
+
+    /// links the watcher to the profile of a returning player, the profile token is checked by
+    /// whoever resolved it
+    pub fn link_profile(&mut self, watcher: Id, profile: ProfileId) -> Result<(), Error> {
+        if !self.watchers.has_watcher(watcher) {
+            return Err(Error::NotAllowed);
+        }
+        self.profiles.insert(watcher, profile);
+        Ok(())
+    }
+
+    /// whether watchers may stay connected from several devices at once, their tunnels are then
+    /// meant to be grouped in [`crate::session::Devices`]
+    pub fn multi_device(&self) -> bool {
+        self.options.multi_device
+    }
+
+    /// whether the game updates the ratings of linked profiles
+    pub fn rated(&self) -> bool {
+        self.options.rated && !self.options.survey_mode
+    }
+
+    /// notes that the rating changes of the game were saved, see [`ratings::record`]
+    pub fn mark_ratings_recorded(&mut self) {
+        self.ratings_recorded = true;
+    }
+
+    /// total points of every player with a linked profile, or of their team, once a rated game
+    /// is over and before its ratings are recorded
+    pub fn rated_results(&self) -> Result<Vec<(ProfileId, u64)>, ratings::Error> {
+        if !self.rated() {
+            return Err(ratings::Error::Unrated);
+        }
+        if !matches!(self.state, State::Done) {
+            return Err(ratings::Error::Unfinished);
+        }
+        if self.ratings_recorded {
+            return Err(ratings::Error::AlreadyRecorded);
+        }
+
+        Ok(self
+            .watchers
+            .players()
+            .filter_map(|(id, _)| {
+                let profile = self.profiles.get(&id)?;
+                Some((*profile, self.score(id).map_or(0, |score| score.points)))
+            })
+            .unique_by(|(profile, _)| *profile)
+            .collect())
+    }
+
+    // Network
+
+    /// add a new watcher with given id and session
+    pub fn add_unassigned<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watcher: Id,
+        tunnel_finder: F,
+    ) -> Result<(), watcher::Error> {
+        if self.watchers.is_full(ValueKind::Unassigned) {
+            self.watchers.prune_unassigned(&tunnel_finder);
+        }
+
+        self.watchers.add_watcher(watcher, Value::Unassigned)?;
+
+        self.touch();
+
+        self.apply_auto_lock(&tunnel_finder);
+
+        if !self.locked {
+            self.handle_unassigned(watcher, tunnel_finder);
+        }
+
+        Ok(())
+    }
+
+    /// tells the watcher why their message was rejected, at most once per interval so a
+    /// misbehaving client cannot turn the game into an echo server
+    fn reply_error<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        watcher_id: Id,
+        error: Error,
+        label: &str,
+        tunnel_finder: F,
+    ) {
+        const MIN_INTERVAL: Duration = Duration::from_millis(500);
+
+        let now = self.clock.now();
+        if self
+            .error_replies
+            .get(&watcher_id)
+            .and_then(|last| now.duration_since(*last).ok())
+            .is_some_and(|elapsed| elapsed < MIN_INTERVAL)
+        {
+            return;
+        }
+        self.error_replies.insert(watcher_id, now);
+
+        self.watchers.send_control(
+            &UpdateMessage::Error {
+                error: error.code(),
+                context: Some(label.to_owned()),
+            }
+            .into(),
+            watcher_id,
+            tunnel_finder,
+        );
+    }
+
+    /// handle incoming message from watcher id, rejections are reported back to the watcher
+    pub fn receive_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        message: IncomingMessage,
+        schedule_message: S,
+        tunnel_finder: F,
+    ) {
+        let label = message.label();
+        self.stats.record_message();
+
+        match self.try_receive_message(watcher_id, message, schedule_message, &tunnel_finder) {
+            Ok(()) | Err(Error::UnknownWatcher) => {}
+            Err(Error::Name(e)) => {
+                self.watchers.send_message(
+                    &UpdateMessage::NameError(e.code()).into(),
+                    watcher_id,
+                    tunnel_finder,
+                );
+            }
+            Err(e) => self.reply_error(watcher_id, e, label, &tunnel_finder),
+        }
+    }
+
+    /// moves the game forward on behalf of the host
+    pub fn try_advance<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        schedule_message: S,
+        tunnel_finder: F,
+    ) -> Result<(), Error> {
+        self.try_receive_message(
+            watcher_id,
+            IncomingMessage::Host(IncomingHostMessage::Next),
+            schedule_message,
+            tunnel_finder,
+        )
+    }
+
+    /// records the answer of a player to the current slide
+    pub fn try_answer<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
+        &mut self,
+        watcher_id: Id,
+        answer: IncomingPlayerMessage,
+        schedule_message: S,
+        tunnel_finder: F,
+    ) -> Result<(), Error> {
+        if !matches!(self.state, State::Slide(_)) {
+            return Err(Error::WrongPhase);
         }
 
-        self.watchers.send_state(
-            &self.state_message(watcher, ValueKind::Player, &tunnel_finder),
-            watcher,
+        self.try_receive_message(
+            watcher_id,
+            IncomingMessage::Player(answer),
+            schedule_message,
             tunnel_finder,
-        );
+        )
     }
 
-    // Network
-
-    /// add a new watcher with given id and session
-    pub fn add_unassigned<T: Tunnel, F: Fn(Id) -> Option<T>>(
+    /// hands a message to the current slide and finishes the slide once it asks to, recording
+    /// the answer under the lock it was checked against
+    fn receive_slide_message<
+        T: Tunnel,
+        F: Fn(Id) -> Option<T>,
+        S: FnMut(AlarmMessage, web_time::Duration),
+    >(
         &mut self,
-        watcher: Id,
+        watcher_id: Id,
+        message: IncomingMessage,
+        lock: Option<u64>,
+        mut schedule_message: S,
         tunnel_finder: F,
-    ) -> Result<(), watcher::Error> {
-        self.watchers.add_watcher(watcher, Value::Unassigned)?;
-
-        if !self.locked {
-            self.handle_unassigned(watcher, tunnel_finder);
+    ) -> Result<(), Error> {
+        let count = self.slide_count();
+        let State::Slide(current_slide) = &mut self.state else {
+            return Err(Error::WrongPhase);
+        };
+        let was_revealed = current_slide.state.answers_revealed();
+        let was_accepting = current_slide.state.accepting_answers();
+        let result = current_slide.state.receive_message(
+            &mut self.leaderboard,
+            &self.watchers,
+            self.team_manager.as_ref(),
+            &mut schedule_message,
+            watcher_id,
+            &tunnel_finder,
+            self.clock.as_ref(),
+            message,
+            current_slide.index,
+            count,
+        );
+        if !was_revealed && current_slide.state.answers_revealed() {
+            let index = current_slide.index;
+            self.emit(&GameEvent::AnswersRevealed { index });
+        }
+        self.wake_bots(was_accepting, &mut schedule_message);
+        if let (Some(hash), Ok(_)) = (lock, &result) {
+            self.answer_lock.record(watcher_id, hash, self.clock.now());
+        }
+        if result? {
+            self.finish_slide(schedule_message, tunnel_finder);
         }
-
         Ok(())
     }
 
-    /// handle incoming message from watcher id
-    pub fn receive_message<
+    /// handle incoming message from watcher id, returning why it was rejected if it was
+    pub fn try_receive_message<
         T: Tunnel,
         F: Fn(Id) -> Option<T>,
         S: FnMut(AlarmMessage, web_time::Duration),
@@ -615,30 +2525,197 @@ impl Game {
         message: IncomingMessage,
         mut schedule_message: S,
         tunnel_finder: F,
-    ) {
+    ) -> Result<(), Error> {
         let Some(watcher_value) = self.watchers.get_watcher_value(watcher_id) else {
-            return;
+            return Err(Error::UnknownWatcher);
         };
 
+        // pings don't count as activity
+        if let IncomingMessage::TimeSync(nonce) = message {
+            self.watchers.send_message(
+                &UpdateMessage::TimeSync {
+                    nonce,
+                    server_time: self.clock.now(),
+                }
+                .into(),
+                watcher_id,
+                tunnel_finder,
+            );
+            return Ok(());
+        }
+
+        self.touch();
+
         if !message.follows(watcher_value.kind()) {
-            return;
+            return Err(match message {
+                IncomingMessage::Host(_) => Error::NotHost,
+                _ => Error::NotAllowed,
+            });
         }
 
+        if !message.within_limits() {
+            return Err(Error::TooLarge);
+        }
+
+        if watcher_value.kind() == ValueKind::Player
+            && self.presence.record(watcher_id, self.slide_generation)
+        {
+            self.announce_activity(&tunnel_finder);
+        }
+
+        // random names join players without a message able to schedule the alarm
+        self.apply_auto_lock(&tunnel_finder);
+        self.schedule_auto_lock(&mut schedule_message);
+
         match message {
-            IncomingMessage::Unassigned(_) if self.locked => {}
+            IncomingMessage::Unassigned(_) if self.locked => Err(Error::Locked),
             IncomingMessage::Host(IncomingHostMessage::Lock(lock_state)) => {
                 self.locked = lock_state;
+                Ok(())
+            }
+            IncomingMessage::Host(IncomingHostMessage::NextOnce(action)) => {
+                if self.last_host_action == Some(action) {
+                    self.watchers.send_state(
+                        &self.state_message(watcher_id, ValueKind::Host, &tunnel_finder),
+                        watcher_id,
+                        &tunnel_finder,
+                    );
+                    return Ok(());
+                }
+                // a refused action can be retried under the same id
+                self.try_receive_message(
+                    watcher_id,
+                    IncomingMessage::Host(IncomingHostMessage::Next),
+                    schedule_message,
+                    tunnel_finder,
+                )?;
+                self.last_host_action = Some(action);
+                Ok(())
+            }
+            IncomingMessage::Host(IncomingHostMessage::Undo) => {
+                let previous = self.undo_stack.pop().ok_or(Error::NothingToUndo)?;
+                self.set_state(previous);
+                for (id, _, value) in self.watchers.vec(&tunnel_finder) {
+                    if matches!(value.kind(), ValueKind::Host | ValueKind::Player) {
+                        self.watchers.send_state(
+                            &self.state_message(id, value.kind(), &tunnel_finder),
+                            id,
+                            &tunnel_finder,
+                        );
+                    }
+                }
+                Ok(())
+            }
+            IncomingMessage::Host(IncomingHostMessage::Reask) => {
+                let State::Slide(current_slide) = &self.state else {
+                    return Err(Error::WrongPhase);
+                };
+                let index = current_slide.index;
+                let Some(slide) = self.fuiz_config.slides.get(index).cloned() else {
+                    return Err(Error::WrongPhase);
+                };
+                let state = self
+                    .slide_state(index, &slide)
+                    .ok_or(Error::SlideUnavailable)?;
+                self.run_slide(index, state, &mut schedule_message, &tunnel_finder);
+                self.undo_stack.clear();
+                Ok(())
+            }
+            IncomingMessage::Host(IncomingHostMessage::ShowAnswers(show)) => {
+                if !matches!(&self.state, State::Slide(current_slide) if current_slide.state.answers_revealed())
+                {
+                    return Err(Error::WrongPhase);
+                }
+                self.show_answers_override = Some(show);
+                self.watchers.announce_specific(
+                    ValueKind::Player,
+                    &UpdateMessage::ShowAnswers(show).into(),
+                    tunnel_finder,
+                );
+                Ok(())
+            }
+            IncomingMessage::Host(IncomingHostMessage::PreviewNext) => {
+                let Some((index, slide)) = self.next_slide_preview() else {
+                    return Err(Error::WrongPhase);
+                };
+                self.watchers.send_control(
+                    &UpdateMessage::NextSlidePreview {
+                        index,
+                        slide: slide.cloned(),
+                    }
+                    .into(),
+                    watcher_id,
+                    tunnel_finder,
+                );
+                Ok(())
+            }
+            IncomingMessage::Unassigned(IncomingUnassignedMessage::Password(password)) => {
+                if !self.requires_password(watcher_id) {
+                    return Err(Error::WrongPhase);
+                }
+                self.check_password(watcher_id, &password)?;
+                self.handle_unassigned(watcher_id, &tunnel_finder);
+                Ok(())
+            }
+            IncomingMessage::Unassigned(IncomingUnassignedMessage::NameRequest(_))
+                if self.requires_password(watcher_id) =>
+            {
+                Err(Error::PasswordRequired)
             }
             IncomingMessage::Unassigned(IncomingUnassignedMessage::NameRequest(s))
                 if !self.options.random_names =>
             {
-                if let Err(e) = self.assign_player_name(watcher_id, &s, &tunnel_finder) {
-                    self.watchers.send_message(
-                        &UpdateMessage::NameError(e).into(),
-                        watcher_id,
-                        tunnel_finder,
-                    );
+                // queued watchers wait for their turn even if a spot just opened
+                if self.watchers.is_full(ValueKind::Player) || self.join_queue.contains(&watcher_id)
+                {
+                    return Err(Error::Full);
+                }
+                self.assign_player_name(watcher_id, &s, &tunnel_finder)?;
+                self.schedule_auto_lock(&mut schedule_message);
+                Ok(())
+            }
+            IncomingMessage::Player(IncomingPlayerMessage::Stake(stake)) => {
+                if !matches!(self.state, State::Stake(_)) {
+                    return Err(Error::WrongPhase);
+                }
+                let id = self.leaderboard_id(watcher_id);
+                if stake {
+                    self.stakes.insert(id);
+                } else {
+                    self.stakes.remove(&id);
+                }
+                self.watchers.announce_specific(
+                    ValueKind::Host,
+                    &UpdateMessage::StakeCount(self.stakes.len()).into(),
+                    &tunnel_finder,
+                );
+                Ok(())
+            }
+            IncomingMessage::Player(IncomingPlayerMessage::Present) => Ok(()),
+            IncomingMessage::Player(IncomingPlayerMessage::Leave) => {
+                if !matches!(self.state, State::WaitingScreen) {
+                    return Err(Error::WrongPhase);
+                }
+                self.watchers.remove_watcher(watcher_id);
+                self.names.remove_name(&watcher_id);
+                if let Some(team_manager) = &mut self.team_manager {
+                    team_manager.set_preferences(watcher_id, Vec::new());
                 }
+                self.watchers.remove_watcher_session(
+                    &watcher_id,
+                    CloseReason::Left,
+                    &tunnel_finder,
+                );
+                self.watchers.announce_specific(
+                    ValueKind::Host,
+                    &UpdateMessage::WaitingScreen(self.waiting_screen_names(&tunnel_finder)).into(),
+                    &tunnel_finder,
+                );
+                self.admit_queued(&tunnel_finder);
+                Ok(())
+            }
+            IncomingMessage::Player(IncomingPlayerMessage::TeamChat(text)) => {
+                self.send_team_chat(watcher_id, &text, &tunnel_finder)
             }
             IncomingMessage::Player(IncomingPlayerMessage::ChooseTeammates(preferences)) => {
                 if let Some(team_manager) = &mut self.team_manager {
@@ -650,56 +2727,102 @@ impl Game {
                             .collect_vec(),
                     );
                 }
+                Ok(())
             }
             message => match &mut self.state {
                 State::WaitingScreen | State::TeamDisplay => {
-                    if let IncomingMessage::Host(IncomingHostMessage::Next) = message {
-                        self.play(schedule_message, &tunnel_finder);
-                    }
+                    let IncomingMessage::Host(IncomingHostMessage::Next) = message else {
+                        return Err(Error::WrongPhase);
+                    };
+                    self.close_queue(&tunnel_finder);
+                    self.play(schedule_message, &tunnel_finder);
+                    Ok(())
                 }
                 State::Slide(current_slide) => {
-                    if current_slide.state.receive_message(
-                        &mut self.leaderboard,
-                        &self.watchers,
-                        self.team_manager.as_ref(),
-                        &mut schedule_message,
+                    let message = match message {
+                        IncomingMessage::Player(message) => {
+                            IncomingMessage::Player(message.for_slide(current_slide.index)?)
+                        }
+                        message => message,
+                    };
+                    let lock = match &message {
+                        IncomingMessage::Player(answer)
+                            if self.options.lock_answers
+                                && current_slide.state.takes_single_answer() =>
+                        {
+                            match self.answer_lock.check(watcher_id, answer) {
+                                Check::First(hash) => Some(hash),
+                                Check::Repeated => return Ok(()),
+                                Check::Changed(_)
+                                    if !self.answer_lock.report(watcher_id)
+                                        || self.audit.len() >= MAX_AUDIT_ENTRIES =>
+                                {
+                                    return Err(Error::AnswerLocked);
+                                }
+                                Check::Changed(locked_at) => {
+                                    let entry = AuditEntry {
+                                        player: watcher_id,
+                                        name: self.watchers.get_name(watcher_id),
+                                        slide: current_slide.index,
+                                        locked_at,
+                                        at: self.clock.now(),
+                                    };
+                                    log::warn!(
+                                        "player {watcher_id} tried to change their locked answer to slide {}",
+                                        current_slide.index
+                                    );
+                                    self.watchers.announce_specific(
+                                        ValueKind::Host,
+                                        &UpdateMessage::AnswerTampered(entry.clone()).into(),
+                                        &tunnel_finder,
+                                    );
+                                    self.audit.push(entry);
+                                    return Err(Error::AnswerLocked);
+                                }
+                            }
+                        }
+                        _ => None,
+                    };
+                    self.receive_slide_message(
                         watcher_id,
-                        &tunnel_finder,
                         message,
-                        current_slide.index,
-                        self.fuiz_config.len(),
-                    ) {
-                        self.finish_slide(schedule_message, tunnel_finder);
-                    }
+                        lock,
+                        schedule_message,
+                        tunnel_finder,
+                    )
                 }
                 State::Leaderboard(index) => {
-                    if let IncomingMessage::Host(IncomingHostMessage::Next) = message {
-                        let next_index = *index + 1;
-                        if let Some(slide) = self.fuiz_config.slides.get(next_index) {
-                            let mut state = slide.to_state();
-
-                            state.play(
-                                self.team_manager.as_ref(),
-                                &self.watchers,
-                                schedule_message,
-                                &tunnel_finder,
-                                next_index,
-                                self.fuiz_config.len(),
-                            );
-
-                            self.set_state(State::Slide(Box::new(CurrentSlide {
-                                index: next_index,
-                                state,
-                            })));
-                        } else {
-                            self.announce_summary(&tunnel_finder);
-                        }
+                    let IncomingMessage::Host(IncomingHostMessage::Next) = message else {
+                        return Err(Error::WrongPhase);
+                    };
+                    let next_index = *index + 1;
+                    let previous = self.state.clone();
+                    if self.start_slide(next_index, &mut schedule_message, &tunnel_finder) {
+                        self.push_undo(previous);
+                    } else {
+                        self.announce_summary(&tunnel_finder);
                     }
+                    Ok(())
                 }
-                State::Done => {
-                    if let IncomingMessage::Host(IncomingHostMessage::Next) = message {
-                        self.mark_as_done(tunnel_finder);
+                State::Stake(index) => {
+                    let IncomingMessage::Host(IncomingHostMessage::Next) = message else {
+                        return Err(Error::WrongPhase);
+                    };
+                    let index = *index;
+                    let previous = self.state.clone();
+                    if self.start_slide(index, &mut schedule_message, &tunnel_finder) {
+                        self.push_undo(previous);
+                    } else {
+                        self.announce_summary(&tunnel_finder);
                     }
+                    Ok(())
+                }
+                State::Done => {
+                    let IncomingMessage::Host(IncomingHostMessage::Next) = message else {
+                        return Err(Error::WrongPhase);
+                    };
+                    self.mark_as_done(tunnel_finder);
+                    Ok(())
                 }
             },
         }
@@ -718,34 +2841,147 @@ impl Game {
         match message {
             AlarmMessage::MultipleChoice(
                 multiple_choice::AlarmMessage::ProceedFromSlideIntoSlide {
-                    index: slide_index,
-                    to: _,
+                    index: slide_index, ..
                 },
             )
             | AlarmMessage::TypeAnswer(type_answer::AlarmMessage::ProceedFromSlideIntoSlide {
                 index: slide_index,
-                to: _,
+                ..
             })
             | AlarmMessage::Order(order::AlarmMessage::ProceedFromSlideIntoSlide {
                 index: slide_index,
-                to: _,
-            }) => match &mut self.state {
-                State::Slide(current_slide) if current_slide.index == slide_index => {
-                    if current_slide.state.receive_alarm(
+                ..
+            })
+            | AlarmMessage::MultipleChoice(multiple_choice::AlarmMessage::FlushAnswersCount {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::TypeAnswer(type_answer::AlarmMessage::FlushAnswersCount {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Order(order::AlarmMessage::FlushAnswersCount {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::MultipleChoice(multiple_choice::AlarmMessage::CorrectRemainingTime {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::TypeAnswer(type_answer::AlarmMessage::CorrectRemainingTime {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Order(order::AlarmMessage::CorrectRemainingTime {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Intermission(intermission::AlarmMessage::EndBreak {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Crossword(crossword::AlarmMessage::ProceedFromSlideIntoSlide {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::MemoryMatch(memory_match::AlarmMessage::ProceedFromSlideIntoSlide {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Typing(typing::AlarmMessage::ProceedFromSlideIntoSlide {
+                index: slide_index,
+                ..
+            })
+            | AlarmMessage::Custom(plugin::AlarmMessage {
+                index: slide_index, ..
+            }) => {
+                let count = self.slide_count();
+                if let State::Slide(current_slide) = &mut self.state {
+                    if current_slide.index != slide_index {
+                        return;
+                    }
+                    let was_revealed = current_slide.state.answers_revealed();
+                    let was_accepting = current_slide.state.accepting_answers();
+                    let was_on_break = current_slide.state.on_break();
+                    let finished = current_slide.state.receive_alarm(
                         &mut self.leaderboard,
                         &self.watchers,
                         self.team_manager.as_ref(),
                         &mut schedule_message,
                         &tunnel_finder,
+                        self.clock.as_ref(),
                         message,
                         current_slide.index,
-                        self.fuiz_config.len(),
-                    ) {
+                        count,
+                    );
+                    let break_ended = was_on_break && !current_slide.state.on_break();
+                    if !was_revealed && current_slide.state.answers_revealed() {
+                        self.emit(&GameEvent::AnswersRevealed { index: slide_index });
+                    }
+                    if break_ended {
+                        // idle time counts from the end of the break
+                        self.touch();
+                    }
+                    self.wake_bots(was_accepting, &mut schedule_message);
+                    if finished {
                         self.finish_slide(schedule_message, tunnel_finder);
                     }
                 }
-                _ => (),
-            },
+            }
+            AlarmMessage::AutoLock(auto_lock::AlarmMessage) => {
+                self.apply_auto_lock(tunnel_finder);
+            }
+            AlarmMessage::LobbyReminder(reminders::AlarmMessage) => {
+                self.schedule_lobby_reminders(schedule_message, tunnel_finder);
+            }
+            AlarmMessage::Bot(bots::AlarmMessage { bot, generation }) => {
+                if generation != self.slide_generation {
+                    return;
+                }
+                let mut rng = self.rng();
+                let (Some(bots), State::Slide(current_slide)) = (self.options.bots, &self.state)
+                else {
+                    return;
+                };
+                if let Some(answer) = current_slide
+                    .state
+                    .bot_answer(bots.roll(&mut rng), &mut rng)
+                {
+                    // bots aren't activity and skip the presence and lock checks meant for
+                    // people, answers landing after the slide moved on are simply dropped
+                    let _ = self.receive_slide_message(
+                        bot,
+                        IncomingMessage::Player(answer),
+                        None,
+                        schedule_message,
+                        tunnel_finder,
+                    );
+                }
+            }
+        }
+    }
+
+    /// lets every bot answer the current slide if it just started accepting answers
+    fn wake_bots<S: FnMut(AlarmMessage, web_time::Duration)>(
+        &mut self,
+        was_accepting: bool,
+        schedule_message: &mut S,
+    ) {
+        let (Some(bots), State::Slide(current_slide)) = (self.options.bots, &self.state) else {
+            return;
+        };
+        if was_accepting || !current_slide.state.accepting_answers() {
+            return;
+        }
+        let mut rng = self.rng();
+        for bot in self.watchers.bots() {
+            schedule_message(
+                AlarmMessage::Bot(bots::AlarmMessage {
+                    bot,
+                    generation: self.slide_generation,
+                }),
+                bots.latency(&mut rng),
+            );
         }
     }
 
@@ -801,56 +3037,154 @@ impl Game {
             State::Leaderboard(index) => match watcher_kind {
                 ValueKind::Host | ValueKind::Unassigned => SyncMessage::Leaderboard {
                     index: *index,
-                    count: self.fuiz_config.len(),
-                    leaderboard: self.leaderboard_message(),
+                    count: self.slide_count(),
+                    leaderboard: self.leaderboard_message(watcher_kind),
                 }
                 .into(),
                 ValueKind::Player => SyncMessage::Score {
                     index: *index,
-                    count: self.fuiz_config.len(),
+                    count: self.slide_count(),
                     score: self.score(watcher_id),
                 }
                 .into(),
             },
+            State::Stake(index) => match watcher_kind {
+                ValueKind::Player => SyncMessage::StakeOffer {
+                    index: *index,
+                    count: self.slide_count(),
+                    points: self.score(watcher_id).map_or(0, |score| score.points),
+                    staked: self.stakes.contains(&self.leaderboard_id(watcher_id)),
+                }
+                .into(),
+                _ => SyncMessage::StakeCount {
+                    index: *index,
+                    count: self.slide_count(),
+                    staked: self.stakes.len(),
+                }
+                .into(),
+            },
             State::Slide(current_slide) => current_slide.state.state_message(
                 watcher_id,
                 watcher_kind,
                 self.team_manager.as_ref(),
                 &self.watchers,
                 tunnel_finder,
+                self.clock.as_ref(),
                 current_slide.index,
-                self.fuiz_config.len(),
+                self.slide_count(),
             ),
             State::Done => match watcher_kind {
                 ValueKind::Host => SyncMessage::Summary({
-                    let (player_count, stats) =
-                        self.leaderboard.host_summary(!self.options.no_leaderboard);
+                    let (player_count, stats) = self
+                        .leaderboard
+                        .host_summary(!self.options.hides_leaderboard());
                     SummaryMessage::Host {
                         stats,
                         player_count,
                         config: self.fuiz_config.clone(),
-                        options: self.options,
+                        options: Box::new(self.options.clone()),
+                        timelines: self.timelines.clone(),
+                        highlights: self.highlights(),
+                        flagged: self.flagged_players(),
                     }
                 })
                 .into(),
-                ValueKind::Player => SyncMessage::Summary(SummaryMessage::Player {
-                    score: if self.options.no_leaderboard {
-                        None
-                    } else {
-                        self.score(watcher_id)
-                    },
-                    points: self.leaderboard.player_summary(
-                        self.leaderboard_id(watcher_id),
-                        !self.options.no_leaderboard,
-                    ),
-                    config: self.fuiz_config.clone(),
-                })
+                ValueKind::Player => SyncMessage::Summary(
+                    self.final_standing(watcher_id)
+                        .summary(Arc::new(self.fuiz_config.without_host_notes())),
+                )
                 .into(),
                 ValueKind::Unassigned => SyncMessage::NotAllowed.into(),
             },
         }
     }
 
+    /// sends the members of every team and whether they are connected to the host, should be
+    /// called whenever a team player connects or disconnects
+    pub fn announce_team_roster<T: Tunnel, F: Fn(Id) -> Option<T>>(&self, tunnel_finder: F) {
+        let Some(roster) = self.team_manager.as_ref().and_then(|team_manager| {
+            team_manager.roster(&self.names, |id| self.watchers.is_alive(id, &tunnel_finder))
+        }) else {
+            return;
+        };
+
+        self.watchers.announce_specific(
+            ValueKind::Host,
+            &UpdateMessage::TeamRoster(roster).into(),
+            &tunnel_finder,
+        );
+    }
+
+    /// synchronizes a reconnecting watcher, bundling the state of the current slide with the
+    /// updates keeping its live counters fresh
+    fn send_catch_up<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        tunnel_finder: F,
+    ) {
+        self.watchers.send_state(
+            &self.catch_up_message(watcher_id, watcher_kind, &tunnel_finder),
+            watcher_id,
+            tunnel_finder,
+        );
+    }
+
+    fn catch_up_message<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        watcher_kind: ValueKind,
+        tunnel_finder: F,
+    ) -> super::SyncMessage {
+        let state = self.state_message(watcher_id, watcher_kind, &tunnel_finder);
+        match &self.state {
+            State::Slide(current_slide) => super::SyncMessage::CatchUp {
+                state: Box::new(state),
+                updates: self
+                    .fuiz_config
+                    .section_progress(current_slide.index)
+                    .map(|progress| UpdateMessage::SectionProgress(progress).into())
+                    .into_iter()
+                    .chain(current_slide.state.catch_up_updates(
+                        watcher_id,
+                        watcher_kind,
+                        &self.watchers,
+                        &tunnel_finder,
+                    ))
+                    .collect(),
+            },
+            _ => state,
+        }
+    }
+
+    fn host_dashboard<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_id: Id,
+        tunnel_finder: F,
+    ) -> HostDashboard {
+        HostDashboard {
+            state: self.catch_up_message(watcher_id, ValueKind::Host, &tunnel_finder),
+            answered_count: match &self.state {
+                State::Slide(current_slide) => Some(
+                    current_slide
+                        .state
+                        .answered_count(&self.watchers, &tunnel_finder),
+                ),
+                _ => None,
+            },
+            leaderboard: (!self.options.hides_leaderboard())
+                .then(|| self.leaderboard_message(ValueKind::Host)),
+            player_count: self.watchers.specific_count(ValueKind::Player),
+            activity: self
+                .presence
+                .activity(&self.watchers.present_players(tunnel_finder)),
+            locked: self.locked,
+            fuiz: self.fuiz_config.info(),
+            theme: self.options.theme.clone(),
+            audit: self.audit.clone(),
+        }
+    }
+
     /// replaces the session associated with watcher id with a new one
     pub fn update_session<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
@@ -861,21 +3195,30 @@ impl Game {
             return;
         };
 
+        if let Some(reason) = self.closed {
+            self.watchers.send_message(
+                &UpdateMessage::GameClosed { reason }.into(),
+                watcher_id,
+                &tunnel_finder,
+            );
+            self.watchers
+                .remove_watcher_session(&watcher_id, reason.close_reason(), tunnel_finder);
+            return;
+        }
+
+        self.touch();
+
         match watcher_value.clone() {
             Value::Host => {
                 self.watchers.send_state(
-                    &self.state_message(watcher_id, watcher_value.kind(), &tunnel_finder),
-                    watcher_id,
-                    &tunnel_finder,
-                );
-                self.watchers.send_state(
-                    &SyncMessage::Metainfo(MetainfoMessage::Host {
-                        locked: self.locked,
-                    })
+                    &SyncMessage::HostDashboard(Box::new(
+                        self.host_dashboard(watcher_id, &tunnel_finder),
+                    ))
                     .into(),
                     watcher_id,
-                    tunnel_finder,
+                    &tunnel_finder,
                 );
+                self.announce_team_roster(tunnel_finder);
             }
             Value::Player(player_value) => {
                 if let PlayerValue::Team {
@@ -890,6 +3233,7 @@ impl Game {
                         watcher_id,
                         &tunnel_finder,
                     );
+                    self.announce_team_roster(&tunnel_finder);
                 }
                 self.watchers.send_message(
                     &UpdateMessage::NameAssign(player_value.name().to_owned()).into(),
@@ -897,11 +3241,7 @@ impl Game {
                     &tunnel_finder,
                 );
                 self.update_player_with_options(watcher_id, &tunnel_finder);
-                self.watchers.send_state(
-                    &self.state_message(watcher_id, watcher_value.kind(), &tunnel_finder),
-                    watcher_id,
-                    &tunnel_finder,
-                );
+                self.send_catch_up(watcher_id, watcher_value.kind(), &tunnel_finder);
             }
             Value::Unassigned if self.locked => {}
             Value::Unassigned => {
@@ -2,6 +2,7 @@ use std::{fmt::Display, num::ParseIntError, str::FromStr};
 
 use enum_map::{Enum, EnumArray};
 use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
 
 const MIN_VALUE: u16 = 0o10_000;
 const MAX_VALUE: u16 = 0o100_000;
@@ -10,6 +11,9 @@ const MAX_VALUE: u16 = 0o100_000;
 pub struct GameId(u16);
 
 impl GameId {
+    /// number of distinct game ids
+    pub const COUNT: usize = (MAX_VALUE - MIN_VALUE) as usize;
+
     pub fn new() -> Self {
         Self(fastrand::u16(MIN_VALUE..MAX_VALUE))
     }
@@ -46,11 +50,32 @@ impl<'de> Deserialize<'de> for GameId {
     }
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("game id must be exactly five octal digits")]
+    Format,
+    #[error(transparent)]
+    Parse(#[from] ParseIntError),
+    #[error("game id is out of range")]
+    OutOfRange,
+}
+
 impl FromStr for GameId {
-    type Err = ParseIntError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(u16::from_str_radix(s, 8)?))
+        // from_str_radix tolerates a leading sign, ids are only ever plain digits
+        if s.len() != 5 || !s.bytes().all(|b| matches!(b, b'0'..=b'7')) {
+            return Err(Error::Format);
+        }
+
+        let value = u16::from_str_radix(s, 8)?;
+
+        if (MIN_VALUE..MAX_VALUE).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err(Error::OutOfRange)
+        }
     }
 }
 
@@ -0,0 +1,86 @@
+//! Notable facts about a finished game, shown to the host with the summary.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_with::skip_serializing_none;
+use web_time::Duration;
+
+use crate::{leaderboard::Leaderboard, watcher::Id};
+
+/// Slide where the smallest share of answers earned points
+#[derive(Debug, Serialize, Clone)]
+pub struct HardestSlide {
+    pub index: usize,
+    /// share of scores that earned points, between 0 and 1
+    pub accuracy: f64,
+}
+
+/// Player with the lowest average time to answer
+#[serde_with::serde_as]
+#[derive(Debug, Serialize, Clone)]
+pub struct FastestPlayer {
+    pub name: String,
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub average: Duration,
+}
+
+/// Player, or team, who climbed the most positions from their lowest standing
+#[derive(Debug, Serialize, Clone)]
+pub struct Comeback {
+    pub name: String,
+    /// lowest position after a slide (0-indexing)
+    pub from: usize,
+    /// final position (0-indexing)
+    pub to: usize,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Highlights {
+    pub hardest_slide: Option<HardestSlide>,
+    pub fastest_player: Option<FastestPlayer>,
+    pub biggest_comeback: Option<Comeback>,
+}
+
+impl Highlights {
+    /// `response_times` holds how long each player took on every slide they answered, only
+    /// players who answered at least half of the `answered_slides` compete for the fastest
+    pub fn new<N: Fn(Id) -> String>(
+        leaderboard: &Leaderboard,
+        response_times: &HashMap<Id, Vec<Duration>>,
+        answered_slides: usize,
+        show_standings: bool,
+        name: N,
+    ) -> Self {
+        let hardest_slide = leaderboard
+            .hardest_slide()
+            .map(|(index, accuracy)| HardestSlide { index, accuracy });
+
+        let fastest_player = response_times
+            .iter()
+            .filter(|(_, times)| !times.is_empty() && times.len() * 2 >= answered_slides)
+            .map(|(id, times)| (*id, times.iter().sum::<Duration>() / times.len() as u32))
+            .min_by_key(|(_, average)| *average)
+            .map(|(id, average)| FastestPlayer {
+                name: name(id),
+                average,
+            });
+
+        // standings stay hidden in games without a leaderboard
+        let biggest_comeback = leaderboard
+            .biggest_comeback()
+            .filter(|_| show_standings)
+            .map(|(id, from, to)| Comeback {
+                name: name(id),
+                from,
+                to,
+            });
+
+        Self {
+            hardest_slide,
+            fastest_player,
+            biggest_comeback,
+        }
+    }
+}
@@ -12,15 +12,59 @@ pub struct FinalSummary {
     mapping: HashMap<Id, Vec<u64>>,
 }
 
+/// change to a total made outside of slides, with the start of the round it was made in
+type Adjustment = (Id, i64, usize);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AdjustmentSerde {
+    InRound(Id, i64, usize),
+    /// saved before adjustments knew when they were made, taken as made in the first round
+    Unrounded(Id, i64),
+}
+
+impl From<AdjustmentSerde> for Adjustment {
+    fn from(serde: AdjustmentSerde) -> Self {
+        match serde {
+            AdjustmentSerde::InRound(id, adjustment, round) => (id, adjustment, round),
+            AdjustmentSerde::Unrounded(id, adjustment) => (id, adjustment, 0),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct LeaderboardSerde {
     points_earned: Vec<Vec<(Id, u64)>>,
+    #[serde(default)]
+    adjustments: Vec<AdjustmentSerde>,
+    #[serde(default)]
+    comeback_bonus_percent: u64,
+    #[serde(default)]
+    unscored: bool,
+    #[serde(default)]
+    late_join_boosts: HashMap<Id, u64>,
+    #[serde(default)]
+    round_start: usize,
+    #[serde(default)]
+    reset_rounds: bool,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(from = "LeaderboardSerde")]
 pub struct Leaderboard {
     points_earned: Vec<Vec<(Id, u64)>>,
+    /// changes to totals made outside of slides, applied after the latest slide
+    adjustments: Vec<Adjustment>,
+    /// extra share of points earned by the last place, shrinking to nothing for the leader
+    comeback_bonus_percent: u64,
+    /// records every score as zero, for games without correct answers
+    unscored: bool,
+    /// extra share of points earned by players who joined after the first slides
+    late_join_boosts: HashMap<Id, u64>,
+    /// slides scored before the current round started
+    round_start: usize,
+    /// totals only count the slides of the current round
+    reset_rounds: bool,
 
     #[serde(skip)]
     previous_scores_descending: Vec<(Id, u64)>,
@@ -34,8 +78,18 @@ pub struct Leaderboard {
 
 impl From<LeaderboardSerde> for Leaderboard {
     fn from(serde: LeaderboardSerde) -> Self {
-        let total_score_mapping = serde
-            .points_earned
+        let counted = if serde.reset_rounds {
+            &serde.points_earned[serde.round_start.min(serde.points_earned.len())..]
+        } else {
+            &serde.points_earned[..]
+        };
+        let adjustments = serde
+            .adjustments
+            .into_iter()
+            .map(Adjustment::from)
+            .collect_vec();
+
+        let total_score_mapping = counted
             .iter()
             .flat_map(|points_earned| points_earned.iter().copied())
             .sorted_by_key(|(id, _)| *id)
@@ -46,10 +100,19 @@ impl From<LeaderboardSerde> for Leaderboard {
                     Err(((id1, points1), (id2, points2)))
                 }
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<HashMap<_, u64>>();
 
-        let previous_total_score_mapping = serde
-            .points_earned
+        // a reset round only keeps the adjustments made during it
+        let total_score_mapping = adjustments
+            .iter()
+            .filter(|(_, _, round)| !serde.reset_rounds || *round == serde.round_start)
+            .fold(total_score_mapping, |mut mapping, (id, adjustment, _)| {
+                let points = mapping.entry(*id).or_default();
+                *points = points.saturating_add_signed(*adjustment);
+                mapping
+            });
+
+        let previous_total_score_mapping = counted
             .iter()
             .rev()
             .skip(1)
@@ -87,6 +150,12 @@ impl From<LeaderboardSerde> for Leaderboard {
 
         Leaderboard {
             points_earned: serde.points_earned,
+            adjustments,
+            comeback_bonus_percent: serde.comeback_bonus_percent,
+            unscored: serde.unscored,
+            late_join_boosts: serde.late_join_boosts,
+            round_start: serde.round_start,
+            reset_rounds: serde.reset_rounds,
             previous_scores_descending,
             scores_descending,
             score_and_position,
@@ -95,21 +164,123 @@ impl From<LeaderboardSerde> for Leaderboard {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Copy)]
+/// points increased by `percent`, saturating instead of overflowing
+fn with_percent(points: u64, percent: u64) -> u64 {
+    let extra = u128::from(points) * u128::from(percent) / 100;
+    points.saturating_add(u64::try_from(extra).unwrap_or(u64::MAX))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct ScoreMessage {
     pub points: u64,
     pub position: usize,
 }
 
+pub const MAX_COMEBACK_BONUS: u64 = crate::CONFIG.fuiz.comeback.max_bonus_percent.unsigned_abs();
+
 impl Leaderboard {
+    /// boosts points earned by trailing players up to `bonus_percent` for the last place
+    pub fn with_comeback(bonus_percent: u64) -> Self {
+        Self {
+            comeback_bonus_percent: bonus_percent,
+            ..Self::default()
+        }
+    }
+
+    /// keeps track of who took part in each slide without awarding any points
+    pub fn unscored(self) -> Self {
+        Self {
+            unscored: true,
+            ..self
+        }
+    }
+
+    /// totals start from zero at every round
+    pub fn reset_each_round(self) -> Self {
+        Self {
+            reset_rounds: true,
+            ..self
+        }
+    }
+
+    /// starts a new round with the next scored slide, clearing the totals if they are reset
+    pub fn start_round(&mut self) {
+        if self.round_start == self.points_earned.len() {
+            return;
+        }
+        self.round_start = self.points_earned.len();
+        if self.reset_rounds {
+            self.rebuild();
+        }
+    }
+
+    /// points after the comeback bonus of the current rank, tied players share a rank and
+    /// players without a score yet count as last
+    fn with_bonus(&self, id: Id, points: u64) -> u64 {
+        if self.unscored {
+            return 0;
+        }
+
+        let points = with_percent(
+            points,
+            self.late_join_boosts.get(&id).copied().unwrap_or_default(),
+        );
+
+        // players ahead of a score, the rank of every player holding it
+        let rank = |score: u64| {
+            self.scores_descending
+                .partition_point(|(_, points)| *points > score)
+        };
+
+        let Some(last) = self
+            .scores_descending
+            .last()
+            .map(|(_, score)| rank(*score))
+            .filter(|last| *last > 0)
+        else {
+            return points;
+        };
+
+        let position = self
+            .score_and_position
+            .get(&id)
+            .map_or(last, |(score, _)| rank(*score));
+
+        let bonus_percent = self.comeback_bonus_percent * position as u64 / last as u64;
+
+        with_percent(points, bonus_percent)
+    }
+
+    /// boosts the points the player earns from now on by `percent`
+    pub fn boost_late_joiner(&mut self, id: Id, percent: u64) {
+        self.late_join_boosts.insert(id, percent);
+    }
+
+    /// median of the current totals, 0 before anyone scored
+    pub fn median_score(&self) -> u64 {
+        let len = self.scores_descending.len();
+        match len {
+            0 => 0,
+            len if len % 2 == 0 => {
+                (self.scores_descending[len / 2 - 1].1 + self.scores_descending[len / 2].1) / 2
+            }
+            len => self.scores_descending[len / 2].1,
+        }
+    }
+
     pub fn add_scores(&mut self, scores: &[(Id, u64)]) {
+        let scores = scores
+            .iter()
+            .map(|(id, points)| (*id, self.with_bonus(*id, *points)))
+            .collect_vec();
+
         let mut summary: HashMap<Id, u64> = self
             .score_and_position
             .iter()
             .map(|(id, (points, _))| (*id, *points))
             .collect();
 
-        for (id, points) in scores {
+        for (id, points) in &scores {
             *summary.entry(*id).or_default() += points;
         }
 
@@ -126,7 +297,7 @@ impl Leaderboard {
             .map(|(position, (id, points))| (*id, (*points, position)))
             .collect();
 
-        self.points_earned.push(scores.to_vec());
+        self.points_earned.push(scores);
 
         self.previous_scores_descending =
             std::mem::replace(&mut self.scores_descending, scores_descending);
@@ -134,21 +305,193 @@ impl Leaderboard {
         self.score_and_position = mapping;
     }
 
-    pub fn last_two_scores_descending(&self) -> [TruncatedVec<(Id, u64)>; 2] {
+    /// changes totals directly, keeping the previous standings so the leaderboard shows the
+    /// adjustments as part of the latest slide
+    pub fn adjust(&mut self, adjustments: &[(Id, i64)]) {
+        let mut summary: HashMap<Id, u64> = self
+            .score_and_position
+            .iter()
+            .map(|(id, (points, _))| (*id, *points))
+            .collect();
+
+        for (id, adjustment) in adjustments {
+            let points = summary.entry(*id).or_default();
+            *points = points.saturating_add_signed(*adjustment);
+        }
+
+        self.scores_descending = summary
+            .iter()
+            .sorted_by_key(|(_, points)| *points)
+            .rev()
+            .map(|(a, b)| (*a, *b))
+            .collect_vec();
+
+        self.score_and_position = self
+            .scores_descending
+            .iter()
+            .enumerate()
+            .map(|(position, (id, points))| (*id, (*points, position)))
+            .collect();
+
+        let round = self.round_start;
+        self.adjustments.extend(
+            adjustments
+                .iter()
+                .map(|(id, adjustment)| (*id, *adjustment, round)),
+        );
+    }
+
+    /// number of slides scored so far
+    pub fn scored_slides(&self) -> usize {
+        self.points_earned.len()
+    }
+
+    /// rebuilds the standings from the points kept
+    fn rebuild(&mut self) {
+        *self = LeaderboardSerde {
+            points_earned: std::mem::take(&mut self.points_earned),
+            adjustments: std::mem::take(&mut self.adjustments)
+                .into_iter()
+                .map(|(id, adjustment, round)| AdjustmentSerde::InRound(id, adjustment, round))
+                .collect(),
+            comeback_bonus_percent: self.comeback_bonus_percent,
+            unscored: self.unscored,
+            late_join_boosts: std::mem::take(&mut self.late_join_boosts),
+            round_start: self.round_start,
+            reset_rounds: self.reset_rounds,
+        }
+        .into();
+    }
+
+    /// points earned on the latest scored slide
+    pub fn last_earned(&self, id: Id) -> u64 {
+        self.points_earned
+            .last()
+            .and_then(|points_earned| points_earned.iter().find(|(i, _)| *i == id))
+            .map_or(0, |(_, points)| *points)
+    }
+
+    /// share of scores that earned points over the last slides, `None` before any slide is scored
+    pub fn recent_accuracy(&self, slides: usize) -> Option<f64> {
+        let (earned, total) = self
+            .points_earned
+            .iter()
+            .rev()
+            .take(slides)
+            .flatten()
+            .fold((0, 0), |(earned, total), (_, points)| {
+                (earned + usize::from(*points > 0), total + 1)
+            });
+
+        (total > 0).then(|| earned as f64 / total as f64)
+    }
+
+    /// slide where the smallest share of scores earned points, with that share, slides nobody
+    /// was scored on are skipped
+    pub fn hardest_slide(&self) -> Option<(usize, f64)> {
+        self.points_earned
+            .iter()
+            .enumerate()
+            .filter(|(_, points_earned)| !points_earned.is_empty())
+            .map(|(index, points_earned)| {
+                let earned = points_earned.iter().filter(|(_, p)| *p > 0).count();
+                (index, earned as f64 / points_earned.len() as f64)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// player, or team, who climbed the most positions from their lowest standing after a
+    /// slide to their final one, as `(id, lowest position, final position)`, tied scores share
+    /// a position
+    pub fn biggest_comeback(&self) -> Option<(Id, usize, usize)> {
+        fn positions(totals: &HashMap<Id, u64>) -> HashMap<Id, usize> {
+            let mut positions = HashMap::with_capacity(totals.len());
+            let mut previous = None;
+            let mut position = 0;
+            let descending = totals
+                .iter()
+                .sorted_by_key(|(_, points)| std::cmp::Reverse(**points));
+            for (index, (id, points)) in descending.enumerate() {
+                if previous != Some(*points) {
+                    previous = Some(*points);
+                    position = index;
+                }
+                positions.insert(*id, position);
+            }
+            positions
+        }
+
+        let mut totals: HashMap<Id, u64> = HashMap::new();
+        let mut lowest: HashMap<Id, usize> = HashMap::new();
+
+        for points_earned in &self.points_earned {
+            for (id, points) in points_earned {
+                *totals.entry(*id).or_default() += points;
+            }
+            for (id, position) in positions(&totals) {
+                let lowest = lowest.entry(id).or_default();
+                *lowest = (*lowest).max(position);
+            }
+        }
+
+        let last = positions(&self.scores_descending.iter().copied().collect());
+
+        lowest
+            .into_iter()
+            .filter_map(|(id, lowest)| {
+                let position = *last.get(&id)?;
+                (position < lowest).then_some((id, lowest, position))
+            })
+            .max_by_key(|(_, lowest, position)| lowest - position)
+    }
+
+    /// totals of the current round, leaving out the ids `excluded` returns true for
+    pub fn round_scores_descending<E: Fn(Id) -> bool>(
+        &self,
+        excluded: E,
+    ) -> TruncatedVec<(Id, u64)> {
         const LIMIT: usize = 50;
 
-        [
-            TruncatedVec::new(
-                self.scores_descending.iter().copied(),
-                LIMIT,
-                self.scores_descending.len(),
-            ),
-            TruncatedVec::new(
-                self.previous_scores_descending.iter().copied(),
-                LIMIT,
-                self.previous_scores_descending.len(),
-            ),
-        ]
+        let totals = self
+            .points_earned
+            .iter()
+            .skip(self.round_start)
+            .flatten()
+            .filter(|(id, _)| !excluded(*id))
+            .fold(
+                HashMap::new(),
+                |mut totals: HashMap<Id, u64>, (id, points)| {
+                    *totals.entry(*id).or_default() += points;
+                    totals
+                },
+            );
+
+        TruncatedVec::new(
+            totals
+                .iter()
+                .map(|(id, points)| (*id, *points))
+                .sorted_by_key(|(_, points)| std::cmp::Reverse(*points)),
+            LIMIT,
+            totals.len(),
+        )
+    }
+
+    /// current and previous standings, leaving out the ids `excluded` returns true for
+    pub fn last_two_scores_descending<E: Fn(Id) -> bool>(
+        &self,
+        excluded: E,
+    ) -> [TruncatedVec<(Id, u64)>; 2] {
+        const LIMIT: usize = 50;
+
+        [&self.scores_descending, &self.previous_scores_descending].map(|scores| {
+            let kept = scores.iter().filter(|(id, _)| !excluded(*id));
+            TruncatedVec::new(kept.clone().copied(), LIMIT, kept.count())
+        })
+    }
+
+    /// number of players, or teams, with a score
+    pub fn ranked_count(&self) -> usize {
+        self.scores_descending.len()
     }
 
     fn compute_final_summary(&self, show_real_score: bool) -> FinalSummary {
@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
 use derive_where::derive_where;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 
 static_toml::static_toml! {
     #[static_toml(
@@ -9,13 +12,36 @@ static_toml::static_toml! {
     const CONFIG = include_toml!("config.toml");
 }
 
+pub mod admission;
+pub mod answer_lock;
+pub mod auto_lock;
+pub mod bots;
+pub mod clock;
+pub mod events;
+pub mod fingerprint;
 pub mod fuiz;
 pub mod game;
 pub mod game_id;
+pub mod highlights;
 pub mod leaderboard;
 pub mod names;
+pub mod presence;
+pub mod ratings;
+pub mod recap;
+pub mod reminders;
+pub mod results;
 pub mod session;
+pub mod snapshot;
+pub mod speed_check;
+pub mod stats;
 pub mod teams;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod theme;
+pub mod tombstone;
+pub mod tournament;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod watcher;
 
 #[derive(Debug, Serialize, Clone, derive_more::From)]
@@ -24,11 +50,26 @@ pub enum SyncMessage {
     MultipleChoice(fuiz::multiple_choice::SyncMessage),
     TypeAnswer(fuiz::type_answer::SyncMessage),
     Order(fuiz::order::SyncMessage),
+    Intermission(fuiz::intermission::SyncMessage),
+    Crossword(fuiz::crossword::SyncMessage),
+    MemoryMatch(fuiz::memory_match::SyncMessage),
+    Typing(fuiz::typing::SyncMessage),
+    Custom(fuiz::plugin::CustomMessage),
+    /// state of a watcher reconnecting mid-slide along with updates to apply on top of it
+    #[from(ignore)]
+    CatchUp {
+        state: Box<SyncMessage>,
+        updates: Vec<UpdateMessage>,
+    },
 }
 
 impl SyncMessage {
-    pub fn to_message(&self) -> String {
-        serde_json::to_string(self).expect("default serializer cannot fail")
+    /// serialized message, `None` after logging when serialization fails so a single bad
+    /// message cannot bring the game down
+    pub fn to_message(&self) -> Option<String> {
+        serde_json::to_string(self)
+            .inspect_err(|e| log::error!("failed to serialize sync message: {e}"))
+            .ok()
     }
 }
 
@@ -38,6 +79,11 @@ pub enum UpdateMessage {
     MultipleChoice(fuiz::multiple_choice::UpdateMessage),
     TypeAnswer(fuiz::type_answer::UpdateMessage),
     Order(fuiz::order::UpdateMessage),
+    Intermission(fuiz::intermission::UpdateMessage),
+    Crossword(fuiz::crossword::UpdateMessage),
+    MemoryMatch(fuiz::memory_match::UpdateMessage),
+    Typing(fuiz::typing::UpdateMessage),
+    Custom(fuiz::plugin::CustomMessage),
 }
 
 #[derive(Debug, Clone, derive_more::From, Serialize, Deserialize)]
@@ -45,11 +91,23 @@ pub enum AlarmMessage {
     MultipleChoice(fuiz::multiple_choice::AlarmMessage),
     TypeAnswer(fuiz::type_answer::AlarmMessage),
     Order(fuiz::order::AlarmMessage),
+    Intermission(fuiz::intermission::AlarmMessage),
+    Crossword(fuiz::crossword::AlarmMessage),
+    MemoryMatch(fuiz::memory_match::AlarmMessage),
+    Typing(fuiz::typing::AlarmMessage),
+    Custom(fuiz::plugin::AlarmMessage),
+    Bot(bots::AlarmMessage),
+    AutoLock(auto_lock::AlarmMessage),
+    LobbyReminder(reminders::AlarmMessage),
 }
 
 impl UpdateMessage {
-    pub fn to_message(&self) -> String {
-        serde_json::to_string(self).expect("default serializer cannot fail")
+    /// serialized message, `None` after logging when serialization fails so a single bad
+    /// message cannot bring the game down
+    pub fn to_message(&self) -> Option<String> {
+        serde_json::to_string(self)
+            .inspect_err(|e| log::error!("failed to serialize update message: {e}"))
+            .ok()
     }
 }
 
@@ -76,3 +134,23 @@ impl<T: Clone> TruncatedVec<T> {
         }
     }
 }
+
+/// Stable, machine readable form of an error sent to clients along with the values frontends
+/// need to phrase it in any language, the `Display` of the error is kept for logs
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub params: Option<BTreeMap<&'static str, u64>>,
+}
+
+impl ErrorCode {
+    fn new(code: &'static str) -> Self {
+        Self { code, params: None }
+    }
+
+    fn with(mut self, name: &'static str, value: u64) -> Self {
+        self.params.get_or_insert_default().insert(name, value);
+        self
+    }
+}
@@ -3,18 +3,35 @@ use std::collections::{hash_map::Entry, HashMap, HashSet};
 use rustrict::CensorStr;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
-use super::watcher::Id;
+use super::{watcher::Id, ErrorCode};
+
+/// random name of `words` words drawn from `rng`, adjectives followed by a noun
+pub fn petname(rng: &mut fastrand::Rng, words: u8) -> String {
+    let petnames = petname::Petnames::default();
+    let mut name = (1..words)
+        .filter_map(|_| rng.choice(petnames.adjectives.iter()))
+        .copied()
+        .collect::<Vec<_>>();
+    name.extend(rng.choice(petnames.nouns.iter()));
+    name.join(" ")
+}
 
 #[derive(Deserialize)]
 struct NamesSerde {
     mapping: HashMap<Id, String>,
+    #[serde(default)]
+    aliases: HashMap<Id, usize>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(from = "NamesSerde")]
 pub struct Names {
     mapping: HashMap<Id, String>,
+    /// number of each name in the order they were given out, for anonymous display
+    aliases: HashMap<Id, usize>,
 
     #[serde(skip_serializing)]
     reverse_mapping: HashMap<String, Id>,
@@ -24,7 +41,7 @@ pub struct Names {
 
 impl From<NamesSerde> for Names {
     fn from(serde: NamesSerde) -> Self {
-        let NamesSerde { mapping } = serde;
+        let NamesSerde { mapping, aliases } = serde;
         let mut reverse_mapping = HashMap::new();
         let mut existing = HashSet::new();
         for (id, name) in mapping.iter() {
@@ -33,12 +50,48 @@ impl From<NamesSerde> for Names {
         }
         Self {
             mapping,
+            aliases,
             reverse_mapping,
             existing,
         }
     }
 }
 
+const CONFIG: crate::config::fuiz::names::NamesConfig = crate::CONFIG.fuiz.names;
+
+/// maximum number of user-perceived characters in a name
+const MAX_LENGTH: usize = CONFIG.max_length.unsigned_abs() as usize;
+/// maximum rendered width of a name, measured in `m`s
+const MAX_WIDTH: usize = CONFIG.max_width.unsigned_abs() as usize;
+
+/// How names are replaced when they shouldn't be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AliasStyle {
+    /// "Player 7"
+    Numbered,
+    /// an emoji and animal, numbered once every animal is taken
+    Emoji,
+}
+
+const ANIMALS: [(&str, &str); 16] = [
+    ("🦊", "Fox"),
+    ("🐼", "Panda"),
+    ("🐸", "Frog"),
+    ("🦉", "Owl"),
+    ("🐙", "Octopus"),
+    ("🦁", "Lion"),
+    ("🐢", "Turtle"),
+    ("🦄", "Unicorn"),
+    ("🐧", "Penguin"),
+    ("🦋", "Butterfly"),
+    ("🐨", "Koala"),
+    ("🦀", "Crab"),
+    ("🐝", "Bee"),
+    ("🦒", "Giraffe"),
+    ("🐬", "Dolphin"),
+    ("🦔", "Hedgehog"),
+];
+
 #[derive(Error, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
     #[error("name already in-use")]
@@ -51,6 +104,21 @@ pub enum Error {
     Sinful,
     #[error("name is too long")]
     TooLong,
+    #[error("name is too wide")]
+    TooWide,
+}
+
+impl Error {
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Self::Used => ErrorCode::new("name.used"),
+            Self::Assigned => ErrorCode::new("name.assigned"),
+            Self::Empty => ErrorCode::new("name.empty"),
+            Self::Sinful => ErrorCode::new("name.inappropriate"),
+            Self::TooLong => ErrorCode::new("name.too_long").with("max_length", MAX_LENGTH as u64),
+            Self::TooWide => ErrorCode::new("name.too_wide").with("max_width", MAX_WIDTH as u64),
+        }
+    }
 }
 
 impl Names {
@@ -59,13 +127,18 @@ impl Names {
     }
 
     pub fn set_name(&mut self, id: Id, name: &str) -> Result<String, Error> {
-        if name.len() > 30 {
-            return Err(Error::TooLong);
-        }
-        let name = rustrict::trim_whitespace(name);
+        // folds fullwidth and other compatibility forms into their regular counterparts
+        let normalized = name.nfkc().collect::<String>();
+        let name = rustrict::trim_whitespace(&normalized);
         if name.is_empty() {
             return Err(Error::Empty);
         }
+        if name.graphemes(true).count() > MAX_LENGTH {
+            return Err(Error::TooLong);
+        }
+        if rustrict::width_str(name) > MAX_WIDTH {
+            return Err(Error::TooWide);
+        }
         if name.is_inappropriate() {
             return Err(Error::Sinful);
         }
@@ -76,12 +149,36 @@ impl Names {
             Entry::Occupied(_) => Err(Error::Assigned),
             Entry::Vacant(v) => {
                 v.insert(name.to_owned());
+                self.aliases.insert(id, self.aliases.len() + 1);
                 self.reverse_mapping.insert(name.to_owned(), id);
                 Ok(name.to_owned())
             }
         }
     }
 
+    /// stand-in for the name that doesn't reveal who it belongs to
+    pub fn get_alias(&self, id: &Id, style: AliasStyle) -> Option<String> {
+        let number = *self.aliases.get(id)?;
+        Some(match style {
+            AliasStyle::Numbered => format!("Player {number}"),
+            AliasStyle::Emoji => {
+                let (emoji, animal) = ANIMALS[(number - 1) % ANIMALS.len()];
+                match (number - 1) / ANIMALS.len() {
+                    0 => format!("{emoji} {animal}"),
+                    lap => format!("{emoji} {animal} {}", lap + 1),
+                }
+            }
+        })
+    }
+
+    /// frees the name of a watcher who left, their alias stays taken
+    pub fn remove_name(&mut self, id: &Id) {
+        if let Some(name) = self.mapping.remove(id) {
+            self.reverse_mapping.remove(&name);
+            self.existing.remove(&name);
+        }
+    }
+
     pub fn get_id(&self, name: &str) -> Option<Id> {
         self.reverse_mapping.get(name).copied()
     }
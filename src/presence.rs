@@ -0,0 +1,59 @@
+//! Application level check of players who stopped interacting, separating devices left
+//! connected from players still taking part.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::watcher::Id;
+
+/// slides a player can go through without interacting before being asked whether they're there
+const AWAY_AFTER_SLIDES: u64 = crate::CONFIG.fuiz.presence.away_after_slides.unsigned_abs();
+
+/// Live player count as seen by the host
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Activity {
+    /// connected players taking part
+    pub active: usize,
+    /// connected players who didn't answer whether they're still there
+    pub away: usize,
+}
+
+/// Last interaction of every player, counted in slides played
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Presence {
+    last_active: HashMap<Id, u64>,
+    /// players asked whether they're still there who haven't answered yet
+    prompted: HashSet<Id>,
+}
+
+impl Presence {
+    /// notes an interaction of the player during the slide, returns whether they were away
+    pub fn record(&mut self, player: Id, slide: u64) -> bool {
+        self.last_active.insert(player, slide);
+        self.prompted.remove(&player)
+    }
+
+    /// players idle for too many slides who weren't asked yet, they count as away from now on
+    pub fn due<I: IntoIterator<Item = Id>>(&mut self, players: I, slide: u64) -> Vec<Id> {
+        let due = players
+            .into_iter()
+            .filter(|player| !self.prompted.contains(player))
+            .filter(|player| {
+                let last_active = self.last_active.get(player).copied().unwrap_or_default();
+                slide.saturating_sub(last_active) > AWAY_AFTER_SLIDES
+            })
+            .collect::<Vec<_>>();
+        self.prompted.extend(due.iter().copied());
+        due
+    }
+
+    /// splits the connected players between active and away ones
+    pub fn activity(&self, present: &HashSet<Id>) -> Activity {
+        let away = present.intersection(&self.prompted).count();
+        Activity {
+            active: present.len() - away,
+            away,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Ratings of returning players carried across games.
+//!
+//! Players opt in by presenting a profile token to whoever hosts the games, who resolves it to a
+//! [`ProfileId`] and links it to their watcher with [`Game::link_profile`]. Once a rated game is
+//! over, [`record`] turns its final standings into Elo-like rating changes and saves them through
+//! a [`ProfileStore`], leaving how profiles are persisted up to the host.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::game::Game;
+
+#[derive(Error, Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("game is not rated")]
+    Unrated,
+    #[error("game is not over")]
+    Unfinished,
+    #[error("ratings of the game were already recorded")]
+    AlreadyRecorded,
+}
+
+/// Identifies a player across games, never shown to other players
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay,
+)]
+pub struct ProfileId(Uuid);
+
+impl ProfileId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ProfileId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ProfileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for ProfileId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::from_str(s)?))
+    }
+}
+
+const CONFIG: crate::config::fuiz::ratings::RatingsConfig = crate::CONFIG.fuiz.ratings;
+
+/// rating of a profile that never played a rated game
+pub const INITIAL_RATING: i64 = CONFIG.initial;
+const K_FACTOR: f64 = CONFIG.k_factor.unsigned_abs() as f64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub value: i64,
+    /// number of rated games the profile took part in
+    pub games: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            value: INITIAL_RATING,
+            games: 0,
+        }
+    }
+}
+
+/// Where ratings are kept between games
+pub trait ProfileStore {
+    /// rating of the profile, the initial one if it never played a rated game
+    fn rating(&self, profile: ProfileId) -> Rating;
+
+    fn save(&mut self, profile: ProfileId, rating: Rating);
+}
+
+impl ProfileStore for HashMap<ProfileId, Rating> {
+    fn rating(&self, profile: ProfileId) -> Rating {
+        self.get(&profile).copied().unwrap_or_default()
+    }
+
+    fn save(&mut self, profile: ProfileId, rating: Rating) {
+        self.insert(profile, rating);
+    }
+}
+
+/// Outcome of a rated game for one profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RatingChange {
+    pub profile: ProfileId,
+    pub before: Rating,
+    pub after: Rating,
+}
+
+/// new ratings of profiles given their rating before the game and their final points, every
+/// pair of profiles counts as a match won by whoever scored more, with the rating gap setting
+/// how much was expected of each
+pub fn rate(results: &[(ProfileId, Rating, u64)]) -> Vec<RatingChange> {
+    let opponents = results.len().saturating_sub(1).max(1) as f64;
+
+    results
+        .iter()
+        .map(|(profile, before, points)| {
+            let delta = results
+                .iter()
+                .filter(|(other, _, _)| other != profile)
+                .map(|(_, other_rating, other_points)| {
+                    let actual = match points.cmp(other_points) {
+                        std::cmp::Ordering::Greater => 1.,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.,
+                    };
+                    let expected =
+                        1. / (1. + 10_f64.powf((other_rating.value - before.value) as f64 / 400.));
+                    actual - expected
+                })
+                .sum::<f64>();
+
+            RatingChange {
+                profile: *profile,
+                before: *before,
+                after: Rating {
+                    value: before.value + (K_FACTOR * delta / opponents).round() as i64,
+                    games: before.games + 1,
+                },
+            }
+        })
+        .collect_vec()
+}
+
+/// rates the profiles linked to a finished rated game and saves their new ratings, a game is
+/// only ever recorded once
+pub fn record<S: ProfileStore>(store: &mut S, game: &mut Game) -> Result<Vec<RatingChange>, Error> {
+    let results = game
+        .rated_results()?
+        .into_iter()
+        .map(|(profile, points)| (profile, store.rating(profile), points))
+        .collect_vec();
+
+    let changes = rate(&results);
+
+    for change in &changes {
+        store.save(change.profile, change.after);
+    }
+    game.mark_ratings_recorded();
+
+    Ok(changes)
+}
@@ -0,0 +1,28 @@
+//! Review of a finished game for a single player, so they can go over their mistakes later.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::{
+    fuiz::config::{Fuiz, OwnAnswer, SubmittedAnswer},
+    leaderboard::ScoreMessage,
+};
+
+/// How the player did on a single slide
+#[derive(Debug, Serialize, Clone)]
+pub struct RecapSlide {
+    /// `None` if the player didn't answer
+    pub answer: Option<OwnAnswer<SubmittedAnswer>>,
+    pub points: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Recap {
+    pub name: Option<String>,
+    /// final points and position, `None` when the game hides the leaderboard
+    pub score: Option<ScoreMessage>,
+    /// each played slide, in the order they were played
+    pub slides: Vec<RecapSlide>,
+    pub config: Arc<Fuiz>,
+}
@@ -0,0 +1,56 @@
+//! Reminders sent to the host of a game left in the lobby, ending with a warning shortly before
+//! the lobby is considered stale and removed.
+
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+const CONFIG: crate::config::fuiz::lobby::LobbyConfig = crate::CONFIG.fuiz.lobby;
+
+/// time between two reminders of an idle lobby
+pub const REMINDER_INTERVAL: Duration =
+    Duration::from_secs(CONFIG.reminder_interval.unsigned_abs());
+/// idle time after which a lobby is stale and should be removed
+pub const LOBBY_EXPIRY: Duration = Duration::from_secs(CONFIG.expire_after.unsigned_abs());
+/// how long before the expiry the host is warned
+pub const EXPIRY_WARNING: Duration = Duration::from_secs(CONFIG.warning_before.unsigned_abs());
+
+/// Checks whether the host of an idle lobby should be reminded or warned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmMessage;
+
+/// What the host of a lobby idle for `idle` should be told, and when to check again
+pub fn check(idle: Duration) -> (Option<Reminder>, Duration) {
+    let remaining = LOBBY_EXPIRY.saturating_sub(idle);
+
+    // past the expiry the lobby is about to go, checking now and then covers a late removal
+    if remaining.is_zero() {
+        return (None, EXPIRY_WARNING);
+    }
+    if remaining <= EXPIRY_WARNING {
+        return (Some(Reminder::ExpiryWarning { remaining }), remaining);
+    }
+
+    let reminder = (idle >= REMINDER_INTERVAL).then_some(Reminder::Idle { idle });
+
+    // checks line up with whole intervals of idleness, activity in between pushes them back
+    let interval = REMINDER_INTERVAL.as_secs().max(1);
+    let next =
+        Duration::from_secs(interval - idle.as_secs() % interval).min(remaining - EXPIRY_WARNING);
+
+    (reminder, next)
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum Reminder {
+    /// the lobby has been idle for this long
+    Idle {
+        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+        idle: Duration,
+    },
+    /// the lobby will be removed after this long unless something happens
+    ExpiryWarning {
+        #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+        remaining: Duration,
+    },
+}
@@ -0,0 +1,174 @@
+//! Per-player results of a finished game, pushed to a webhook chosen by the host.
+//!
+//! The engine only prepares the [`Payload`], whoever runs the game sends it once the game is
+//! over, so teachers collecting grades get them into their spreadsheet without downloading
+//! anything.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use garde::Validate;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+const CONFIG: crate::config::fuiz::results::ResultsConfig = crate::CONFIG.fuiz.results;
+
+const MAX_URL_LENGTH: usize = CONFIG.max_url_length.unsigned_abs() as usize;
+
+fn is_internal_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        // shared address space of carrier-grade NAT
+        || (a == 100 && (64..128).contains(&b))
+}
+
+fn is_internal_ipv6(ip: Ipv6Addr) -> bool {
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        || ip.to_ipv4_mapped().is_some_and(is_internal_ipv4)
+}
+
+/// host of an http(s) url, `None` if the url has none or hides it behind credentials
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    if authority.contains('@') {
+        return None;
+    }
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => authority.split(':').next()?,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// the url must reach a public host, names resolving to internal addresses are still for
+/// whoever sends the request to refuse
+fn validate_url(url: &str, _: &()) -> garde::Result {
+    let host = url_host(url)
+        .ok_or_else(|| garde::Error::new("webhook must be an http(s) url"))?
+        .to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+
+    let internal = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => is_internal_ipv4(ip),
+        Ok(IpAddr::V6(ip)) => is_internal_ipv6(ip),
+        Err(_) => {
+            let top_level = host.rsplit('.').next().unwrap_or_default();
+            // top level domains never start with a digit, such hosts are addresses in disguise
+            top_level.starts_with(|c: char| c.is_ascii_digit())
+                || ["localhost", "local", "internal"].contains(&top_level)
+        }
+    };
+
+    if internal {
+        Err(garde::Error::new("webhook must reach a public host"))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct Webhook {
+    /// where the results are posted once the game is over
+    #[garde(length(max = MAX_URL_LENGTH), custom(validate_url))]
+    url: String,
+    #[garde(skip)]
+    #[serde(default)]
+    format: Format,
+}
+
+/// Final result of a single player
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerResult {
+    pub name: String,
+    pub team: Option<String>,
+    /// total points of the player, or of their team
+    pub points: u64,
+    /// final position (0-indexing)
+    pub position: Option<usize>,
+    /// number of slides the player answered
+    pub answered: usize,
+    /// number of answers that earned points
+    pub correct: usize,
+    /// number of slides answered implausibly fast
+    pub flagged: usize,
+}
+
+/// Request to send to the webhook
+#[derive(Debug, Clone)]
+pub struct Payload {
+    pub url: String,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+fn csv_field(field: &str) -> String {
+    // spreadsheets run cells starting like a formula, names are shown as typed instead
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{field}")
+    } else {
+        field.to_owned()
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+fn to_csv(results: &[PlayerResult]) -> String {
+    std::iter::once("name,team,points,position,answered,correct,flagged".to_owned())
+        .chain(results.iter().map(|result| {
+            [
+                csv_field(&result.name),
+                csv_field(result.team.as_deref().unwrap_or_default()),
+                result.points.to_string(),
+                // positions start from 1 for people reading the sheet
+                result
+                    .position
+                    .map(|p| (p + 1).to_string())
+                    .unwrap_or_default(),
+                result.answered.to_string(),
+                result.correct.to_string(),
+                result.flagged.to_string(),
+            ]
+            .join(",")
+        }))
+        .map(|line| line + "\r\n")
+        .join("")
+}
+
+impl Webhook {
+    pub fn payload(&self, results: &[PlayerResult]) -> Payload {
+        let (content_type, body) = match self.format {
+            Format::Csv => ("text/csv", to_csv(results)),
+            Format::Json => (
+                "application/json",
+                serde_json::to_string(results).expect("default serializer cannot fail"),
+            ),
+        };
+
+        Payload {
+            url: self.url.clone(),
+            content_type,
+            body,
+        }
+    }
+}
@@ -1,4 +1,82 @@
-use super::{SyncMessage, UpdateMessage};
+use std::{cell::RefCell, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{watcher::Id, SyncMessage, UpdateMessage};
+
+/// Why the engine is closing a tunnel, lets clients show the right message instead of a
+/// generic disconnect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CloseReason {
+    /// removed by the host
+    Kicked,
+    /// the game finished and no longer accepts connections
+    GameEnded,
+    /// the server is going down
+    ServerShutdown,
+    /// the connection stayed inactive for too long
+    Idle,
+    /// the client sent something the server could not handle
+    ProtocolError,
+    /// the connection could not keep up with outgoing messages
+    Backlogged,
+    /// a message could not be delivered, the connection is most likely dead
+    Unreachable,
+    /// the player left the game on their own
+    Left,
+    /// the game ran into an internal error and was removed
+    InternalError,
+    /// the watcher reconnected from another connection, such as another tab or device
+    Superseded,
+}
+
+impl CloseReason {
+    /// websocket close code matching the reason, custom reasons use the 4000-4999 range
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::GameEnded => 1000,
+            Self::ServerShutdown => 1001,
+            Self::ProtocolError => 1002,
+            Self::Backlogged => 1008,
+            Self::InternalError => 1011,
+            Self::Kicked => 4000,
+            Self::Idle => 4001,
+            Self::Unreachable => 4002,
+            Self::Left => 4003,
+            Self::Superseded => 4004,
+        }
+    }
+
+    /// final JSON frame sent before closing, `None` after logging when serialization fails so
+    /// the tunnel is closed without it
+    pub fn to_message(&self) -> Option<String> {
+        serde_json::to_string(&CloseFrame { closed: *self })
+            .inspect_err(|e| log::error!("failed to serialize close frame: {e}"))
+            .ok()
+    }
+}
+
+#[derive(Serialize)]
+struct CloseFrame {
+    #[serde(rename = "Closed")]
+    closed: CloseReason,
+}
+
+/// Returned by a tunnel that failed to hand a message to its connection
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("message could not be delivered")]
+pub struct Undelivered;
+
+/// What a connection may do on behalf of its watcher
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    /// sends commands and receives their acknowledgments
+    #[default]
+    Controller,
+    /// only follows the game, such as a projector showing the host screen
+    Viewer,
+}
 
 // pub enum Message {
 //     Outgoing(OutgoingMessage),
@@ -6,11 +84,205 @@ use super::{SyncMessage, UpdateMessage};
 // }
 
 pub trait Tunnel {
-    fn send_message(&self, message: &UpdateMessage);
+    fn send_message(&self, message: &UpdateMessage) {
+        if let Some(message) = message.to_message() {
+            self.send_raw(message.into());
+        }
+    }
 
-    fn send_state(&self, state: &SyncMessage);
+    fn send_state(&self, state: &SyncMessage) {
+        if let Some(state) = state.to_message() {
+            self.send_raw(state.into());
+        }
+    }
+
+    /// sends an already serialized message, allows broadcasts to serialize only once
+    fn send_raw(&self, message: Arc<str>);
+
+    /// sends an already serialized message and reports whether the connection accepted it,
+    /// tunnels able to detect failed writes should override it so dead tunnels are closed
+    /// right away instead of after a heartbeat timeout
+    fn try_send_raw(&self, message: Arc<str>) -> Result<(), Undelivered> {
+        self.send_raw(message);
+        Ok(())
+    }
+
+    /// sends an already serialized acknowledgment of a command, viewers never receive them
+    fn try_send_control_raw(&self, message: Arc<str>) -> Result<(), Undelivered> {
+        match self.role() {
+            Role::Controller => self.try_send_raw(message),
+            Role::Viewer => Ok(()),
+        }
+    }
+
+    /// what the connection may do on behalf of its watcher
+    fn role(&self) -> Role {
+        Role::Controller
+    }
+
+    /// whether the outbound queue has grown beyond what the connection is draining
+    fn is_backlogged(&self) -> bool {
+        false
+    }
 
     // fn send_multiple(&self, messages: &[Message]);
 
-    fn close(self);
+    fn close(self, reason: CloseReason);
+}
+
+/// sends through the tunnel unless it is backlogged, in which case the tunnel is dropped so
+/// a stalled connection cannot keep accumulating messages, tunnels failing to deliver are
+/// closed as well
+pub(crate) fn deliver<T: Tunnel>(session: T, message: Arc<str>) {
+    if session.is_backlogged() {
+        session.close(CloseReason::Backlogged);
+    } else if session.try_send_raw(message).is_err() {
+        session.close(CloseReason::Unreachable);
+    }
+}
+
+/// delivers an acknowledgment of a command to the connections controlling the watcher
+pub(crate) fn deliver_control<T: Tunnel>(session: T, message: Arc<str>) {
+    if session.is_backlogged() {
+        session.close(CloseReason::Backlogged);
+    } else if session.try_send_control_raw(message).is_err() {
+        session.close(CloseReason::Unreachable);
+    }
+}
+
+/// delivers a message serialized for this tunnel alone, a message that failed to serialize
+/// leaves the client out of sync so the tunnel is closed for it to reconnect
+pub(crate) fn deliver_serialized<T: Tunnel>(session: T, message: Option<String>) {
+    match message {
+        Some(message) => deliver(session, message.into()),
+        None => session.close(CloseReason::Unreachable),
+    }
+}
+
+/// Every connection of a watcher playing from several devices at once, messages reach all of
+/// them while answers, keyed by watcher, are recorded once whichever device sent them
+#[derive(Debug, Clone)]
+pub struct Devices<T>(pub Vec<T>);
+
+impl<T: Tunnel + Clone> Tunnel for Devices<T> {
+    fn send_raw(&self, message: Arc<str>) {
+        for device in &self.0 {
+            device.send_raw(message.clone());
+        }
+    }
+
+    /// devices are delivered to one by one so a stalled or dead one is closed alone
+    fn try_send_raw(&self, message: Arc<str>) -> Result<(), Undelivered> {
+        for device in &self.0 {
+            deliver(device.clone(), message.clone());
+        }
+        Ok(())
+    }
+
+    fn try_send_control_raw(&self, message: Arc<str>) -> Result<(), Undelivered> {
+        for device in &self.0 {
+            deliver_control(device.clone(), message.clone());
+        }
+        Ok(())
+    }
+
+    /// a viewer only when none of the devices controls the watcher
+    fn role(&self) -> Role {
+        if self
+            .0
+            .iter()
+            .any(|device| device.role() == Role::Controller)
+        {
+            Role::Controller
+        } else {
+            Role::Viewer
+        }
+    }
+
+    fn close(self, reason: CloseReason) {
+        for device in self.0 {
+            device.close(reason);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Outgoing {
+    Raw(Arc<str>),
+    Control(Arc<str>),
+    Close(CloseReason),
+}
+
+/// Collects everything the game sends while its state is being mutated, so the caller can
+/// release the game lock before the messages are fanned out to the real tunnels
+#[derive(Debug, Default)]
+pub struct Outbox {
+    outgoing: RefCell<Vec<(Id, Outgoing)>>,
+}
+
+/// Tunnel handed to the game in place of the real one, it records into an [`Outbox`]
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxTunnel<'a> {
+    outbox: &'a Outbox,
+    watcher_id: Id,
+}
+
+impl Tunnel for OutboxTunnel<'_> {
+    fn send_raw(&self, message: Arc<str>) {
+        self.outbox
+            .outgoing
+            .borrow_mut()
+            .push((self.watcher_id, Outgoing::Raw(message)));
+    }
+
+    /// recorded as is, the role is only known to the real tunnel
+    fn try_send_control_raw(&self, message: Arc<str>) -> Result<(), Undelivered> {
+        self.outbox
+            .outgoing
+            .borrow_mut()
+            .push((self.watcher_id, Outgoing::Control(message)));
+        Ok(())
+    }
+
+    fn close(self, reason: CloseReason) {
+        self.outbox
+            .outgoing
+            .borrow_mut()
+            .push((self.watcher_id, Outgoing::Close(reason)));
+    }
+}
+
+impl Outbox {
+    /// wraps a tunnel finder, liveness is still answered by the real tunnels but messages are
+    /// only recorded
+    pub fn tunnel_finder<'a, T: Tunnel, F: Fn(Id) -> Option<T> + 'a>(
+        &'a self,
+        tunnel_finder: F,
+    ) -> impl Fn(Id) -> Option<OutboxTunnel<'a>> + 'a {
+        move |watcher_id| {
+            tunnel_finder(watcher_id).map(|_| OutboxTunnel {
+                outbox: self,
+                watcher_id,
+            })
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.borrow().is_empty()
+    }
+
+    /// delivers the recorded messages in the order they were sent
+    pub fn flush<T: Tunnel, F: Fn(Id) -> Option<T>>(self, tunnel_finder: F) {
+        for (watcher_id, outgoing) in self.outgoing.into_inner() {
+            let Some(session) = tunnel_finder(watcher_id) else {
+                continue;
+            };
+
+            match outgoing {
+                Outgoing::Raw(message) => deliver(session, message),
+                Outgoing::Control(message) => deliver_control(session, message),
+                Outgoing::Close(reason) => session.close(reason),
+            }
+        }
+    }
 }
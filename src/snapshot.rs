@@ -0,0 +1,101 @@
+//! Versioned envelopes for everything persisted outside the process.
+//!
+//! Games and queued alarms are stored as `{ "version": n, "payload": ... }`. Loading runs the
+//! payload through the migrations between its version and the current one before handing it to
+//! serde, so snapshots taken by an older release keep loading after an upgrade.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{game::Game, AlarmMessage};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("snapshot is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("snapshot version {0} is newer than this release supports")]
+    UnsupportedVersion(u32),
+    #[error("migration from version {from} failed: {reason}")]
+    Migration { from: u32, reason: String },
+}
+
+/// Upgrades a payload from the version matching its index in [`Versioned::MIGRATIONS`] to the
+/// next one
+pub type Migration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// Type persisted through an envelope
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// current version, equal to the number of migrations
+    const VERSION: u32;
+    /// `MIGRATIONS[n]` turns a version `n` payload into a version `n + 1` one
+    const MIGRATIONS: &'static [Migration];
+}
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a, T> {
+    version: u32,
+    payload: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    version: u32,
+    payload: serde_json::Value,
+}
+
+/// snapshots predating envelopes are the bare payload
+fn unversioned(payload: serde_json::Value) -> Result<serde_json::Value, String> {
+    Ok(payload)
+}
+
+impl Versioned for Game {
+    const VERSION: u32 = 1;
+    const MIGRATIONS: &'static [Migration] = &[unversioned];
+}
+
+impl Versioned for AlarmMessage {
+    const VERSION: u32 = 1;
+    const MIGRATIONS: &'static [Migration] = &[unversioned];
+}
+
+/// serializes the value inside an envelope tagged with its current version
+pub fn save<T: Versioned>(value: &T) -> Result<String, Error> {
+    Ok(serde_json::to_string(&EnvelopeRef {
+        version: T::VERSION,
+        payload: value,
+    })?)
+}
+
+/// loads a value saved by any release, migrating it to the current version
+pub fn load<T: Versioned>(snapshot: &str) -> Result<T, Error> {
+    let value: serde_json::Value = serde_json::from_str(snapshot)?;
+
+    let is_envelope = value
+        .as_object()
+        .is_some_and(|o| o.len() == 2 && o.contains_key("version") && o.contains_key("payload"));
+
+    let Envelope {
+        version,
+        mut payload,
+    } = if is_envelope {
+        serde_json::from_value(value)?
+    } else {
+        Envelope {
+            version: 0,
+            payload: value,
+        }
+    };
+
+    if version > T::VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    for (from, migration) in T::MIGRATIONS.iter().enumerate().skip(version as usize) {
+        payload = migration(payload).map_err(|reason| Error::Migration {
+            from: from as u32,
+            reason,
+        })?;
+    }
+
+    Ok(serde_json::from_value(payload)?)
+}
@@ -0,0 +1,85 @@
+//! Answers given faster than a person could have read the question, most likely by a script.
+//! The connection latency of each player is taken off their response time before it is judged.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::watcher::Id;
+
+/// quickest plausible time between answers showing up on a device and a player picking one
+const MIN_RESPONSE: Duration = Duration::from_millis(
+    crate::CONFIG
+        .fuiz
+        .speed_check
+        .min_response_ms
+        .unsigned_abs(),
+);
+
+/// Player who answered implausibly fast, shown to the host in the summary
+#[derive(Debug, Clone, Serialize)]
+pub struct FlaggedPlayer {
+    pub name: String,
+    /// indices of the slides answered too fast
+    pub slides: Vec<usize>,
+}
+
+/// Round trip times of the players and the answers flagged so far
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SpeedCheck {
+    /// latest round trip time measured on the connection of each player
+    latencies: HashMap<Id, Duration>,
+    /// slides each player answered too fast
+    flagged: HashMap<Id, Vec<usize>>,
+}
+
+impl SpeedCheck {
+    pub fn record_latency(&mut self, player: Id, round_trip: Duration) {
+        self.latencies.insert(player, round_trip);
+    }
+
+    /// flags the response times of a finished slide that are too short once the round trip of
+    /// the answers and of the reply is taken off
+    pub fn check(&mut self, slide: usize, response_times: &[(Id, Duration)]) {
+        for (player, response_time) in response_times {
+            let latency = self.latencies.get(player).copied().unwrap_or_default();
+            if response_time.saturating_sub(latency) < MIN_RESPONSE {
+                self.flagged.entry(*player).or_default().push(slide);
+            }
+        }
+    }
+
+    pub fn is_flagged(&self, player: Id) -> bool {
+        self.flagged.contains_key(&player)
+    }
+
+    /// players flagged at least once
+    pub fn flagged_ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.flagged.keys().copied()
+    }
+
+    /// slides the player answered too fast
+    pub fn flagged_slides(&self, player: Id) -> usize {
+        self.flagged.get(&player).map_or(0, Vec::len)
+    }
+
+    /// every flagged player, most flagged slides first
+    pub fn flagged<N: Fn(Id) -> String>(&self, name: N) -> Vec<FlaggedPlayer> {
+        let mut flagged = self
+            .flagged
+            .iter()
+            .map(|(id, slides)| FlaggedPlayer {
+                name: name(*id),
+                slides: slides.clone(),
+            })
+            .collect::<Vec<_>>();
+        flagged.sort_by(|a, b| {
+            b.slides
+                .len()
+                .cmp(&a.slides.len())
+                .then(a.name.cmp(&b.name))
+        });
+        flagged
+    }
+}
@@ -0,0 +1,203 @@
+//! Operational figures of a single game, the server aggregates them into its instance-wide
+//! statistics.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameStats {
+    created: SystemTime,
+    /// when the game reached its summary or got closed
+    ended: Option<SystemTime>,
+    /// messages received from watchers, bots excluded
+    messages: u64,
+    /// most players in the game at once
+    peak_players: usize,
+    /// number of slides started of each kind
+    slide_kinds: BTreeMap<String, usize>,
+}
+
+impl Default for GameStats {
+    fn default() -> Self {
+        Self::new(SystemTime::now())
+    }
+}
+
+/// Figures of a game at a given time
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsReport {
+    /// time since the game was created, until it ended if it did
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub duration: Duration,
+    pub ended: bool,
+    pub messages: u64,
+    pub messages_per_second: f64,
+    pub peak_players: usize,
+    pub slide_kinds: BTreeMap<String, usize>,
+}
+
+impl GameStats {
+    pub fn new(created: SystemTime) -> Self {
+        Self {
+            created,
+            ended: None,
+            messages: 0,
+            peak_players: 0,
+            slide_kinds: BTreeMap::new(),
+        }
+    }
+
+    pub fn record_message(&mut self) {
+        self.messages += 1;
+    }
+
+    pub fn record_players(&mut self, players: usize) {
+        self.peak_players = self.peak_players.max(players);
+    }
+
+    pub fn record_slide(&mut self, kind: &str) {
+        *self.slide_kinds.entry(kind.to_owned()).or_default() += 1;
+    }
+
+    /// keeps the first end, a game closed after its summary ended with the summary
+    pub fn record_end(&mut self, at: SystemTime) {
+        self.ended.get_or_insert(at);
+    }
+
+    pub fn report(&self, now: SystemTime) -> StatsReport {
+        let duration = self
+            .ended
+            .unwrap_or(now)
+            .duration_since(self.created)
+            .unwrap_or_default();
+
+        StatsReport {
+            duration,
+            ended: self.ended.is_some(),
+            messages: self.messages,
+            messages_per_second: if duration.is_zero() {
+                0.
+            } else {
+                self.messages as f64 / duration.as_secs_f64()
+            },
+            peak_players: self.peak_players,
+            slide_kinds: self.slide_kinds.clone(),
+        }
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const DAYS_PER_WEEK: u64 = 7;
+
+/// Games that ended on one day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DayRollup {
+    /// peak players of each game
+    players: Vec<usize>,
+    messages: u64,
+    /// games removed after crashing, they are not counted among the games above
+    #[serde(default)]
+    quarantined: usize,
+}
+
+/// Summary of a period, `start` counting days since the unix epoch in UTC
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodSummary {
+    pub start: u64,
+    pub games: usize,
+    pub median_players: f64,
+    pub messages: u64,
+    pub quarantined: usize,
+}
+
+/// Finished games bucketed by the day they ended, kept by whoever owns the games so statistics
+/// outlive the games themselves
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Rollups {
+    days: BTreeMap<u64, DayRollup>,
+}
+
+fn median(values: &[usize]) -> f64 {
+    let sorted = values.iter().copied().sorted().collect_vec();
+    match sorted.len() {
+        0 => 0.,
+        len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.,
+        len => sorted[len / 2] as f64,
+    }
+}
+
+impl Rollups {
+    /// counts a game that ended at the given time
+    pub fn record(&mut self, at: SystemTime, report: &StatsReport) {
+        let rollup = self.day(at);
+        rollup.players.push(report.peak_players);
+        rollup.messages += report.messages;
+    }
+
+    /// counts a game quarantined at the given time after it crashed
+    pub fn record_quarantine(&mut self, at: SystemTime) {
+        self.day(at).quarantined += 1;
+    }
+
+    fn day(&mut self, at: SystemTime) -> &mut DayRollup {
+        let day = at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            / SECONDS_PER_DAY;
+
+        self.days.entry(day).or_default()
+    }
+
+    /// forgets the days before the given one
+    pub fn prune_before(&mut self, day: u64) {
+        self.days = self.days.split_off(&day);
+    }
+
+    fn summarize<'a, I: Iterator<Item = &'a DayRollup>>(start: u64, days: I) -> PeriodSummary {
+        let (players, messages, quarantined) = days.fold(
+            (Vec::new(), 0, 0),
+            |(mut players, messages, quarantined), day| {
+                players.extend_from_slice(&day.players);
+                (
+                    players,
+                    messages + day.messages,
+                    quarantined + day.quarantined,
+                )
+            },
+        );
+
+        PeriodSummary {
+            start,
+            games: players.len(),
+            median_players: median(&players),
+            messages,
+            quarantined,
+        }
+    }
+
+    /// one summary per day with games, oldest first
+    pub fn daily(&self) -> Vec<PeriodSummary> {
+        self.days
+            .iter()
+            .map(|(day, rollup)| Self::summarize(*day, std::iter::once(rollup)))
+            .collect()
+    }
+
+    /// one summary per week with games, weeks start on the day of the unix epoch, a thursday
+    pub fn weekly(&self) -> Vec<PeriodSummary> {
+        self.days
+            .iter()
+            .chunk_by(|(day, _)| *day / DAYS_PER_WEEK)
+            .into_iter()
+            .map(|(week, days)| {
+                Self::summarize(week * DAYS_PER_WEEK, days.map(|(_, rollup)| rollup))
+            })
+            .collect()
+    }
+}
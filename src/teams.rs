@@ -12,6 +12,51 @@ use super::{
     TruncatedVec,
 };
 
+/// how the answers of a multiple choice question are shown to the members of a team
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerSplit {
+    /// each member sees a different part of the answers
+    #[default]
+    Split,
+    /// every member sees all the answers
+    Shared,
+    /// only the first member of the team sees the answers and answers for the team
+    Captain,
+}
+
+/// team a late joiner was placed in
+pub struct Placement {
+    pub team_name: String,
+    /// whether every team was full so a new one was created for the player
+    pub new_team: bool,
+}
+
+/// player listed in the roster of their team
+#[derive(Debug, Serialize, Clone)]
+pub struct RosterMember {
+    name: String,
+    connected: bool,
+}
+
+/// team with all its members, shown to the host to verify the setup
+#[derive(Debug, Serialize, Clone)]
+pub struct RosterTeam {
+    name: String,
+    members: Vec<RosterMember>,
+}
+
+/// picks a unique name for a new team
+fn new_team_name(names: &mut names::Names, team_id: Id, rng: &mut fastrand::Rng) -> String {
+    loop {
+        if let Ok(unique_name) = names.set_name(
+            team_id,
+            &pluralizer::pluralize(&names::petname(rng, 1).to_title_case(), 2, false),
+        ) {
+            break unique_name;
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TeamManager {
     player_to_team: HashMap<Id, Id>,
@@ -24,10 +69,13 @@ pub struct TeamManager {
     next_team_to_receive_player: usize,
 
     team_to_players: HashMap<Id, Vec<Id>>,
+
+    #[serde(default)]
+    pub answer_split: AnswerSplit,
 }
 
 impl TeamManager {
-    pub fn new(optimal_size: usize, assign_random: bool) -> Self {
+    pub fn new(optimal_size: usize, assign_random: bool, answer_split: AnswerSplit) -> Self {
         Self {
             player_to_team: HashMap::default(),
             team_to_players: HashMap::default(),
@@ -40,6 +88,7 @@ impl TeamManager {
             },
             teams: OnceCell::default(),
             next_team_to_receive_player: 0,
+            answer_split,
         }
     }
 
@@ -51,6 +100,7 @@ impl TeamManager {
         &mut self,
         watchers: &mut Watchers,
         names: &mut names::Names,
+        rng: &mut fastrand::Rng,
         tunnel_finder: F,
     ) {
         let optimal_size = self.optimal_size;
@@ -99,7 +149,7 @@ impl TeamManager {
                 .map(|(_, g)| {
                     // to guard against attacks
                     let mut players = g.map(|(_, player_id)| player_id).collect_vec();
-                    fastrand::shuffle(&mut players);
+                    rng.shuffle(&mut players);
                     players
                 })
                 .sorted_by_key(std::vec::Vec::len)
@@ -139,21 +189,7 @@ impl TeamManager {
                 .map(|players| {
                     let team_id = Id::new();
 
-                    let team_name = loop {
-                        match names.set_name(
-                            team_id,
-                            &pluralizer::pluralize(
-                                &petname::petname(1, " ")
-                                    .expect("Petname failed")
-                                    .to_title_case(),
-                                2,
-                                false,
-                            ),
-                        ) {
-                            Ok(unique_name) => break unique_name,
-                            Err(_) => continue,
-                        };
-                    };
+                    let team_name = new_team_name(names, team_id, rng);
 
                     players.iter().copied().enumerate().for_each(
                         |(player_index_in_team, player_id)| {
@@ -190,6 +226,30 @@ impl TeamManager {
         })
     }
 
+    /// every team with its members and whether they are connected, `None` before teams are formed
+    pub fn roster<F: Fn(Id) -> bool>(
+        &self,
+        names: &names::Names,
+        connected: F,
+    ) -> Option<Vec<RosterTeam>> {
+        self.teams.get().map(|teams| {
+            teams
+                .iter()
+                .map(|(team_id, team_name)| RosterTeam {
+                    name: team_name.to_owned(),
+                    members: self
+                        .members_of(*team_id)
+                        .iter()
+                        .map(|player_id| RosterMember {
+                            name: names.get_name(player_id).unwrap_or_default(),
+                            connected: connected(*player_id),
+                        })
+                        .collect_vec(),
+                })
+                .collect_vec()
+        })
+    }
+
     pub fn get_team(&self, player_id: Id) -> Option<Id> {
         self.player_to_team.get(&player_id).copied()
     }
@@ -200,46 +260,87 @@ impl TeamManager {
         }
     }
 
-    pub fn add_player(&mut self, player_id: Id, watchers: &mut Watchers) -> Option<String> {
-        if let Some(teams) = self.teams.get() {
-            let next_index = self.next_team_to_receive_player;
-
-            self.next_team_to_receive_player += 1;
+    /// places a player joining after teams were formed, in the next team that is not full or
+    /// in a new team when all of them are
+    pub fn add_player(
+        &mut self,
+        player_id: Id,
+        watchers: &mut Watchers,
+        names: &mut names::Names,
+        rng: &mut fastrand::Rng,
+    ) -> Option<Placement> {
+        let optimal_size = self.optimal_size;
+        let team_to_players = &self.team_to_players;
+        let player_to_team = &self.player_to_team;
+        let next_index = self.next_team_to_receive_player;
 
-            let (team_id, team_name) = teams
-                .get(next_index % teams.len())
-                .expect("there is always at least one team");
+        let teams = self.teams.get_mut()?;
 
-            self.player_to_team.insert(player_id, *team_id);
-            let p = self
-                .team_to_players
-                .get_mut(team_id)
-                .expect("race condition :(");
+        let existing = player_to_team.get(&player_id).and_then(|team_id| {
+            teams
+                .iter()
+                .find(|(id, _)| id == team_id)
+                .map(|(id, name)| (*id, name.to_owned()))
+        });
 
-            let player_index = {
-                match p.iter().position(|p| *p == player_id) {
-                    Some(i) => i,
+        let (team_id, team_name, new_team) = match existing {
+            Some((team_id, team_name)) => (team_id, team_name, false),
+            None => {
+                let open_team = (0..teams.len())
+                    .map(|offset| (next_index + offset) % teams.len())
+                    .find(|index| {
+                        team_to_players
+                            .get(&teams[*index].0)
+                            .is_none_or(|players| players.len() < optimal_size)
+                    });
+
+                match open_team {
+                    Some(index) => {
+                        self.next_team_to_receive_player = index + 1;
+                        let (team_id, team_name) = &teams[index];
+                        (*team_id, team_name.to_owned(), false)
+                    }
                     None => {
-                        p.push(player_id);
-                        p.len() - 1
+                        let team_id = Id::new();
+                        let team_name = new_team_name(names, team_id, rng);
+                        teams.push((team_id, team_name.clone()));
+                        self.team_to_players.insert(team_id, Vec::new());
+                        (team_id, team_name, true)
                     }
                 }
-            };
-
-            watchers.update_watcher_value(
-                player_id,
-                watcher::Value::Player(watcher::PlayerValue::Team {
-                    team_name: team_name.to_owned(),
-                    individual_name: watchers.get_name(player_id).unwrap_or_default(),
-                    team_id: *team_id,
-                    player_index_in_team: player_index,
-                }),
-            );
-
-            Some(team_name.to_owned())
-        } else {
-            None
-        }
+            }
+        };
+
+        self.player_to_team.insert(player_id, team_id);
+        let p = self
+            .team_to_players
+            .get_mut(&team_id)
+            .expect("race condition :(");
+
+        let player_index = {
+            match p.iter().position(|p| *p == player_id) {
+                Some(i) => i,
+                None => {
+                    p.push(player_id);
+                    p.len() - 1
+                }
+            }
+        };
+
+        watchers.update_watcher_value(
+            player_id,
+            watcher::Value::Player(watcher::PlayerValue::Team {
+                team_name: team_name.clone(),
+                individual_name: watchers.get_name(player_id).unwrap_or_default(),
+                team_id,
+                player_index_in_team: player_index,
+            }),
+        );
+
+        Some(Placement {
+            team_name,
+            new_team,
+        })
     }
 
     pub fn _team_size(&self, player_id: Id) -> Option<usize> {
@@ -256,6 +357,13 @@ impl TeamManager {
         })
     }
 
+    /// players of the team with the given id, in the order they joined it
+    pub fn members_of(&self, team_id: Id) -> &[Id] {
+        self.team_to_players
+            .get(&team_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
     pub fn team_index<F: Fn(Id) -> bool>(&self, player_id: Id, f: F) -> Option<usize> {
         self.get_team(player_id)
             .and_then(|team_id| self.team_to_players.get(&team_id))
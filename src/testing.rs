@@ -0,0 +1,331 @@
+//! In-process harness driving games without websockets.
+//!
+//! A [`Simulation`] owns a game, a set of [`FakeTunnel`]s recording everything sent to them and
+//! a virtual scheduler firing alarms as the [`VirtualClock`] is advanced, scenarios read as a
+//! chain of steps:
+//!
+//! ```ignore
+//! let mut sim = Simulation::new(fuiz, options);
+//! sim.join(3)
+//!     .host_next()
+//!     .everyone_answers(IncomingPlayerMessage::IndexAnswer(0))
+//!     .advance(Duration::from_secs(30))
+//!     .host_next();
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+use web_time::Duration;
+
+use crate::{
+    clock::{Clock, VirtualClock},
+    fuiz::config::Fuiz,
+    game::{
+        Game, IncomingHostMessage, IncomingMessage, IncomingPlayerMessage,
+        IncomingUnassignedMessage, Options, State,
+    },
+    session::{CloseReason, Tunnel},
+    watcher::Id,
+    AlarmMessage,
+};
+
+/// Everything delivered to the fake tunnels
+#[derive(Debug, Default)]
+pub struct Network {
+    connected: HashSet<Id>,
+    received: HashMap<Id, Vec<serde_json::Value>>,
+    closed: HashMap<Id, CloseReason>,
+}
+
+/// Tunnel recording messages into a shared [`Network`]
+#[derive(Debug, Clone)]
+pub struct FakeTunnel {
+    network: Rc<RefCell<Network>>,
+    watcher_id: Id,
+}
+
+impl Tunnel for FakeTunnel {
+    fn send_raw(&self, message: Arc<str>) {
+        let message = serde_json::from_str(&message).expect("engine only sends valid JSON");
+        self.network
+            .borrow_mut()
+            .received
+            .entry(self.watcher_id)
+            .or_default()
+            .push(message);
+    }
+
+    fn close(self, reason: CloseReason) {
+        let mut network = self.network.borrow_mut();
+        network.connected.remove(&self.watcher_id);
+        network.closed.insert(self.watcher_id, reason);
+    }
+}
+
+/// Alarms waiting for the virtual clock to reach them
+#[derive(Debug, Default)]
+struct Scheduler {
+    elapsed: Duration,
+    sequence: u64,
+    pending: Vec<(Duration, u64, AlarmMessage)>,
+}
+
+impl Scheduler {
+    fn schedule(&mut self, alarm: AlarmMessage, delay: Duration) {
+        self.sequence += 1;
+        self.pending
+            .push((self.elapsed + delay, self.sequence, alarm));
+    }
+
+    /// removes the earliest alarm due at or before the given time
+    fn pop_due(&mut self, until: Duration) -> Option<(Duration, AlarmMessage)> {
+        let (index, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, (due, _, _))| *due <= until)
+            .min_by_key(|(_, (due, sequence, _))| (*due, *sequence))?;
+        let (due, _, alarm) = self.pending.swap_remove(index);
+        Some((due, alarm))
+    }
+}
+
+/// A game with its host and players wired to fake tunnels
+pub struct Simulation {
+    game: Game,
+    host: Id,
+    players: Vec<Id>,
+    network: Rc<RefCell<Network>>,
+    scheduler: Rc<RefCell<Scheduler>>,
+    clock: Arc<VirtualClock>,
+}
+
+fn tunnel_finder(network: &Rc<RefCell<Network>>) -> impl Fn(Id) -> Option<FakeTunnel> + '_ {
+    |watcher_id| {
+        network
+            .borrow()
+            .connected
+            .contains(&watcher_id)
+            .then(|| FakeTunnel {
+                network: network.clone(),
+                watcher_id,
+            })
+    }
+}
+
+fn schedule(scheduler: &Rc<RefCell<Scheduler>>) -> impl FnMut(AlarmMessage, Duration) + '_ {
+    |alarm, delay| scheduler.borrow_mut().schedule(alarm, delay)
+}
+
+impl Simulation {
+    /// creates a game with a connected host and no players
+    pub fn new(fuiz: Fuiz, options: Options) -> Self {
+        let host = Id::new();
+        let clock = Arc::new(VirtualClock::default());
+        let mut game = Game::new(fuiz, options, host);
+        game.set_clock(clock.clone());
+
+        let network = Rc::new(RefCell::new(Network::default()));
+        network.borrow_mut().connected.insert(host);
+
+        Self {
+            game,
+            host,
+            players: Vec::new(),
+            network,
+            scheduler: Rc::default(),
+            clock,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// the game itself, for setup the simulation doesn't cover
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    pub fn state(&self) -> &State {
+        &self.game.state
+    }
+
+    pub fn host(&self) -> Id {
+        self.host
+    }
+
+    /// id of the nth player to join
+    pub fn player(&self, index: usize) -> Id {
+        self.players[index]
+    }
+
+    pub fn players(&self) -> &[Id] {
+        &self.players
+    }
+
+    /// connects the given number of new players
+    pub fn join(&mut self, count: usize) -> &mut Self {
+        for _ in 0..count {
+            let id = Id::new();
+            self.network.borrow_mut().connected.insert(id);
+            self.game
+                .add_unassigned(id, tunnel_finder(&self.network))
+                .expect("simulated game has room for every player");
+            self.players.push(id);
+        }
+        self
+    }
+
+    /// delivers a message as if the watcher sent it
+    pub fn send(&mut self, watcher_id: Id, message: IncomingMessage) -> &mut Self {
+        self.game.receive_message(
+            watcher_id,
+            message,
+            schedule(&self.scheduler),
+            tunnel_finder(&self.network),
+        );
+        self
+    }
+
+    /// starts the reminders of an idle lobby, as the server does for every new game
+    pub fn schedule_lobby_reminders(&mut self) -> &mut Self {
+        self.game
+            .schedule_lobby_reminders(schedule(&self.scheduler), tunnel_finder(&self.network));
+        self
+    }
+
+    pub fn host_next(&mut self) -> &mut Self {
+        self.send(self.host, IncomingMessage::Host(IncomingHostMessage::Next))
+    }
+
+    pub fn host_lock(&mut self, locked: bool) -> &mut Self {
+        self.send(
+            self.host,
+            IncomingMessage::Host(IncomingHostMessage::Lock(locked)),
+        )
+    }
+
+    /// picks a name for the nth player when names are not random
+    pub fn name(&mut self, index: usize, name: &str) -> &mut Self {
+        self.send(
+            self.player(index),
+            IncomingMessage::Unassigned(IncomingUnassignedMessage::NameRequest(name.to_owned())),
+        )
+    }
+
+    /// the nth player answers the current slide
+    pub fn answer(&mut self, index: usize, answer: IncomingPlayerMessage) -> &mut Self {
+        self.send(self.player(index), IncomingMessage::Player(answer))
+    }
+
+    /// every player gives the same answer, in the order they joined
+    pub fn everyone_answers(&mut self, answer: IncomingPlayerMessage) -> &mut Self {
+        for index in 0..self.players.len() {
+            self.answer(index, answer.clone());
+        }
+        self
+    }
+
+    /// moves time forward, firing every alarm falling due in order
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        let target = self.scheduler.borrow().elapsed + duration;
+
+        loop {
+            let due = self.scheduler.borrow_mut().pop_due(target);
+            let Some((at, alarm)) = due else {
+                break;
+            };
+
+            self.move_clock_to(at);
+            self.game.receive_alarm(
+                alarm,
+                schedule(&self.scheduler),
+                tunnel_finder(&self.network),
+            );
+        }
+
+        self.move_clock_to(target);
+        self
+    }
+
+    /// fires every pending alarm, including the ones they schedule
+    pub fn run_alarms(&mut self) -> &mut Self {
+        loop {
+            let (latest, elapsed) = {
+                let scheduler = self.scheduler.borrow();
+                let latest = scheduler.pending.iter().map(|(due, _, _)| *due).max();
+                (latest, scheduler.elapsed)
+            };
+            let Some(at) = latest else {
+                break;
+            };
+            self.advance(at.saturating_sub(elapsed));
+        }
+        self
+    }
+
+    fn move_clock_to(&mut self, at: Duration) {
+        let mut scheduler = self.scheduler.borrow_mut();
+        if at > scheduler.elapsed {
+            self.clock.advance(at - scheduler.elapsed);
+            scheduler.elapsed = at;
+        }
+    }
+
+    /// drops the watcher's tunnel without telling the game
+    pub fn disconnect(&mut self, watcher_id: Id) -> &mut Self {
+        self.network.borrow_mut().connected.remove(&watcher_id);
+        self
+    }
+
+    /// reconnects a watcher and lets the game resend its state
+    pub fn reconnect(&mut self, watcher_id: Id) -> &mut Self {
+        self.network.borrow_mut().connected.insert(watcher_id);
+        self.game
+            .update_session(watcher_id, tunnel_finder(&self.network));
+        self
+    }
+
+    /// every message received by the watcher so far
+    pub fn messages(&self, watcher_id: Id) -> Vec<serde_json::Value> {
+        self.network
+            .borrow()
+            .received
+            .get(&watcher_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn last_message(&self, watcher_id: Id) -> Option<serde_json::Value> {
+        self.network
+            .borrow()
+            .received
+            .get(&watcher_id)
+            .and_then(|messages| messages.last().cloned())
+    }
+
+    /// forgets received messages so later assertions only see new ones
+    pub fn clear_messages(&mut self) -> &mut Self {
+        self.network.borrow_mut().received.clear();
+        self
+    }
+
+    /// reason the watcher's tunnel was closed by the game, if it was
+    pub fn close_reason(&self, watcher_id: Id) -> Option<CloseReason> {
+        self.network.borrow().closed.get(&watcher_id).copied()
+    }
+
+    /// time passed on the virtual clock since the simulation started
+    pub fn elapsed(&self) -> Duration {
+        self.clock
+            .now()
+            .duration_since(web_time::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
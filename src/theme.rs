@@ -0,0 +1,65 @@
+//! Look of a game picked by the host, passed through to hosts and players alike so every screen
+//! of the session is styled the same.
+
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+
+const MAX_BACKGROUND_LENGTH: usize = crate::CONFIG
+    .fuiz
+    .theme
+    .max_background_length
+    .unsigned_abs() as usize;
+
+/// color written as `#rrggbb`
+fn validate_color(color: &Option<String>) -> garde::Result {
+    match color {
+        Some(color)
+            if !(color.len() == 7
+                && color.starts_with('#')
+                && color[1..].chars().all(|c| c.is_ascii_hexdigit())) =>
+        {
+            Err(garde::Error::new("color is not written as #rrggbb"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// identifier of a background known to the frontend, made of letters, digits, `-` and `_`
+fn validate_background(background: &Option<String>) -> garde::Result {
+    match background {
+        Some(background)
+            if !background
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            Err(garde::Error::new("background is not a valid identifier"))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Font {
+    #[default]
+    Default,
+    Serif,
+    Monospace,
+    Rounded,
+    Handwritten,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct Theme {
+    #[garde(custom(|v, _| validate_color(v)))]
+    #[serde(default)]
+    primary_color: Option<String>,
+    #[garde(custom(|v, _| validate_color(v)))]
+    #[serde(default)]
+    background_color: Option<String>,
+    #[garde(length(min = 1, max = MAX_BACKGROUND_LENGTH), custom(|v, _| validate_background(v)))]
+    #[serde(default)]
+    background: Option<String>,
+    #[garde(skip)]
+    #[serde(default)]
+    font: Font,
+}
@@ -0,0 +1,85 @@
+//! What is left of a removed game for a short while, so watchers reconnecting right as it ended
+//! still get their final summary instead of an unknown game.
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, SystemTime};
+
+use crate::{
+    fuiz::config::Fuiz,
+    game::{self, FinalStanding, GameClosedReason},
+    watcher::Id,
+    SyncMessage, UpdateMessage,
+};
+
+/// how long a removed game is remembered
+pub const GRACE_PERIOD: Duration =
+    Duration::from_secs(crate::CONFIG.fuiz.tombstone.grace_period.unsigned_abs());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    removed_at: SystemTime,
+    reason: Option<GameClosedReason>,
+    /// final summary of each host, already serialized
+    summaries: HashMap<Id, String>,
+    /// fuiz as players see it, kept once for every player summary
+    #[serde(default)]
+    config: Option<Arc<Fuiz>>,
+    /// where each player finished, their summary is built again when they reconnect
+    #[serde(default)]
+    standings: HashMap<Id, FinalStanding>,
+}
+
+impl Tombstone {
+    pub fn new(removed_at: SystemTime, reason: Option<GameClosedReason>) -> Self {
+        Self {
+            removed_at,
+            reason,
+            summaries: HashMap::new(),
+            config: None,
+            standings: HashMap::new(),
+        }
+    }
+
+    pub fn add_host(&mut self, host: Id, summary: String) {
+        self.summaries.insert(host, summary);
+    }
+
+    pub fn set_players(&mut self, config: Arc<Fuiz>, standings: HashMap<Id, FinalStanding>) {
+        self.config = Some(config);
+        self.standings = standings;
+    }
+
+    pub fn reason(&self) -> Option<GameClosedReason> {
+        self.reason
+    }
+
+    /// whether the grace period is over and the tombstone can be dropped
+    pub fn expired(&self, now: SystemTime) -> bool {
+        now.duration_since(self.removed_at)
+            .is_ok_and(|elapsed| elapsed >= GRACE_PERIOD)
+    }
+
+    /// final summary of the watcher, if they were a host or player of the finished game
+    fn summary(&self, watcher_id: Id) -> Option<String> {
+        if let Some(summary) = self.summaries.get(&watcher_id) {
+            return Some(summary.clone());
+        }
+
+        let standing = self.standings.get(&watcher_id)?.clone();
+        let config = self.config.clone()?;
+        SyncMessage::from(game::SyncMessage::Summary(standing.summary(config))).to_message()
+    }
+
+    /// messages to send to a watcher reconnecting to the removed game, their summary followed
+    /// by why the game was closed
+    pub fn replay(&self, watcher_id: Id) -> Vec<String> {
+        let reason = self.reason.unwrap_or(GameClosedReason::Finished);
+
+        self.summary(watcher_id)
+            .into_iter()
+            .chain(UpdateMessage::from(game::UpdateMessage::GameClosed { reason }).to_message())
+            .collect()
+    }
+}
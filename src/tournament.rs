@@ -0,0 +1,137 @@
+//! Tournaments chaining several games into rounds.
+//!
+//! The engine doesn't own games, whoever does keeps a [`Tournament`] next to them: each round is a
+//! set of [`GameId`]s, finished games are recorded into cumulative standings, and the best players
+//! are carried over into the games of the next round under the same watcher ids and names.
+
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    game::{self, Game},
+    game_id::GameId,
+    watcher::Id,
+};
+
+#[derive(Error, Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("game is not part of the tournament")]
+    UnknownGame,
+    #[error("game was already recorded")]
+    AlreadyRecorded,
+    #[error("game is already part of the tournament")]
+    DuplicateGame,
+    #[error("round has no games")]
+    EmptyRound,
+}
+
+/// Cumulative result of a player over the tournament
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub id: Id,
+    pub name: String,
+    pub points: u64,
+    /// number of recorded games the player took part in
+    pub games: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    rounds: Vec<Vec<GameId>>,
+    recorded: HashSet<GameId>,
+    standings: HashMap<Id, Standing>,
+}
+
+impl Tournament {
+    pub fn rounds(&self) -> &[Vec<GameId>] {
+        &self.rounds
+    }
+
+    pub fn current_round(&self) -> Option<&[GameId]> {
+        self.rounds.last().map(Vec::as_slice)
+    }
+
+    pub fn contains(&self, game_id: GameId) -> bool {
+        self.rounds.iter().flatten().contains(&game_id)
+    }
+
+    /// starts a new round made of the given games
+    pub fn add_round(&mut self, games: Vec<GameId>) -> Result<(), Error> {
+        if games.is_empty() {
+            return Err(Error::EmptyRound);
+        }
+        if !games.iter().all_unique() || games.iter().any(|game_id| self.contains(*game_id)) {
+            return Err(Error::DuplicateGame);
+        }
+        self.rounds.push(games);
+        Ok(())
+    }
+
+    /// whether every game of the current round was recorded
+    pub fn round_finished(&self) -> bool {
+        self.current_round()
+            .is_some_and(|games| games.iter().all(|game_id| self.recorded.contains(game_id)))
+    }
+
+    /// adds the final scores of a finished game to the standings
+    pub fn record(&mut self, game_id: GameId, game: &Game) -> Result<(), Error> {
+        if !self.contains(game_id) {
+            return Err(Error::UnknownGame);
+        }
+        if !self.recorded.insert(game_id) {
+            return Err(Error::AlreadyRecorded);
+        }
+
+        for (id, name, points) in game.final_scores() {
+            let standing = self.standings.entry(id).or_insert_with(|| Standing {
+                id,
+                name: name.clone(),
+                points: 0,
+                games: 0,
+            });
+            standing.name = name;
+            standing.points += points;
+            standing.games += 1;
+        }
+
+        Ok(())
+    }
+
+    /// standings from first to last, ties broken by name
+    pub fn standings(&self) -> Vec<Standing> {
+        self.standings
+            .values()
+            .sorted_by(|a, b| b.points.cmp(&a.points).then_with(|| a.name.cmp(&b.name)))
+            .cloned()
+            .collect()
+    }
+
+    /// best players of the tournament so far
+    pub fn qualifiers(&self, count: usize) -> Vec<Standing> {
+        self.standings().into_iter().take(count).collect()
+    }
+
+    /// spreads the qualifiers over the games of the next round, alternating so that every game
+    /// gets a similar share of the strongest players
+    pub fn seed(qualifiers: &[Standing], games: &mut [&mut Game]) -> Result<(), game::Error> {
+        if games.is_empty() {
+            return Ok(());
+        }
+
+        for (rank, standing) in qualifiers.iter().enumerate() {
+            let lap = rank / games.len();
+            let position = rank % games.len();
+            let index = if lap.is_multiple_of(2) {
+                position
+            } else {
+                games.len() - 1 - position
+            };
+            games[index].seed_player(standing.id, &standing.name)?;
+        }
+
+        Ok(())
+    }
+}
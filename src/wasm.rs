@@ -0,0 +1,199 @@
+//! JavaScript facade running a whole game in the browser for offline and solo play.
+//!
+//! Messages the game sends are queued and handed to JavaScript through
+//! [`LocalGame::take_messages`], alarms are queued the same way and have to be fired back
+//! through [`LocalGame::fire_alarm`] once their delay elapsed.
+
+use std::{cell::RefCell, collections::HashSet, rc::Rc, sync::Arc};
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use web_time::Duration;
+
+use crate::{
+    fuiz::config::Fuiz,
+    game::{Game, IncomingMessage, Options},
+    session::{CloseReason, Tunnel},
+    watcher::Id,
+    AlarmMessage,
+};
+
+#[derive(Debug, Serialize)]
+struct QueuedMessage {
+    to: Id,
+    message: Arc<str>,
+}
+
+#[derive(Debug, Serialize)]
+struct QueuedAlarm {
+    alarm: AlarmMessage,
+    delay_ms: u128,
+}
+
+#[derive(Debug, Default)]
+struct Queues {
+    connected: HashSet<Id>,
+    messages: Vec<QueuedMessage>,
+    alarms: Vec<QueuedAlarm>,
+}
+
+/// Tunnel queueing everything it receives for JavaScript to pick up
+#[derive(Debug, Clone)]
+struct LocalTunnel {
+    queues: Rc<RefCell<Queues>>,
+    watcher_id: Id,
+}
+
+impl Tunnel for LocalTunnel {
+    fn send_raw(&self, message: Arc<str>) {
+        self.queues.borrow_mut().messages.push(QueuedMessage {
+            to: self.watcher_id,
+            message,
+        });
+    }
+
+    fn close(self, reason: CloseReason) {
+        let mut queues = self.queues.borrow_mut();
+        if let Some(message) = reason.to_message() {
+            queues.messages.push(QueuedMessage {
+                to: self.watcher_id,
+                message: message.into(),
+            });
+        }
+        queues.connected.remove(&self.watcher_id);
+    }
+}
+
+/// Game running entirely inside the page
+#[wasm_bindgen]
+pub struct LocalGame {
+    game: Game,
+    host_id: Id,
+    queues: Rc<RefCell<Queues>>,
+}
+
+/// finds the tunnels of connected watchers
+fn tunnel_finder(queues: &Rc<RefCell<Queues>>) -> impl Fn(Id) -> Option<LocalTunnel> + '_ {
+    |watcher_id| {
+        queues
+            .borrow()
+            .connected
+            .contains(&watcher_id)
+            .then(|| LocalTunnel {
+                queues: queues.clone(),
+                watcher_id,
+            })
+    }
+}
+
+/// queues alarms for JavaScript to fire back after their delay
+fn schedule(queues: &Rc<RefCell<Queues>>) -> impl FnMut(AlarmMessage, Duration) + '_ {
+    |alarm, delay| {
+        queues.borrow_mut().alarms.push(QueuedAlarm {
+            alarm,
+            delay_ms: delay.as_millis(),
+        });
+    }
+}
+
+fn js_error<E: std::fmt::Display>(e: E) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+#[wasm_bindgen]
+impl LocalGame {
+    /// creates a game from the JSON of a fuiz and its options
+    #[wasm_bindgen(constructor)]
+    pub fn new(fuiz: &str, options: &str) -> Result<LocalGame, JsError> {
+        let fuiz: Fuiz = serde_json::from_str(fuiz).map_err(js_error)?;
+        let options: Options = serde_json::from_str(options).map_err(js_error)?;
+        garde::Validate::validate(&fuiz).map_err(js_error)?;
+        garde::Validate::validate(&options).map_err(js_error)?;
+
+        let host_id = Id::new();
+        let queues = Rc::new(RefCell::new(Queues::default()));
+        queues.borrow_mut().connected.insert(host_id);
+
+        Ok(Self {
+            game: Game::new(fuiz, options, host_id),
+            host_id,
+            queues,
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn host_id(&self) -> String {
+        self.host_id.to_string()
+    }
+
+    /// adds a new watcher and returns its id
+    pub fn join(&mut self) -> Result<String, JsError> {
+        let watcher_id = Id::new();
+        self.queues.borrow_mut().connected.insert(watcher_id);
+
+        if let Err(e) = self
+            .game
+            .add_unassigned(watcher_id, tunnel_finder(&self.queues))
+        {
+            self.queues.borrow_mut().connected.remove(&watcher_id);
+            return Err(js_error(e));
+        }
+
+        Ok(watcher_id.to_string())
+    }
+
+    /// disconnects a watcher, it can come back through [`LocalGame::reconnect`]
+    pub fn leave(&mut self, watcher_id: &str) -> Result<(), JsError> {
+        let watcher_id: Id = watcher_id.parse().map_err(js_error)?;
+        self.queues.borrow_mut().connected.remove(&watcher_id);
+        Ok(())
+    }
+
+    /// reconnects a watcher that left earlier and resends its state
+    pub fn reconnect(&mut self, watcher_id: &str) -> Result<(), JsError> {
+        let watcher_id: Id = watcher_id.parse().map_err(js_error)?;
+        self.queues.borrow_mut().connected.insert(watcher_id);
+
+        self.game
+            .update_session(watcher_id, tunnel_finder(&self.queues));
+
+        Ok(())
+    }
+
+    /// handles the JSON of an incoming message sent by the watcher
+    pub fn send(&mut self, watcher_id: &str, message: &str) -> Result<(), JsError> {
+        let watcher_id: Id = watcher_id.parse().map_err(js_error)?;
+        let message: IncomingMessage = serde_json::from_str(message).map_err(js_error)?;
+
+        self.game.receive_message(
+            watcher_id,
+            message,
+            schedule(&self.queues),
+            tunnel_finder(&self.queues),
+        );
+
+        Ok(())
+    }
+
+    /// fires the JSON of an alarm previously returned by [`LocalGame::take_alarms`]
+    pub fn fire_alarm(&mut self, alarm: &str) -> Result<(), JsError> {
+        let alarm: AlarmMessage = serde_json::from_str(alarm).map_err(js_error)?;
+
+        self.game
+            .receive_alarm(alarm, schedule(&self.queues), tunnel_finder(&self.queues));
+
+        Ok(())
+    }
+
+    /// JSON array of `{ to, message }` sent since the last call
+    pub fn take_messages(&self) -> String {
+        let messages = std::mem::take(&mut self.queues.borrow_mut().messages);
+        serde_json::to_string(&messages).expect("default serializer cannot fail")
+    }
+
+    /// JSON array of `{ alarm, delay_ms }` scheduled since the last call
+    pub fn take_alarms(&self) -> String {
+        let alarms = std::mem::take(&mut self.queues.borrow_mut().alarms);
+        serde_json::to_string(&alarms).expect("default serializer cannot fail")
+    }
+}
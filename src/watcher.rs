@@ -2,6 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
     str::FromStr,
+    sync::Arc,
 };
 
 use enum_map::{Enum, EnumMap};
@@ -12,7 +13,10 @@ use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{session::Tunnel, SyncMessage, UpdateMessage};
+use super::{
+    session::{deliver, deliver_control, deliver_serialized, CloseReason, Tunnel},
+    ErrorCode, SyncMessage, UpdateMessage,
+};
 
 #[derive(
     Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay,
@@ -87,12 +91,19 @@ impl PlayerValue {
 #[derive(Deserialize)]
 struct WatchersSerde {
     mapping: HashMap<Id, Value>,
+    #[serde(default)]
+    limits: Limits,
+    #[serde(default)]
+    bots: HashSet<Id>,
 }
 
 #[derive(Default, Serialize, Deserialize)]
 #[serde(from = "WatchersSerde")]
 pub struct Watchers {
     mapping: HashMap<Id, Value>,
+    limits: Limits,
+    /// players driven by the server, they have no tunnel yet count as present
+    bots: HashSet<Id>,
 
     #[serde(skip_serializing)]
     reverse_mapping: EnumMap<ValueKind, HashSet<Id>>,
@@ -100,29 +111,76 @@ pub struct Watchers {
 
 impl From<WatchersSerde> for Watchers {
     fn from(serde: WatchersSerde) -> Self {
-        let WatchersSerde { mapping } = serde;
+        let WatchersSerde {
+            mapping,
+            limits,
+            bots,
+        } = serde;
         let mut reverse_mapping: EnumMap<ValueKind, HashSet<Id>> = EnumMap::default();
         for (id, value) in mapping.iter() {
             reverse_mapping[value.kind()].insert(*id);
         }
         Self {
             mapping,
+            limits,
+            bots,
             reverse_mapping,
         }
     }
 }
 
-const MAX_PLAYERS: usize = crate::CONFIG.fuiz.max_player_count.unsigned_abs() as usize;
+pub const MAX_PLAYERS: usize = crate::CONFIG.fuiz.max_player_count.unsigned_abs() as usize;
+pub const MAX_UNASSIGNED: usize = crate::CONFIG.fuiz.max_unassigned_count.unsigned_abs() as usize;
+
+/// Capacity of a game, counted separately for each kind of watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limits {
+    pub players: usize,
+    pub unassigned: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            players: MAX_PLAYERS,
+            unassigned: MAX_UNASSIGNED,
+        }
+    }
+}
+
+impl Limits {
+    /// maximum number of watchers of the given kind, hosts are not limited
+    pub fn of(&self, kind: ValueKind) -> Option<usize> {
+        match kind {
+            ValueKind::Host => None,
+            ValueKind::Player => Some(self.players),
+            ValueKind::Unassigned => Some(self.unassigned),
+        }
+    }
+}
 
 #[derive(Error, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
     #[error("maximum number of players reached")]
     MaximumPlayers,
+    #[error("maximum number of unassigned watchers reached")]
+    MaximumUnassigned,
+}
+
+impl Error {
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Self::MaximumPlayers => ErrorCode::new("watcher.maximum_players"),
+            Self::MaximumUnassigned => ErrorCode::new("watcher.maximum_unassigned"),
+        }
+    }
 }
 
 impl Watchers {
-    pub fn with_host_id(host_id: Id) -> Self {
+    pub fn with_host_id(host_id: Id, limits: Limits) -> Self {
         Self {
+            limits,
+            bots: HashSet::new(),
             mapping: {
                 let mut map = HashMap::default();
                 map.insert(host_id, Value::Host);
@@ -161,15 +219,112 @@ impl Watchers {
             .collect_vec()
     }
 
+    /// visits every watcher with a live tunnel without collecting them first
+    pub fn for_each_watcher<T: Tunnel, F: Fn(Id) -> Option<T>, V: FnMut(Id, T, &Value)>(
+        &self,
+        tunnel_finder: F,
+        mut visitor: V,
+    ) {
+        for id in self.reverse_mapping.values().flat_map(|v| v.iter()) {
+            if let (Some(t), Some(v)) = (tunnel_finder(*id), self.mapping.get(id)) {
+                visitor(*id, t, v);
+            }
+        }
+    }
+
+    /// visits every watcher of the given kind with a live tunnel without collecting them first
+    pub fn for_each_specific<T: Tunnel, F: Fn(Id) -> Option<T>, V: FnMut(Id, T, &Value)>(
+        &self,
+        filter: ValueKind,
+        tunnel_finder: F,
+        mut visitor: V,
+    ) {
+        for id in &self.reverse_mapping[filter] {
+            if let (Some(t), Some(v)) = (tunnel_finder(*id), self.mapping.get(id)) {
+                visitor(*id, t, v);
+            }
+        }
+    }
+
+    /// whether any watcher of the given kind currently has a live tunnel
+    pub fn any_alive<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        filter: ValueKind,
+        tunnel_finder: F,
+    ) -> bool {
+        self.reverse_mapping[filter]
+            .iter()
+            .any(|id| tunnel_finder(*id).is_some())
+    }
+
+    /// adds a player driven by the server
+    pub fn add_bot(&mut self, bot_id: Id, name: String) -> Result<(), Error> {
+        self.add_watcher(bot_id, Value::Player(PlayerValue::Individual { name }))?;
+        self.bots.insert(bot_id);
+        Ok(())
+    }
+
+    pub fn bots(&self) -> impl Iterator<Item = Id> + '_ {
+        self.bots.iter().copied()
+    }
+
+    /// players expected to answer: those with a live tunnel and every bot
+    pub fn present_players<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        tunnel_finder: F,
+    ) -> HashSet<Id> {
+        self.reverse_mapping[ValueKind::Player]
+            .iter()
+            .filter(|id| self.bots.contains(id) || tunnel_finder(**id).is_some())
+            .copied()
+            .collect()
+    }
+
+    /// every watcher of the game, including disconnected ones, so the tunnels they map to can
+    /// be dropped along with the game
+    pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.mapping.keys().copied()
+    }
+
+    /// every watcher of the kind, including disconnected ones
+    pub fn ids_of(&self, kind: ValueKind) -> impl Iterator<Item = Id> + '_ {
+        self.reverse_mapping[kind].iter().copied()
+    }
+
+    /// every player along with their value, including disconnected ones
+    pub fn players(&self) -> impl Iterator<Item = (Id, &PlayerValue)> {
+        self.reverse_mapping[ValueKind::Player]
+            .iter()
+            .filter_map(|id| match self.mapping.get(id) {
+                Some(Value::Player(player)) => Some((*id, player)),
+                _ => None,
+            })
+    }
+
+    /// number of watchers of every kind, including disconnected ones
+    pub fn count(&self) -> usize {
+        self.mapping.len()
+    }
+
     pub fn specific_count(&self, filter: ValueKind) -> usize {
         self.reverse_mapping[filter].len()
     }
 
+    /// whether no more watchers of the given kind can be added
+    pub fn is_full(&self, kind: ValueKind) -> bool {
+        self.limits
+            .of(kind)
+            .is_some_and(|limit| self.specific_count(kind) >= limit)
+    }
+
     pub fn add_watcher(&mut self, watcher_id: Id, watcher_value: Value) -> Result<(), Error> {
         let kind = watcher_value.kind();
 
-        if self.mapping.len() >= MAX_PLAYERS {
-            return Err(Error::MaximumPlayers);
+        if self.is_full(kind) {
+            return Err(match kind {
+                ValueKind::Unassigned => Error::MaximumUnassigned,
+                _ => Error::MaximumPlayers,
+            });
         }
 
         self.mapping.insert(watcher_id, watcher_value);
@@ -178,6 +333,34 @@ impl Watchers {
         Ok(())
     }
 
+    /// forgets unassigned watchers whose tunnels are gone, returns how many were removed
+    pub fn prune_unassigned<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &mut self,
+        tunnel_finder: F,
+    ) -> usize {
+        let dead = self.reverse_mapping[ValueKind::Unassigned]
+            .iter()
+            .copied()
+            .filter(|id| tunnel_finder(*id).is_none())
+            .collect_vec();
+
+        for id in &dead {
+            self.reverse_mapping[ValueKind::Unassigned].remove(id);
+            self.mapping.remove(id);
+        }
+
+        dead.len()
+    }
+
+    /// forgets a watcher altogether, freeing its spot
+    pub fn remove_watcher(&mut self, watcher_id: Id) {
+        if let Some(value) = self.mapping.remove(&watcher_id) {
+            self.reverse_mapping[value.kind()].remove(&watcher_id);
+        }
+    }
+
+    /// changes the value of an existing watcher, capacity is not checked here so callers
+    /// promoting a watcher into another kind should consult [`Watchers::is_full`] first
     pub fn update_watcher_value(&mut self, watcher_id: Id, watcher_value: Value) {
         let old_kind = match self.mapping.get(&watcher_id) {
             Some(v) => v.kind(),
@@ -210,10 +393,11 @@ impl Watchers {
     pub fn remove_watcher_session<T: Tunnel, F: Fn(Id) -> Option<T>>(
         &mut self,
         watcher_id: &Id,
+        reason: CloseReason,
         tunnel_finder: F,
     ) {
         if let Some(x) = tunnel_finder(*watcher_id) {
-            x.close();
+            x.close(reason);
         }
     }
 
@@ -227,7 +411,7 @@ impl Watchers {
             return;
         };
 
-        session.send_message(message);
+        deliver_serialized(session, message.to_message());
     }
 
     pub fn send_state<T: Tunnel, F: Fn(Id) -> Option<T>>(
@@ -240,7 +424,22 @@ impl Watchers {
             return;
         };
 
-        session.send_state(message);
+        deliver_serialized(session, message.to_message());
+    }
+
+    /// sends an acknowledgment of a command, only to the connections controlling the watcher
+    pub fn send_control<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        message: &UpdateMessage,
+        watcher_id: Id,
+        tunnel_finder: F,
+    ) {
+        let (Some(session), Some(message)) = (tunnel_finder(watcher_id), message.to_message())
+        else {
+            return;
+        };
+
+        deliver_control(session, message.into());
     }
 
     pub fn get_name(&self, watcher_id: Id) -> Option<String> {
@@ -254,13 +453,11 @@ impl Watchers {
     where
         S: Fn(Id, ValueKind) -> Option<super::UpdateMessage>,
     {
-        for (watcher, session, v) in self.vec(tunnel_finder) {
-            let Some(message) = sender(watcher, v.kind()) else {
-                continue;
-            };
-
-            session.send_message(&message);
-        }
+        self.for_each_watcher(tunnel_finder, |watcher, session, v| {
+            if let Some(message) = sender(watcher, v.kind()) {
+                deliver_serialized(session, message.to_message());
+            }
+        });
     }
 
     pub fn announce<T: Tunnel, F: Fn(Id) -> Option<T>>(
@@ -268,7 +465,33 @@ impl Watchers {
         message: &super::UpdateMessage,
         tunnel_finder: F,
     ) {
-        self.announce_with(|_, _| Some(message.to_owned()), tunnel_finder);
+        let Some(message) = message.to_message() else {
+            return;
+        };
+        let message: Arc<str> = message.into();
+
+        self.for_each_watcher(tunnel_finder, |_, session, _| {
+            deliver(session, message.clone());
+        });
+    }
+
+    /// sends the same message to the given watchers only, such as the members of a team
+    pub fn announce_among<T: Tunnel, F: Fn(Id) -> Option<T>>(
+        &self,
+        watcher_ids: &[Id],
+        message: &super::UpdateMessage,
+        tunnel_finder: F,
+    ) {
+        let Some(message) = message.to_message() else {
+            return;
+        };
+        let message: Arc<str> = message.into();
+
+        for watcher_id in watcher_ids {
+            if let Some(session) = tunnel_finder(*watcher_id) {
+                deliver(session, message.clone());
+            }
+        }
     }
 
     pub fn announce_specific<T: Tunnel, F: Fn(Id) -> Option<T>>(
@@ -277,8 +500,13 @@ impl Watchers {
         message: &super::UpdateMessage,
         tunnel_finder: F,
     ) {
-        for (_, session, _) in self.specific_vec(filter, tunnel_finder) {
-            session.send_message(message);
-        }
+        let Some(message) = message.to_message() else {
+            return;
+        };
+        let message: Arc<str> = message.into();
+
+        self.for_each_specific(filter, tunnel_finder, |_, session, _| {
+            deliver(session, message.clone());
+        });
     }
 }
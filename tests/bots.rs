@@ -0,0 +1,97 @@
+//! Bots of practice games playing on their own.
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{Options, State},
+    testing::Simulation,
+};
+use web_time::Duration;
+
+fn fuiz() -> Fuiz {
+    serde_json::from_value(serde_json::json!({
+        "title": "Practice",
+        "slides": [
+            {
+                "MultipleChoice": {
+                    "title": "Pick the first",
+                    "introduce_question": 0,
+                    "time_limit": 30000,
+                    "points_awarded": 1000,
+                    "answers": [
+                        { "correct": true, "content": { "Text": "first" } },
+                        { "correct": false, "content": { "Text": "second" } },
+                    ],
+                }
+            },
+        ],
+    }))
+    .expect("static quiz is valid")
+}
+
+fn options() -> Options {
+    seeded_options(None)
+}
+
+fn seeded_options(seed: Option<u64>) -> Options {
+    serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": false,
+        "no_leaderboard": false,
+        "teams": null,
+        "bots": {
+            "count": 3,
+            "accuracy": 50,
+            "min_latency": 1000,
+            "max_latency": 2000,
+        },
+        "seed": seed,
+    }))
+    .expect("static options are valid")
+}
+
+#[test]
+fn bot_answers_leave_the_game_idle() {
+    let mut sim = Simulation::new(fuiz(), options());
+    sim.host_next();
+    assert!(matches!(sim.state(), State::Slide(_)));
+
+    sim.advance(Duration::from_secs(5));
+    let State::Slide(current_slide) = sim.state() else {
+        panic!("the slide is still shown");
+    };
+    let slide = serde_json::to_value(&current_slide.state).expect("slides serialize");
+    let answered = slide["MultipleChoice"]["user_answers"]
+        .as_object()
+        .map(serde_json::Map::len);
+    assert_eq!(answered, Some(3), "every bot answered");
+    assert!(
+        sim.game().idle_for() >= Duration::from_secs(5),
+        "idle for {:?}",
+        sim.game().idle_for()
+    );
+}
+
+/// every bot answer with when it landed, sorted
+fn bot_answers(seed: u64) -> Vec<String> {
+    let mut sim = Simulation::new(fuiz(), seeded_options(Some(seed)));
+    sim.host_next().advance(Duration::from_secs(5));
+    let State::Slide(current_slide) = sim.state() else {
+        panic!("the slide is still shown");
+    };
+    let slide = serde_json::to_value(&current_slide.state).expect("slides serialize");
+    let mut answers = slide["MultipleChoice"]["user_answers"]
+        .as_object()
+        .expect("answers were given")
+        .values()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    answers.sort();
+    answers
+}
+
+#[test]
+fn seeded_bots_answer_the_same() {
+    for seed in 0..8 {
+        assert_eq!(bot_answers(seed), bot_answers(seed), "seed {seed}");
+    }
+}
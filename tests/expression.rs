@@ -0,0 +1,65 @@
+//! Exact evaluation of the arithmetic answers accepted by type answer slides.
+
+use fuiz::fuiz::expression::evaluate;
+
+#[test]
+fn equal_values_written_differently_match() {
+    let half = evaluate("1/2").expect("1/2 is an expression");
+    assert_eq!(evaluate("0.5"), Some(half));
+    assert_eq!(evaluate("2/4"), Some(half));
+    assert_eq!(evaluate(" ( 3 - 2 ) / 2 "), Some(half));
+    assert_ne!(evaluate("1/3"), Some(half));
+}
+
+#[test]
+fn decimals_may_omit_either_side_of_the_point() {
+    assert_eq!(evaluate(".5"), evaluate("1/2"));
+    assert_eq!(evaluate("5."), evaluate("5"));
+    assert_eq!(evaluate("-.25"), evaluate("-1/4"));
+    assert_eq!(evaluate("."), None);
+    assert_eq!(evaluate("1.2.3"), None);
+}
+
+#[test]
+fn unary_signs_apply_to_the_factor() {
+    assert_eq!(evaluate("--2"), evaluate("2"));
+    assert_eq!(evaluate("-2*-3"), evaluate("6"));
+    assert_eq!(evaluate("+-1"), evaluate("-1"));
+}
+
+#[test]
+fn division_by_zero_has_no_value() {
+    assert_eq!(evaluate("1/0"), None);
+    assert_eq!(evaluate("1/(2-2)"), None);
+    assert_eq!(evaluate("0/0"), None);
+    assert!(evaluate("0/5").is_some());
+}
+
+#[test]
+fn overflow_has_no_value() {
+    let large = "9".repeat(39);
+    assert_eq!(evaluate(&large), None);
+    let max = i128::MAX.to_string();
+    assert!(evaluate(&max).is_some());
+    assert_eq!(evaluate(&format!("{max}+1")), None);
+    assert_eq!(evaluate(&format!("{max}*{max}")), None);
+    assert_eq!(evaluate(&format!("1/{max}/{max}")), None);
+}
+
+#[test]
+fn nesting_is_bounded() {
+    let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(evaluate(&nested(10)), evaluate("1"));
+    assert_eq!(evaluate(&nested(1000)), None);
+    assert_eq!(evaluate(&format!("{}1", "-".repeat(1000))), None);
+}
+
+#[test]
+fn trailing_input_is_rejected() {
+    assert_eq!(evaluate("1 2"), None);
+    assert_eq!(evaluate("1)"), None);
+    assert_eq!(evaluate("(1"), None);
+    assert_eq!(evaluate("1x"), None);
+    assert_eq!(evaluate("1+"), None);
+    assert_eq!(evaluate(""), None);
+}
@@ -0,0 +1,118 @@
+//! Handicaps of players joining a team game once slides were scored.
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{IncomingPlayerMessage, Options, State},
+    testing::Simulation,
+    watcher::Id,
+};
+
+const POINTS_AWARDED: u64 = 1000;
+
+fn fuiz() -> Fuiz {
+    let slide = serde_json::json!({
+        "MultipleChoice": {
+            "title": "Pick the first",
+            "introduce_question": 0,
+            "time_limit": 10000,
+            "points_awarded": POINTS_AWARDED,
+            "answers": [
+                { "correct": true, "content": { "Text": "first" } },
+                { "correct": false, "content": { "Text": "second" } },
+            ],
+        }
+    });
+    serde_json::from_value(serde_json::json!({
+        "title": "Late joiners",
+        "slides": [slide.clone(), slide],
+    }))
+    .expect("static quiz is valid")
+}
+
+fn options(team_size: usize, handicap: &str) -> Options {
+    serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": false,
+        "no_leaderboard": false,
+        "teams": { "size": team_size, "assign_random": true },
+        "late_join_handicap": handicap,
+    }))
+    .expect("static options are valid")
+}
+
+/// two players form a team and score the first slide before the game moves on
+fn scored_first_slide(team_size: usize, handicap: &str) -> Simulation {
+    let mut sim = Simulation::new(fuiz(), options(team_size, handicap));
+    sim.join(2).host_next().host_next();
+    assert!(matches!(sim.state(), State::Slide(_)));
+
+    sim.everyone_answers(IncomingPlayerMessage::IndexAnswer(0))
+        .run_alarms()
+        .host_next();
+    assert!(matches!(sim.state(), State::Leaderboard(0)));
+    sim
+}
+
+fn points(sim: &Simulation, player: Id) -> Option<u64> {
+    let game = sim.game();
+    game.leaderboard
+        .score(game.leaderboard_id(player))
+        .map(|score| score.points)
+}
+
+#[test]
+fn joining_a_scored_team_keeps_its_score() {
+    for handicap in ["Scale", "SeedMedian"] {
+        let mut sim = scored_first_slide(3, handicap);
+        let team_points = points(&sim, sim.player(0));
+        assert!(team_points.is_some_and(|points| points > 0));
+
+        sim.join(1);
+        let latecomer = sim.player(2);
+        assert_eq!(
+            sim.game().leaderboard_id(latecomer),
+            sim.game().leaderboard_id(sim.player(0)),
+            "{handicap}: the latecomer joins the open team"
+        );
+        assert_eq!(points(&sim, latecomer), team_points, "{handicap}");
+        assert!(
+            sim.game().leaderboard.score(latecomer).is_none(),
+            "{handicap}: no score of its own next to the team's"
+        );
+
+        sim.host_next()
+            .answer(2, IncomingPlayerMessage::IndexAnswer(0))
+            .run_alarms()
+            .host_next();
+        let gained = points(&sim, latecomer).unwrap_or_default() - team_points.unwrap_or_default();
+        assert!(
+            gained <= POINTS_AWARDED,
+            "{handicap}: the team's points aren't boosted, gained {gained}"
+        );
+    }
+}
+
+#[test]
+fn a_new_team_of_latecomers_gets_the_handicap() {
+    let mut sim = scored_first_slide(2, "SeedMedian");
+    let team_points = points(&sim, sim.player(0));
+    sim.join(1);
+    let latecomer = sim.player(2);
+    assert_ne!(
+        sim.game().leaderboard_id(latecomer),
+        sim.game().leaderboard_id(sim.player(0))
+    );
+    assert_eq!(points(&sim, latecomer), team_points);
+    assert!(sim.game().leaderboard.score(latecomer).is_none());
+
+    let mut sim = scored_first_slide(2, "Scale");
+    sim.join(1);
+    sim.host_next()
+        .answer(2, IncomingPlayerMessage::IndexAnswer(0))
+        .run_alarms()
+        .host_next();
+    assert!(
+        points(&sim, sim.player(2)).unwrap_or_default() > POINTS_AWARDED,
+        "missing half the slides doubles the points earned"
+    );
+}
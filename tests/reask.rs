@@ -0,0 +1,57 @@
+//! Slides the host asks again.
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{IncomingHostMessage, IncomingMessage, IncomingPlayerMessage, Options, State},
+    testing::Simulation,
+};
+
+fn fuiz() -> Fuiz {
+    serde_json::from_value(serde_json::json!({
+        "title": "Again",
+        "slides": [
+            {
+                "MultipleChoice": {
+                    "title": "Pick the first",
+                    "introduce_question": 0,
+                    "time_limit": 10000,
+                    "points_awarded": 1000,
+                    "answers": [
+                        { "correct": true, "content": { "Text": "first" } },
+                        { "correct": false, "content": { "Text": "second" } },
+                    ],
+                }
+            },
+        ],
+    }))
+    .expect("static quiz is valid")
+}
+
+fn options() -> Options {
+    serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": false,
+        "no_leaderboard": false,
+        "teams": null,
+    }))
+    .expect("static options are valid")
+}
+
+#[test]
+fn asked_again_slide_is_recorded_once() {
+    let mut sim = Simulation::new(fuiz(), options());
+    sim.join(2).host_next();
+    sim.answer(0, IncomingPlayerMessage::IndexAnswer(0));
+    sim.send(
+        sim.host(),
+        IncomingMessage::Host(IncomingHostMessage::Reask),
+    );
+    assert!(matches!(sim.state(), State::Slide(_)));
+
+    sim.everyone_answers(IncomingPlayerMessage::IndexAnswer(0))
+        .run_alarms();
+
+    let stats = sim.game().stats();
+    assert_eq!(stats.slide_kinds.get("MultipleChoice"), Some(&1));
+    assert_eq!(stats.slide_kinds.len(), 1);
+}
@@ -0,0 +1,240 @@
+//! Property tests throwing random interleavings of messages and alarms at every slide type.
+
+use fuiz::{
+    fuiz::config::Fuiz,
+    game::{IncomingHostMessage, IncomingMessage, IncomingPlayerMessage, Options, State},
+    testing::Simulation,
+};
+use proptest::prelude::*;
+use web_time::Duration;
+
+const POINTS_AWARDED: u64 = 1000;
+
+fn fuiz() -> Fuiz {
+    serde_json::from_value(serde_json::json!({
+        "title": "Properties",
+        "slides": [
+            {
+                "MultipleChoice": {
+                    "title": "Pick the second",
+                    "introduce_question": 2000,
+                    "time_limit": 10000,
+                    "points_awarded": POINTS_AWARDED,
+                    "answers": [
+                        { "correct": false, "content": { "Text": "first" } },
+                        { "correct": true, "content": { "Text": "second" } },
+                        { "correct": false, "content": { "Text": "third" } },
+                    ],
+                }
+            },
+            {
+                "TypeAnswer": {
+                    "title": "Type yes",
+                    "introduce_question": 0,
+                    "time_limit": 10000,
+                    "points_awarded": POINTS_AWARDED,
+                    "answers": ["yes"],
+                }
+            },
+            {
+                "Order": {
+                    "title": "Count up",
+                    "introduce_question": 2000,
+                    "time_limit": 10000,
+                    "points_awarded": POINTS_AWARDED,
+                    "answers": ["one", "two", "three"],
+                    "axis_labels": {},
+                }
+            },
+            {
+                "Order": {
+                    "title": "Nothing to order",
+                    "introduce_question": 0,
+                    "time_limit": 10000,
+                    "points_awarded": POINTS_AWARDED,
+                    "answers": ["same", "same"],
+                    "axis_labels": {},
+                }
+            },
+        ],
+    }))
+    .expect("static quiz is valid")
+}
+
+fn options(no_leaderboard: bool, bots: bool) -> Options {
+    let bots = bots.then(|| {
+        serde_json::json!({
+            "count": 3,
+            "accuracy": 30,
+            "min_latency": 0,
+            "max_latency": 5000,
+        })
+    });
+    serde_json::from_value(serde_json::json!({
+        "random_names": true,
+        "show_answers": false,
+        "no_leaderboard": no_leaderboard,
+        "teams": null,
+        "bots": bots,
+    }))
+    .expect("static options are valid")
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    Join,
+    Answer(usize, IncomingPlayerMessage),
+    HostNext,
+    HostIndex(usize),
+    Advance(u64),
+    Disconnect(usize),
+    Reconnect(usize),
+}
+
+fn answer() -> impl Strategy<Value = IncomingPlayerMessage> {
+    prop_oneof![
+        (0usize..4).prop_map(IncomingPlayerMessage::IndexAnswer),
+        prop_oneof![Just("yes"), Just("no"), Just("")]
+            .prop_map(|s| IncomingPlayerMessage::StringAnswer(s.to_owned())),
+        Just(IncomingPlayerMessage::StringArrayAnswer(vec![
+            "one".to_owned(),
+            "two".to_owned(),
+            "three".to_owned(),
+        ])),
+        Just(IncomingPlayerMessage::StringArrayAnswer(vec![
+            "three".to_owned(),
+            "one".to_owned(),
+        ])),
+    ]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        2 => Just(Action::Join),
+        6 => (0usize..8, answer()).prop_map(|(player, answer)| Action::Answer(player, answer)),
+        3 => Just(Action::HostNext),
+        1 => (0usize..4).prop_map(Action::HostIndex),
+        3 => (0u64..15_000).prop_map(Action::Advance),
+        1 => (0usize..8).prop_map(Action::Disconnect),
+        1 => (0usize..8).prop_map(Action::Reconnect),
+    ]
+}
+
+/// position of the game in its run, must never decrease
+fn progress(sim: &Simulation) -> (usize, u8) {
+    match sim.state() {
+        State::WaitingScreen | State::TeamDisplay => (0, 0),
+        State::Slide(current_slide) => {
+            let slide = serde_json::to_value(&current_slide.state).expect("slides serialize");
+            let phase = slide
+                .as_object()
+                .and_then(|variant| variant.values().next())
+                .and_then(|state| state.get("state"))
+                .and_then(|phase| phase.as_str())
+                .expect("slide state has a phase");
+            let phase = match phase {
+                "Unstarted" => 1,
+                "Question" => 2,
+                "Answers" => 3,
+                "AnswersResults" => 4,
+                other => panic!("unknown slide phase {other}"),
+            };
+            (current_slide.index, phase)
+        }
+        State::Stake(index) => (*index, 0),
+        State::Leaderboard(index) => (*index, 5),
+        State::Done => (usize::MAX, 0),
+    }
+}
+
+fn run(actions: Vec<Action>, no_leaderboard: bool, bots: bool) -> Result<(), TestCaseError> {
+    let mut sim = Simulation::new(fuiz(), options(no_leaderboard, bots));
+    let mut previous = progress(&sim);
+    let mut connected = Vec::new();
+    let mut scored = 0;
+
+    for action in actions {
+        match action {
+            Action::Join => {
+                if sim.players().len() < 8 {
+                    sim.join(1);
+                    connected.push(true);
+                }
+            }
+            Action::Answer(player, answer) => {
+                if player < sim.players().len() {
+                    sim.answer(player, answer);
+                }
+            }
+            Action::HostNext => {
+                sim.host_next();
+            }
+            Action::HostIndex(index) => {
+                sim.send(
+                    sim.host(),
+                    IncomingMessage::Host(IncomingHostMessage::Index(index)),
+                );
+            }
+            Action::Advance(millis) => {
+                sim.advance(Duration::from_millis(millis));
+            }
+            Action::Disconnect(player) => {
+                if player < sim.players().len() {
+                    sim.disconnect(sim.player(player));
+                    connected[player] = false;
+                }
+            }
+            Action::Reconnect(player) => {
+                if player < sim.players().len() {
+                    sim.reconnect(sim.player(player));
+                    connected[player] = true;
+                }
+            }
+        }
+
+        let current = progress(&sim);
+        prop_assert!(
+            current >= previous,
+            "state went backwards from {previous:?} to {current:?}"
+        );
+
+        // scores are added once the host moves on from the results of a slide
+        let just_scored = previous.1 == 4 && current != previous;
+        if just_scored {
+            scored += 1;
+        }
+        previous = current;
+
+        for (index, player) in sim.players().iter().enumerate() {
+            let score = sim.game().leaderboard.score(*player);
+
+            if let Some(score) = score {
+                prop_assert!(
+                    score.points <= POINTS_AWARDED * scored,
+                    "player has {} points after {scored} scored slides",
+                    score.points
+                );
+            }
+
+            if just_scored && connected[index] {
+                prop_assert!(
+                    score.is_some(),
+                    "connected player is missing from the leaderboard"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn slides_only_move_forward(
+        actions in proptest::collection::vec(action(), 0..120),
+        no_leaderboard in any::<bool>(),
+        bots in any::<bool>(),
+    ) {
+        run(actions, no_leaderboard, bots)?;
+    }
+}